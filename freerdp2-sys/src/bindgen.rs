@@ -770,6 +770,10 @@ pub const CB_HUGE_FILE_SUPPORT_ENABLED: u32 = 32;
 pub const RDP_KEYBOARD_LAYOUT_TYPE_STANDARD: u32 = 1;
 pub const RDP_KEYBOARD_LAYOUT_TYPE_VARIANT: u32 = 2;
 pub const RDP_KEYBOARD_LAYOUT_TYPE_IME: u32 = 4;
+pub const INFO_TYPE_LOGON: u32 = 0;
+pub const INFO_TYPE_LOGON_LONG: u32 = 1;
+pub const INFO_TYPE_LOGON_PLAIN_NOTIFY: u32 = 2;
+pub const INFO_TYPE_LOGON_EXTENDED_INF: u32 = 3;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct rdp_rdp {
@@ -797,11 +801,6 @@ pub type rdpCodecs = rdp_codecs;
 pub type freerdp = rdp_freerdp;
 #[doc = " Defines the context for a given instance of RDP connection.\n It is embedded in the rdp_freerdp structure, and allocated by a call to\n freerdp_context_new(). It is deallocated by a call to freerdp_context_free()."]
 pub type rdpContext = rdp_context;
-#[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub struct rdp_freerdp_peer {
-    _unused: [u8; 0],
-}
 pub type freerdp_peer = rdp_freerdp_peer;
 pub type rdpClientContext = rdp_client_context;
 pub type RDP_CLIENT_ENTRY_POINTS_V1 = rdp_client_entry_points_v1;
@@ -11739,6 +11738,63 @@ fn bindgen_test_layout__RECTANGLE_16() {
 pub type RECTANGLE_16 = _RECTANGLE_16;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+pub struct _REGION16_DATA {
+    _unused: [u8; 0],
+}
+pub type REGION16_DATA = _REGION16_DATA;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _REGION16 {
+    pub extents: RECTANGLE_16,
+    pub data: *mut REGION16_DATA,
+}
+#[test]
+fn bindgen_test_layout__REGION16() {
+    const UNINIT: ::std::mem::MaybeUninit<_REGION16> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_REGION16>(),
+        16usize,
+        concat!("Size of: ", stringify!(_REGION16))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_REGION16>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_REGION16))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).extents) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_REGION16),
+            "::",
+            stringify!(extents)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).data) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_REGION16),
+            "::",
+            stringify!(data)
+        )
+    );
+}
+pub type REGION16 = _REGION16;
+extern "C" {
+    pub fn region16_init(region: *mut REGION16);
+}
+extern "C" {
+    pub fn region16_rects(region: *const REGION16, nbRects: *mut UINT32) -> *const RECTANGLE_16;
+}
+extern "C" {
+    pub fn region16_uninit(region: *mut REGION16);
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
 pub struct _IO_marker {
     _unused: [u8; 0],
 }
@@ -15232,12 +15288,65 @@ fn bindgen_test_layout__RFX_CONTEXT() {
         )
     );
 }
+extern "C" {
+    pub fn rfx_context_new(encoder: BOOL) -> *mut RFX_CONTEXT;
+}
+extern "C" {
+    pub fn rfx_context_free(context: *mut RFX_CONTEXT);
+}
+extern "C" {
+    pub fn rfx_context_reset(context: *mut RFX_CONTEXT, width: UINT32, height: UINT32) -> BOOL;
+}
+extern "C" {
+    pub fn rfx_context_set_pixel_format(context: *mut RFX_CONTEXT, pixel_format: UINT32);
+}
+extern "C" {
+    pub fn rfx_process_message(
+        context: *mut RFX_CONTEXT,
+        data: *const BYTE,
+        length: UINT32,
+        left: UINT32,
+        top: UINT32,
+        dst: *mut BYTE,
+        dstFormat: UINT32,
+        dstStride: UINT32,
+        dstHeight: UINT32,
+        invalidRegion: *mut REGION16,
+    ) -> BOOL;
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _NSC_CONTEXT {
     _unused: [u8; 0],
 }
 pub type NSC_CONTEXT = _NSC_CONTEXT;
+extern "C" {
+    pub fn nsc_context_new() -> *mut NSC_CONTEXT;
+}
+extern "C" {
+    pub fn nsc_context_free(context: *mut NSC_CONTEXT);
+}
+extern "C" {
+    pub fn nsc_context_reset(context: *mut NSC_CONTEXT, width: UINT32, height: UINT32) -> BOOL;
+}
+extern "C" {
+    pub fn nsc_process_message(
+        context: *mut NSC_CONTEXT,
+        bpp: UINT16,
+        width: UINT32,
+        height: UINT32,
+        data: *const BYTE,
+        length: UINT32,
+        pDstData: *mut BYTE,
+        DstFormat: UINT32,
+        nDstStride: UINT32,
+        nXDst: UINT32,
+        nYDst: UINT32,
+        nWidth: UINT32,
+        nHeight: UINT32,
+        flip: UINT32,
+    ) -> BOOL;
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _FREERDP_ADDIN {
@@ -15673,6 +15782,240 @@ fn bindgen_test_layout__RDPGFX_SURFACE_COMMAND() {
 pub type RDPGFX_SURFACE_COMMAND = _RDPGFX_SURFACE_COMMAND;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
+pub struct _RDPGFX_H264_QUANT_QUALITY {
+    pub qpVal: BYTE,
+    pub qualityVal: BYTE,
+    pub qp: BYTE,
+    pub r: BYTE,
+    pub p: BYTE,
+}
+#[test]
+fn bindgen_test_layout__RDPGFX_H264_QUANT_QUALITY() {
+    const UNINIT: ::std::mem::MaybeUninit<_RDPGFX_H264_QUANT_QUALITY> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_RDPGFX_H264_QUANT_QUALITY>(),
+        5usize,
+        concat!("Size of: ", stringify!(_RDPGFX_H264_QUANT_QUALITY))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_RDPGFX_H264_QUANT_QUALITY>(),
+        1usize,
+        concat!("Alignment of ", stringify!(_RDPGFX_H264_QUANT_QUALITY))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).qpVal) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_H264_QUANT_QUALITY),
+            "::",
+            stringify!(qpVal)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).qualityVal) as usize - ptr as usize },
+        1usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_H264_QUANT_QUALITY),
+            "::",
+            stringify!(qualityVal)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).qp) as usize - ptr as usize },
+        2usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_H264_QUANT_QUALITY),
+            "::",
+            stringify!(qp)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).r) as usize - ptr as usize },
+        3usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_H264_QUANT_QUALITY),
+            "::",
+            stringify!(r)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).p) as usize - ptr as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_H264_QUANT_QUALITY),
+            "::",
+            stringify!(p)
+        )
+    );
+}
+pub type RDPGFX_H264_QUANT_QUALITY = _RDPGFX_H264_QUANT_QUALITY;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _RDPGFX_H264_METABLOCK {
+    pub numRegionRects: UINT32,
+    pub regionRects: *mut RECTANGLE_16,
+    pub quantQualityVals: *mut RDPGFX_H264_QUANT_QUALITY,
+}
+#[test]
+fn bindgen_test_layout__RDPGFX_H264_METABLOCK() {
+    const UNINIT: ::std::mem::MaybeUninit<_RDPGFX_H264_METABLOCK> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_RDPGFX_H264_METABLOCK>(),
+        24usize,
+        concat!("Size of: ", stringify!(_RDPGFX_H264_METABLOCK))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_RDPGFX_H264_METABLOCK>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_RDPGFX_H264_METABLOCK))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).numRegionRects) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_H264_METABLOCK),
+            "::",
+            stringify!(numRegionRects)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).regionRects) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_H264_METABLOCK),
+            "::",
+            stringify!(regionRects)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).quantQualityVals) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_H264_METABLOCK),
+            "::",
+            stringify!(quantQualityVals)
+        )
+    );
+}
+pub type RDPGFX_H264_METABLOCK = _RDPGFX_H264_METABLOCK;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _RDPGFX_AVC420_BITMAP_STREAM {
+    pub meta: RDPGFX_H264_METABLOCK,
+    pub length: UINT32,
+    pub data: *mut BYTE,
+}
+#[test]
+fn bindgen_test_layout__RDPGFX_AVC420_BITMAP_STREAM() {
+    const UNINIT: ::std::mem::MaybeUninit<_RDPGFX_AVC420_BITMAP_STREAM> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_RDPGFX_AVC420_BITMAP_STREAM>(),
+        40usize,
+        concat!("Size of: ", stringify!(_RDPGFX_AVC420_BITMAP_STREAM))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_RDPGFX_AVC420_BITMAP_STREAM>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_RDPGFX_AVC420_BITMAP_STREAM))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).meta) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_AVC420_BITMAP_STREAM),
+            "::",
+            stringify!(meta)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).length) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_AVC420_BITMAP_STREAM),
+            "::",
+            stringify!(length)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).data) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_AVC420_BITMAP_STREAM),
+            "::",
+            stringify!(data)
+        )
+    );
+}
+pub type RDPGFX_AVC420_BITMAP_STREAM = _RDPGFX_AVC420_BITMAP_STREAM;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _RDPGFX_AVC444_BITMAP_STREAM {
+    pub cbAvc420EncodedBitstream1: UINT32,
+    pub LC: BYTE,
+    pub bitstream: [RDPGFX_AVC420_BITMAP_STREAM; 2usize],
+}
+#[test]
+fn bindgen_test_layout__RDPGFX_AVC444_BITMAP_STREAM() {
+    const UNINIT: ::std::mem::MaybeUninit<_RDPGFX_AVC444_BITMAP_STREAM> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_RDPGFX_AVC444_BITMAP_STREAM>(),
+        88usize,
+        concat!("Size of: ", stringify!(_RDPGFX_AVC444_BITMAP_STREAM))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_RDPGFX_AVC444_BITMAP_STREAM>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_RDPGFX_AVC444_BITMAP_STREAM))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).cbAvc420EncodedBitstream1) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_AVC444_BITMAP_STREAM),
+            "::",
+            stringify!(cbAvc420EncodedBitstream1)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).LC) as usize - ptr as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_AVC444_BITMAP_STREAM),
+            "::",
+            stringify!(LC)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).bitstream) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_RDPGFX_AVC444_BITMAP_STREAM),
+            "::",
+            stringify!(bitstream)
+        )
+    );
+}
+pub type RDPGFX_AVC444_BITMAP_STREAM = _RDPGFX_AVC444_BITMAP_STREAM;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
 pub struct _RDPGFX_DELETE_ENCODING_CONTEXT_PDU {
     pub surfaceId: UINT16,
     pub codecContextId: UINT32,
@@ -17229,6 +17572,32 @@ pub struct _CLEAR_CONTEXT {
     _unused: [u8; 0],
 }
 pub type CLEAR_CONTEXT = _CLEAR_CONTEXT;
+extern "C" {
+    pub fn clear_decompress(
+        clear: *mut CLEAR_CONTEXT,
+        pSrcData: *const BYTE,
+        SrcSize: UINT32,
+        nWidth: UINT32,
+        nHeight: UINT32,
+        pDstData: *mut BYTE,
+        DstFormat: UINT32,
+        nDstStep: UINT32,
+        nXDst: UINT32,
+        nYDst: UINT32,
+        nDstWidth: UINT32,
+        nDstHeight: UINT32,
+        palette: *const gdiPalette,
+    ) -> INT32;
+}
+extern "C" {
+    pub fn clear_context_reset(clear: *mut CLEAR_CONTEXT) -> BOOL;
+}
+extern "C" {
+    pub fn clear_context_new(Compressor: BOOL) -> *mut CLEAR_CONTEXT;
+}
+extern "C" {
+    pub fn clear_context_free(clear: *mut CLEAR_CONTEXT);
+}
 pub type BITMAP_PLANAR_CONTEXT = _BITMAP_PLANAR_CONTEXT;
 extern "C" {
     pub fn freerdp_bitmap_compress(
@@ -17482,6 +17851,23 @@ extern "C" {
 extern "C" {
     pub fn freerdp_planar_switch_bgr(planar: *mut BITMAP_PLANAR_CONTEXT, bgr: BOOL);
 }
+extern "C" {
+    pub fn planar_decompress(
+        planar: *mut BITMAP_PLANAR_CONTEXT,
+        pSrcData: *const BYTE,
+        SrcSize: UINT32,
+        nSrcWidth: UINT32,
+        nSrcHeight: UINT32,
+        pDstData: *mut BYTE,
+        DstFormat: UINT32,
+        nDstStep: UINT32,
+        nXDst: UINT32,
+        nYDst: UINT32,
+        nDstWidth: UINT32,
+        nDstHeight: UINT32,
+        vFlip: BOOL,
+    ) -> BOOL;
+}
 pub type BITMAP_INTERLEAVED_CONTEXT = _BITMAP_INTERLEAVED_CONTEXT;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
@@ -17547,12 +17933,93 @@ fn bindgen_test_layout__BITMAP_INTERLEAVED_CONTEXT() {
         )
     );
 }
+extern "C" {
+    pub fn interleaved_compress(
+        interleaved: *mut BITMAP_INTERLEAVED_CONTEXT,
+        pDstData: *mut BYTE,
+        pDstSize: *mut UINT32,
+        nWidth: UINT32,
+        nHeight: UINT32,
+        pSrcData: *const BYTE,
+        SrcFormat: UINT32,
+        nSrcStep: UINT32,
+        nXSrc: UINT32,
+        nYSrc: UINT32,
+        palette: *const gdiPalette,
+        bpp: UINT32,
+    ) -> BOOL;
+}
+extern "C" {
+    pub fn interleaved_decompress(
+        interleaved: *mut BITMAP_INTERLEAVED_CONTEXT,
+        pSrcData: *const BYTE,
+        SrcSize: UINT32,
+        nSrcWidth: UINT32,
+        nSrcHeight: UINT32,
+        bpp: UINT32,
+        pDstData: *mut BYTE,
+        DstFormat: UINT32,
+        nDstStep: UINT32,
+        nXDst: UINT32,
+        nYDst: UINT32,
+        nDstWidth: UINT32,
+        nDstHeight: UINT32,
+        palette: *const gdiPalette,
+    ) -> BOOL;
+}
+extern "C" {
+    pub fn bitmap_interleaved_context_reset(interleaved: *mut BITMAP_INTERLEAVED_CONTEXT) -> BOOL;
+}
+extern "C" {
+    pub fn bitmap_interleaved_context_new(Compressor: BOOL) -> *mut BITMAP_INTERLEAVED_CONTEXT;
+}
+extern "C" {
+    pub fn bitmap_interleaved_context_free(interleaved: *mut BITMAP_INTERLEAVED_CONTEXT);
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct _PROGRESSIVE_CONTEXT {
     _unused: [u8; 0],
 }
 pub type PROGRESSIVE_CONTEXT = _PROGRESSIVE_CONTEXT;
+extern "C" {
+    pub fn progressive_create_surface_context(
+        progressive: *mut PROGRESSIVE_CONTEXT,
+        surfaceId: UINT16,
+        width: UINT32,
+        height: UINT32,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn progressive_delete_surface_context(
+        progressive: *mut PROGRESSIVE_CONTEXT,
+        surfaceId: UINT16,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn progressive_decompress(
+        progressive: *mut PROGRESSIVE_CONTEXT,
+        pSrcData: *const BYTE,
+        SrcSize: UINT32,
+        pDstData: *mut BYTE,
+        DstFormat: UINT32,
+        nDstStep: UINT32,
+        nXDst: UINT32,
+        nYDst: UINT32,
+        invalidRegion: *mut REGION16,
+        surfaceId: UINT16,
+        frameId: UINT32,
+    ) -> INT32;
+}
+extern "C" {
+    pub fn progressive_context_reset(progressive: *mut PROGRESSIVE_CONTEXT) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn progressive_context_new(Compressor: BOOL) -> *mut PROGRESSIVE_CONTEXT;
+}
+extern "C" {
+    pub fn progressive_context_free(progressive: *mut PROGRESSIVE_CONTEXT);
+}
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct rdp_codecs {
@@ -31249,6 +31716,25 @@ extern "C" {
         unicode: BOOL,
     ) -> ::std::os::raw::c_int;
 }
+extern "C" {
+    pub fn freerdp_assistance_bin_to_hex_string(
+        data: *const BYTE,
+        size: usize,
+    ) -> *mut ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn freerdp_assistance_construct_expert_blob(
+        name: *const ::std::os::raw::c_char,
+        pass: *const ::std::os::raw::c_char,
+    ) -> *mut ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn freerdp_assistance_encrypt_pass_stub(
+        password: *const ::std::os::raw::c_char,
+        passStub: *const ::std::os::raw::c_char,
+        pEncryptedSize: *mut usize,
+    ) -> *mut BYTE;
+}
 extern "C" {
     pub fn freerdp_client_settings_parse_assistance_file(
         settings: *mut rdpSettings,
@@ -39428,3 +39914,1674 @@ fn bindgen_test_layout___va_list_tag() {
         )
     );
 }
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rdp_logon_info {
+    pub sessionId: UINT32,
+    pub username: *mut ::std::os::raw::c_char,
+    pub domain: *mut ::std::os::raw::c_char,
+}
+#[test]
+fn bindgen_test_layout_rdp_logon_info() {
+    const UNINIT: ::std::mem::MaybeUninit<rdp_logon_info> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<rdp_logon_info>(),
+        24usize,
+        concat!("Size of: ", stringify!(rdp_logon_info))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<rdp_logon_info>(),
+        8usize,
+        concat!("Alignment of ", stringify!(rdp_logon_info))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).sessionId) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info),
+            "::",
+            stringify!(sessionId)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).username) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info),
+            "::",
+            stringify!(username)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).domain) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info),
+            "::",
+            stringify!(domain)
+        )
+    );
+}
+pub type logon_info = rdp_logon_info;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rdp_logon_info_ex {
+    pub haveCookie: BOOL,
+    pub LogonId: UINT32,
+    pub ArcRandomBits: [BYTE; 16usize],
+    pub haveErrors: BOOL,
+    pub ErrorNotificationType: UINT32,
+    pub ErrorNotificationData: UINT32,
+}
+#[test]
+fn bindgen_test_layout_rdp_logon_info_ex() {
+    const UNINIT: ::std::mem::MaybeUninit<rdp_logon_info_ex> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<rdp_logon_info_ex>(),
+        36usize,
+        concat!("Size of: ", stringify!(rdp_logon_info_ex))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<rdp_logon_info_ex>(),
+        4usize,
+        concat!("Alignment of ", stringify!(rdp_logon_info_ex))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).haveCookie) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info_ex),
+            "::",
+            stringify!(haveCookie)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).LogonId) as usize - ptr as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info_ex),
+            "::",
+            stringify!(LogonId)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ArcRandomBits) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info_ex),
+            "::",
+            stringify!(ArcRandomBits)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).haveErrors) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info_ex),
+            "::",
+            stringify!(haveErrors)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ErrorNotificationType) as usize - ptr as usize },
+        28usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info_ex),
+            "::",
+            stringify!(ErrorNotificationType)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ErrorNotificationData) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_logon_info_ex),
+            "::",
+            stringify!(ErrorNotificationData)
+        )
+    );
+}
+pub type logon_info_ex = rdp_logon_info_ex;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _SEC_WINNT_AUTH_IDENTITY {
+    pub User: *mut UINT16,
+    pub UserLength: UINT32,
+    pub Domain: *mut UINT16,
+    pub DomainLength: UINT32,
+    pub Password: *mut UINT16,
+    pub PasswordLength: UINT32,
+    pub Flags: UINT32,
+}
+#[test]
+fn bindgen_test_layout__SEC_WINNT_AUTH_IDENTITY() {
+    const UNINIT: ::std::mem::MaybeUninit<_SEC_WINNT_AUTH_IDENTITY> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_SEC_WINNT_AUTH_IDENTITY>(),
+        48usize,
+        concat!("Size of: ", stringify!(_SEC_WINNT_AUTH_IDENTITY))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_SEC_WINNT_AUTH_IDENTITY>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_SEC_WINNT_AUTH_IDENTITY))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).User) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SEC_WINNT_AUTH_IDENTITY),
+            "::",
+            stringify!(User)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).UserLength) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SEC_WINNT_AUTH_IDENTITY),
+            "::",
+            stringify!(UserLength)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Domain) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SEC_WINNT_AUTH_IDENTITY),
+            "::",
+            stringify!(Domain)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).DomainLength) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SEC_WINNT_AUTH_IDENTITY),
+            "::",
+            stringify!(DomainLength)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Password) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SEC_WINNT_AUTH_IDENTITY),
+            "::",
+            stringify!(Password)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).PasswordLength) as usize - ptr as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SEC_WINNT_AUTH_IDENTITY),
+            "::",
+            stringify!(PasswordLength)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Flags) as usize - ptr as usize },
+        44usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SEC_WINNT_AUTH_IDENTITY),
+            "::",
+            stringify!(Flags)
+        )
+    );
+}
+pub type SEC_WINNT_AUTH_IDENTITY = _SEC_WINNT_AUTH_IDENTITY;
+pub const SEC_WINNT_AUTH_IDENTITY_ANSI: u32 = 1;
+pub const SEC_WINNT_AUTH_IDENTITY_UNICODE: u32 = 2;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _SecBuffer {
+    pub cbBuffer: ULONG,
+    pub BufferType: ULONG,
+    pub pvBuffer: *mut ::std::os::raw::c_void,
+}
+#[test]
+fn bindgen_test_layout__SecBuffer() {
+    const UNINIT: ::std::mem::MaybeUninit<_SecBuffer> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_SecBuffer>(),
+        16usize,
+        concat!("Size of: ", stringify!(_SecBuffer))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_SecBuffer>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_SecBuffer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).cbBuffer) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SecBuffer),
+            "::",
+            stringify!(cbBuffer)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).BufferType) as usize - ptr as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SecBuffer),
+            "::",
+            stringify!(BufferType)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).pvBuffer) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_SecBuffer),
+            "::",
+            stringify!(pvBuffer)
+        )
+    );
+}
+pub type SecBuffer = _SecBuffer;
+pub const LicenseCallbackResult_LICENSE_CB_INTERNAL_ERROR: LicenseCallbackResult = 0;
+pub const LicenseCallbackResult_LICENSE_CB_ABORT: LicenseCallbackResult = 1;
+pub const LicenseCallbackResult_LICENSE_CB_IN_PROGRESS: LicenseCallbackResult = 2;
+pub const LicenseCallbackResult_LICENSE_CB_COMPLETED: LicenseCallbackResult = 3;
+pub type LicenseCallbackResult = ::std::os::raw::c_uint;
+pub type psPeerContextNew = ::std::option::Option<
+    unsafe extern "C" fn(client: *mut freerdp_peer, context: *mut rdpContext) -> BOOL,
+>;
+pub type psPeerContextFree = ::std::option::Option<
+    unsafe extern "C" fn(client: *mut freerdp_peer, context: *mut rdpContext),
+>;
+pub type psPeerInitialize =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerGetFileDescriptor = ::std::option::Option<
+    unsafe extern "C" fn(
+        peer: *mut freerdp_peer,
+        rfds: *mut *mut ::std::os::raw::c_void,
+        rcount: *mut ::std::os::raw::c_int,
+    ) -> BOOL,
+>;
+pub type psPeerGetEventHandle =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> HANDLE>;
+pub type psPeerGetEventHandles = ::std::option::Option<
+    unsafe extern "C" fn(peer: *mut freerdp_peer, events: *mut HANDLE, count: DWORD) -> DWORD,
+>;
+pub type psPeerGetReceiveEventHandle =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> HANDLE>;
+pub type psPeerCheckFileDescriptor =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerIsWriteBlocked =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerDrainOutputBuffer =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> ::std::os::raw::c_int>;
+pub type psPeerHasMoreToRead =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerClose = ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerDisconnect = ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer)>;
+pub type psPeerCapabilities =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerPostConnect =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerActivate =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerLogon = ::std::option::Option<
+    unsafe extern "C" fn(
+        peer: *mut freerdp_peer,
+        identity: *mut SEC_WINNT_AUTH_IDENTITY,
+        automatic: BOOL,
+    ) -> BOOL,
+>;
+pub type psPeerAdjustMonitorsLayout =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerClientCapabilities =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer) -> BOOL>;
+pub type psPeerSendChannelData = ::std::option::Option<
+    unsafe extern "C" fn(
+        peer: *mut freerdp_peer,
+        channelId: UINT16,
+        data: *const BYTE,
+        size: usize,
+    ) -> BOOL,
+>;
+pub type psPeerSendChannelPacket = ::std::option::Option<
+    unsafe extern "C" fn(
+        client: *mut freerdp_peer,
+        channelId: UINT16,
+        totalSize: usize,
+        flags: UINT32,
+        data: *const BYTE,
+        chunkSize: usize,
+    ) -> BOOL,
+>;
+pub type psPeerReceiveChannelData = ::std::option::Option<
+    unsafe extern "C" fn(
+        peer: *mut freerdp_peer,
+        channelId: UINT16,
+        data: *const BYTE,
+        size: usize,
+        flags: UINT32,
+        totalSize: usize,
+    ) -> BOOL,
+>;
+pub type psPeerVirtualChannelOpen = ::std::option::Option<
+    unsafe extern "C" fn(
+        peer: *mut freerdp_peer,
+        name: *const ::std::os::raw::c_char,
+        flags: UINT32,
+    ) -> HANDLE,
+>;
+pub type psPeerVirtualChannelClose =
+    ::std::option::Option<unsafe extern "C" fn(peer: *mut freerdp_peer, hChannel: HANDLE) -> BOOL>;
+pub type psPeerVirtualChannelRead = ::std::option::Option<
+    unsafe extern "C" fn(
+        peer: *mut freerdp_peer,
+        hChannel: HANDLE,
+        buffer: *mut BYTE,
+        length: UINT32,
+    ) -> ::std::os::raw::c_int,
+>;
+pub type psPeerVirtualChannelWrite = ::std::option::Option<
+    unsafe extern "C" fn(
+        peer: *mut freerdp_peer,
+        hChannel: HANDLE,
+        buffer: *const BYTE,
+        length: UINT32,
+    ) -> ::std::os::raw::c_int,
+>;
+pub type psPeerVirtualChannelGetData = ::std::option::Option<
+    unsafe extern "C" fn(peer: *mut freerdp_peer, hChannel: HANDLE) -> *mut ::std::os::raw::c_void,
+>;
+pub type psPeerVirtualChannelSetData = ::std::option::Option<
+    unsafe extern "C" fn(
+        peer: *mut freerdp_peer,
+        hChannel: HANDLE,
+        data: *mut ::std::os::raw::c_void,
+    ) -> ::std::os::raw::c_int,
+>;
+pub type psPeerComputeNtlmHash = ::std::option::Option<
+    unsafe extern "C" fn(
+        client: *mut freerdp_peer,
+        authIdentity: *const SEC_WINNT_AUTH_IDENTITY,
+        ntproofvalue: *const SecBuffer,
+        randkey: *const BYTE,
+        mic: *const BYTE,
+        micvalue: *const SecBuffer,
+        ntlmhash: *mut BYTE,
+    ) -> ::std::os::raw::c_int,
+>;
+pub type psPeerLicenseCallback = ::std::option::Option<
+    unsafe extern "C" fn(peer: *mut freerdp_peer, s: *mut wStream) -> LicenseCallbackResult,
+>;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rdp_freerdp_peer {
+    pub context: *mut rdpContext,
+    pub sockfd: ::std::os::raw::c_int,
+    pub hostname: [::std::os::raw::c_char; 50usize],
+    pub input: *mut rdpInput,
+    pub update: *mut rdpUpdate,
+    pub settings: *mut rdpSettings,
+    pub autodetect: *mut rdpAutoDetect,
+    pub ContextExtra: *mut ::std::os::raw::c_void,
+    pub ContextSize: usize,
+    pub ContextNew: psPeerContextNew,
+    pub ContextFree: psPeerContextFree,
+    pub Initialize: psPeerInitialize,
+    pub GetFileDescriptor: psPeerGetFileDescriptor,
+    pub GetEventHandle: psPeerGetEventHandle,
+    pub GetReceiveEventHandle: psPeerGetReceiveEventHandle,
+    pub CheckFileDescriptor: psPeerCheckFileDescriptor,
+    pub Close: psPeerClose,
+    pub Disconnect: psPeerDisconnect,
+    pub Capabilities: psPeerCapabilities,
+    pub PostConnect: psPeerPostConnect,
+    pub Activate: psPeerActivate,
+    pub Logon: psPeerLogon,
+    pub SendChannelData: psPeerSendChannelData,
+    pub ReceiveChannelData: psPeerReceiveChannelData,
+    pub VirtualChannelOpen: psPeerVirtualChannelOpen,
+    pub VirtualChannelClose: psPeerVirtualChannelClose,
+    pub VirtualChannelRead: psPeerVirtualChannelRead,
+    pub VirtualChannelWrite: psPeerVirtualChannelWrite,
+    pub VirtualChannelGetData: psPeerVirtualChannelGetData,
+    pub VirtualChannelSetData: psPeerVirtualChannelSetData,
+    pub pId: ::std::os::raw::c_int,
+    pub ack_frame_id: UINT32,
+    pub local: BOOL,
+    pub connected: BOOL,
+    pub activated: BOOL,
+    pub authenticated: BOOL,
+    pub identity: SEC_WINNT_AUTH_IDENTITY,
+    pub IsWriteBlocked: psPeerIsWriteBlocked,
+    pub DrainOutputBuffer: psPeerDrainOutputBuffer,
+    pub HasMoreToRead: psPeerHasMoreToRead,
+    pub GetEventHandles: psPeerGetEventHandles,
+    pub AdjustMonitorsLayout: psPeerAdjustMonitorsLayout,
+    pub ClientCapabilities: psPeerClientCapabilities,
+    pub ComputeNtlmHash: psPeerComputeNtlmHash,
+    pub LicenseCallback: psPeerLicenseCallback,
+    pub SendChannelPacket: psPeerSendChannelPacket,
+}
+#[test]
+fn bindgen_test_layout_rdp_freerdp_peer() {
+    const UNINIT: ::std::mem::MaybeUninit<rdp_freerdp_peer> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<rdp_freerdp_peer>(),
+        424usize,
+        concat!("Size of: ", stringify!(rdp_freerdp_peer))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<rdp_freerdp_peer>(),
+        8usize,
+        concat!("Alignment of ", stringify!(rdp_freerdp_peer))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).context) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(context)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).sockfd) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(sockfd)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).hostname) as usize - ptr as usize },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(hostname)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).input) as usize - ptr as usize },
+        64usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(input)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).update) as usize - ptr as usize },
+        72usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(update)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).settings) as usize - ptr as usize },
+        80usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(settings)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).autodetect) as usize - ptr as usize },
+        88usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(autodetect)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ContextExtra) as usize - ptr as usize },
+        96usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(ContextExtra)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ContextSize) as usize - ptr as usize },
+        104usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(ContextSize)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ContextNew) as usize - ptr as usize },
+        112usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(ContextNew)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ContextFree) as usize - ptr as usize },
+        120usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(ContextFree)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Initialize) as usize - ptr as usize },
+        128usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(Initialize)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetFileDescriptor) as usize - ptr as usize },
+        136usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(GetFileDescriptor)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetEventHandle) as usize - ptr as usize },
+        144usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(GetEventHandle)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetReceiveEventHandle) as usize - ptr as usize },
+        152usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(GetReceiveEventHandle)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).CheckFileDescriptor) as usize - ptr as usize },
+        160usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(CheckFileDescriptor)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Close) as usize - ptr as usize },
+        168usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(Close)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Disconnect) as usize - ptr as usize },
+        176usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(Disconnect)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Capabilities) as usize - ptr as usize },
+        184usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(Capabilities)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).PostConnect) as usize - ptr as usize },
+        192usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(PostConnect)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Activate) as usize - ptr as usize },
+        200usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(Activate)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Logon) as usize - ptr as usize },
+        208usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(Logon)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).SendChannelData) as usize - ptr as usize },
+        216usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(SendChannelData)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ReceiveChannelData) as usize - ptr as usize },
+        224usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(ReceiveChannelData)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).VirtualChannelOpen) as usize - ptr as usize },
+        232usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(VirtualChannelOpen)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).VirtualChannelClose) as usize - ptr as usize },
+        240usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(VirtualChannelClose)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).VirtualChannelRead) as usize - ptr as usize },
+        248usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(VirtualChannelRead)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).VirtualChannelWrite) as usize - ptr as usize },
+        256usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(VirtualChannelWrite)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).VirtualChannelGetData) as usize - ptr as usize },
+        264usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(VirtualChannelGetData)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).VirtualChannelSetData) as usize - ptr as usize },
+        272usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(VirtualChannelSetData)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).pId) as usize - ptr as usize },
+        280usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(pId)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ack_frame_id) as usize - ptr as usize },
+        284usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(ack_frame_id)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).local) as usize - ptr as usize },
+        288usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(local)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).connected) as usize - ptr as usize },
+        292usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(connected)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).activated) as usize - ptr as usize },
+        296usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(activated)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).authenticated) as usize - ptr as usize },
+        300usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(authenticated)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).identity) as usize - ptr as usize },
+        304usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(identity)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).IsWriteBlocked) as usize - ptr as usize },
+        352usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(IsWriteBlocked)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).DrainOutputBuffer) as usize - ptr as usize },
+        360usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(DrainOutputBuffer)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).HasMoreToRead) as usize - ptr as usize },
+        368usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(HasMoreToRead)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetEventHandles) as usize - ptr as usize },
+        376usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(GetEventHandles)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).AdjustMonitorsLayout) as usize - ptr as usize },
+        384usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(AdjustMonitorsLayout)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ClientCapabilities) as usize - ptr as usize },
+        392usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(ClientCapabilities)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).ComputeNtlmHash) as usize - ptr as usize },
+        400usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(ComputeNtlmHash)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).LicenseCallback) as usize - ptr as usize },
+        408usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(LicenseCallback)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).SendChannelPacket) as usize - ptr as usize },
+        416usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_peer),
+            "::",
+            stringify!(SendChannelPacket)
+        )
+    );
+}
+extern "C" {
+    pub fn freerdp_peer_context_new(client: *mut freerdp_peer) -> BOOL;
+}
+extern "C" {
+    pub fn freerdp_peer_context_free(client: *mut freerdp_peer);
+}
+extern "C" {
+    pub fn freerdp_peer_new(sockfd: ::std::os::raw::c_int) -> *mut freerdp_peer;
+}
+extern "C" {
+    pub fn freerdp_peer_free(client: *mut freerdp_peer);
+}
+pub type freerdp_listener = rdp_freerdp_listener;
+pub type psListenerOpen = ::std::option::Option<
+    unsafe extern "C" fn(
+        instance: *mut freerdp_listener,
+        bind_address: *const ::std::os::raw::c_char,
+        port: UINT16,
+    ) -> BOOL,
+>;
+pub type psListenerOpenLocal = ::std::option::Option<
+    unsafe extern "C" fn(
+        instance: *mut freerdp_listener,
+        path: *const ::std::os::raw::c_char,
+    ) -> BOOL,
+>;
+pub type psListenerOpenFromSocket = ::std::option::Option<
+    unsafe extern "C" fn(instance: *mut freerdp_listener, fd: ::std::os::raw::c_int) -> BOOL,
+>;
+pub type psListenerGetFileDescriptor = ::std::option::Option<
+    unsafe extern "C" fn(
+        instance: *mut freerdp_listener,
+        rfds: *mut *mut ::std::os::raw::c_void,
+        rcount: *mut ::std::os::raw::c_int,
+    ) -> BOOL,
+>;
+pub type psListenerGetEventHandles = ::std::option::Option<
+    unsafe extern "C" fn(
+        instance: *mut freerdp_listener,
+        events: *mut HANDLE,
+        nCount: DWORD,
+    ) -> DWORD,
+>;
+pub type psListenerCheckFileDescriptor =
+    ::std::option::Option<unsafe extern "C" fn(instance: *mut freerdp_listener) -> BOOL>;
+pub type psListenerClose =
+    ::std::option::Option<unsafe extern "C" fn(instance: *mut freerdp_listener)>;
+pub type psPeerAccepted = ::std::option::Option<
+    unsafe extern "C" fn(instance: *mut freerdp_listener, client: *mut freerdp_peer) -> BOOL,
+>;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rdp_freerdp_listener {
+    pub info: *mut ::std::os::raw::c_void,
+    pub listener: *mut ::std::os::raw::c_void,
+    pub param1: *mut ::std::os::raw::c_void,
+    pub param2: *mut ::std::os::raw::c_void,
+    pub param3: *mut ::std::os::raw::c_void,
+    pub param4: *mut ::std::os::raw::c_void,
+    pub Open: psListenerOpen,
+    pub OpenLocal: psListenerOpenLocal,
+    pub GetFileDescriptor: psListenerGetFileDescriptor,
+    pub GetEventHandles: psListenerGetEventHandles,
+    pub CheckFileDescriptor: psListenerCheckFileDescriptor,
+    pub Close: psListenerClose,
+    pub PeerAccepted: psPeerAccepted,
+    pub OpenFromSocket: psListenerOpenFromSocket,
+    pub CheckPeerAcceptRestrictions: psListenerCheckFileDescriptor,
+}
+#[test]
+fn bindgen_test_layout_rdp_freerdp_listener() {
+    const UNINIT: ::std::mem::MaybeUninit<rdp_freerdp_listener> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<rdp_freerdp_listener>(),
+        120usize,
+        concat!("Size of: ", stringify!(rdp_freerdp_listener))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<rdp_freerdp_listener>(),
+        8usize,
+        concat!("Alignment of ", stringify!(rdp_freerdp_listener))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).info) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(info)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).listener) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(listener)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).param1) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(param1)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).param2) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(param2)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).param3) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(param3)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).param4) as usize - ptr as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(param4)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Open) as usize - ptr as usize },
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(Open)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).OpenLocal) as usize - ptr as usize },
+        56usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(OpenLocal)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetFileDescriptor) as usize - ptr as usize },
+        64usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(GetFileDescriptor)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetEventHandles) as usize - ptr as usize },
+        72usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(GetEventHandles)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).CheckFileDescriptor) as usize - ptr as usize },
+        80usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(CheckFileDescriptor)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Close) as usize - ptr as usize },
+        88usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(Close)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).PeerAccepted) as usize - ptr as usize },
+        96usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(PeerAccepted)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).OpenFromSocket) as usize - ptr as usize },
+        104usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(OpenFromSocket)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).CheckPeerAcceptRestrictions) as usize - ptr as usize },
+        112usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rdp_freerdp_listener),
+            "::",
+            stringify!(CheckPeerAcceptRestrictions)
+        )
+    );
+}
+extern "C" {
+    pub fn freerdp_listener_new() -> *mut freerdp_listener;
+}
+extern "C" {
+    pub fn freerdp_listener_free(instance: *mut freerdp_listener);
+}
+pub type IWTSVirtualChannelManager = _IWTSVirtualChannelManager;
+pub type IWTSListener = _IWTSListener;
+pub type IWTSVirtualChannel = _IWTSVirtualChannel;
+pub type IWTSPlugin = _IWTSPlugin;
+pub type IWTSListenerCallback = _IWTSListenerCallback;
+pub type IWTSVirtualChannelCallback = _IWTSVirtualChannelCallback;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _IWTSListener {
+    pub GetConfiguration: ::std::option::Option<
+        unsafe extern "C" fn(
+            pListener: *mut IWTSListener,
+            ppPropertyBag: *mut *mut ::std::os::raw::c_void,
+        ) -> UINT,
+    >,
+    pub pInterface: *mut ::std::os::raw::c_void,
+}
+#[test]
+fn bindgen_test_layout__IWTSListener() {
+    const UNINIT: ::std::mem::MaybeUninit<_IWTSListener> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_IWTSListener>(),
+        16usize,
+        concat!("Size of: ", stringify!(_IWTSListener))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_IWTSListener>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_IWTSListener))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetConfiguration) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSListener),
+            "::",
+            stringify!(GetConfiguration)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).pInterface) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSListener),
+            "::",
+            stringify!(pInterface)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _IWTSVirtualChannel {
+    pub Write: ::std::option::Option<
+        unsafe extern "C" fn(
+            pChannel: *mut IWTSVirtualChannel,
+            cbSize: ULONG,
+            pBuffer: *const BYTE,
+            pReserved: *mut ::std::os::raw::c_void,
+        ) -> UINT,
+    >,
+    pub Close:
+        ::std::option::Option<unsafe extern "C" fn(pChannel: *mut IWTSVirtualChannel) -> UINT>,
+}
+#[test]
+fn bindgen_test_layout__IWTSVirtualChannel() {
+    const UNINIT: ::std::mem::MaybeUninit<_IWTSVirtualChannel> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_IWTSVirtualChannel>(),
+        16usize,
+        concat!("Size of: ", stringify!(_IWTSVirtualChannel))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_IWTSVirtualChannel>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_IWTSVirtualChannel))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Write) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannel),
+            "::",
+            stringify!(Write)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Close) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannel),
+            "::",
+            stringify!(Close)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _IWTSVirtualChannelManager {
+    pub CreateListener: ::std::option::Option<
+        unsafe extern "C" fn(
+            pChannelMgr: *mut IWTSVirtualChannelManager,
+            pszChannelName: *const ::std::os::raw::c_char,
+            ulFlags: ULONG,
+            pListenerCallback: *mut IWTSListenerCallback,
+            ppListener: *mut *mut IWTSListener,
+        ) -> UINT,
+    >,
+    pub GetChannelId:
+        ::std::option::Option<unsafe extern "C" fn(channel: *mut IWTSVirtualChannel) -> UINT32>,
+    pub FindChannelById: ::std::option::Option<
+        unsafe extern "C" fn(
+            pChannelMgr: *mut IWTSVirtualChannelManager,
+            ChannelId: UINT32,
+        ) -> *mut IWTSVirtualChannel,
+    >,
+    pub GetChannelName: ::std::option::Option<
+        unsafe extern "C" fn(channel: *mut IWTSVirtualChannel) -> *const ::std::os::raw::c_char,
+    >,
+    pub DestroyListener: ::std::option::Option<
+        unsafe extern "C" fn(
+            pChannelMgr: *mut IWTSVirtualChannelManager,
+            ppListener: *mut IWTSListener,
+        ) -> UINT,
+    >,
+}
+#[test]
+fn bindgen_test_layout__IWTSVirtualChannelManager() {
+    const UNINIT: ::std::mem::MaybeUninit<_IWTSVirtualChannelManager> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_IWTSVirtualChannelManager>(),
+        40usize,
+        concat!("Size of: ", stringify!(_IWTSVirtualChannelManager))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_IWTSVirtualChannelManager>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_IWTSVirtualChannelManager))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).CreateListener) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannelManager),
+            "::",
+            stringify!(CreateListener)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetChannelId) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannelManager),
+            "::",
+            stringify!(GetChannelId)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).FindChannelById) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannelManager),
+            "::",
+            stringify!(FindChannelById)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetChannelName) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannelManager),
+            "::",
+            stringify!(GetChannelName)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).DestroyListener) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannelManager),
+            "::",
+            stringify!(DestroyListener)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _IWTSPlugin {
+    pub Initialize: ::std::option::Option<
+        unsafe extern "C" fn(
+            pPlugin: *mut IWTSPlugin,
+            pChannelMgr: *mut IWTSVirtualChannelManager,
+        ) -> UINT,
+    >,
+    pub Connected: ::std::option::Option<unsafe extern "C" fn(pPlugin: *mut IWTSPlugin) -> UINT>,
+    pub Disconnected: ::std::option::Option<
+        unsafe extern "C" fn(pPlugin: *mut IWTSPlugin, dwDisconnectCode: DWORD) -> UINT,
+    >,
+    pub Terminated: ::std::option::Option<unsafe extern "C" fn(pPlugin: *mut IWTSPlugin) -> UINT>,
+    pub Attached: ::std::option::Option<unsafe extern "C" fn(pPlugin: *mut IWTSPlugin) -> UINT>,
+    pub Detached: ::std::option::Option<unsafe extern "C" fn(pPlugin: *mut IWTSPlugin) -> UINT>,
+    pub pInterface: *mut ::std::os::raw::c_void,
+}
+#[test]
+fn bindgen_test_layout__IWTSPlugin() {
+    const UNINIT: ::std::mem::MaybeUninit<_IWTSPlugin> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_IWTSPlugin>(),
+        56usize,
+        concat!("Size of: ", stringify!(_IWTSPlugin))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_IWTSPlugin>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_IWTSPlugin))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Initialize) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSPlugin),
+            "::",
+            stringify!(Initialize)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Connected) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSPlugin),
+            "::",
+            stringify!(Connected)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Disconnected) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSPlugin),
+            "::",
+            stringify!(Disconnected)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Terminated) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSPlugin),
+            "::",
+            stringify!(Terminated)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Attached) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSPlugin),
+            "::",
+            stringify!(Attached)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).Detached) as usize - ptr as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSPlugin),
+            "::",
+            stringify!(Detached)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).pInterface) as usize - ptr as usize },
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSPlugin),
+            "::",
+            stringify!(pInterface)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _IWTSListenerCallback {
+    pub OnNewChannelConnection: ::std::option::Option<
+        unsafe extern "C" fn(
+            pListenerCallback: *mut IWTSListenerCallback,
+            pChannel: *mut IWTSVirtualChannel,
+            Data: *mut BYTE,
+            pbAccept: *mut BOOL,
+            ppCallback: *mut *mut IWTSVirtualChannelCallback,
+        ) -> UINT,
+    >,
+}
+#[test]
+fn bindgen_test_layout__IWTSListenerCallback() {
+    const UNINIT: ::std::mem::MaybeUninit<_IWTSListenerCallback> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_IWTSListenerCallback>(),
+        8usize,
+        concat!("Size of: ", stringify!(_IWTSListenerCallback))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_IWTSListenerCallback>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_IWTSListenerCallback))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).OnNewChannelConnection) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSListenerCallback),
+            "::",
+            stringify!(OnNewChannelConnection)
+        )
+    );
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _IWTSVirtualChannelCallback {
+    pub OnDataReceived: ::std::option::Option<
+        unsafe extern "C" fn(
+            pChannelCallback: *mut IWTSVirtualChannelCallback,
+            data: *mut wStream,
+        ) -> UINT,
+    >,
+    pub OnOpen: ::std::option::Option<
+        unsafe extern "C" fn(pChannelCallback: *mut IWTSVirtualChannelCallback) -> UINT,
+    >,
+    pub OnClose: ::std::option::Option<
+        unsafe extern "C" fn(pChannelCallback: *mut IWTSVirtualChannelCallback) -> UINT,
+    >,
+}
+#[test]
+fn bindgen_test_layout__IWTSVirtualChannelCallback() {
+    const UNINIT: ::std::mem::MaybeUninit<_IWTSVirtualChannelCallback> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_IWTSVirtualChannelCallback>(),
+        24usize,
+        concat!("Size of: ", stringify!(_IWTSVirtualChannelCallback))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_IWTSVirtualChannelCallback>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_IWTSVirtualChannelCallback))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).OnDataReceived) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannelCallback),
+            "::",
+            stringify!(OnDataReceived)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).OnOpen) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannelCallback),
+            "::",
+            stringify!(OnOpen)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).OnClose) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IWTSVirtualChannelCallback),
+            "::",
+            stringify!(OnClose)
+        )
+    );
+}
+pub type IDRDYNVC_ENTRY_POINTS = _IDRDYNVC_ENTRY_POINTS;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct _IDRDYNVC_ENTRY_POINTS {
+    pub RegisterPlugin: ::std::option::Option<
+        unsafe extern "C" fn(
+            pEntryPoints: *mut IDRDYNVC_ENTRY_POINTS,
+            name: *const ::std::os::raw::c_char,
+            pPlugin: *mut IWTSPlugin,
+        ) -> UINT,
+    >,
+    pub GetPlugin: ::std::option::Option<
+        unsafe extern "C" fn(
+            pEntryPoints: *mut IDRDYNVC_ENTRY_POINTS,
+            name: *const ::std::os::raw::c_char,
+        ) -> *mut IWTSPlugin,
+    >,
+    pub GetPluginData: ::std::option::Option<
+        unsafe extern "C" fn(pEntryPoints: *mut IDRDYNVC_ENTRY_POINTS) -> *mut ADDIN_ARGV,
+    >,
+    pub GetRdpSettings: ::std::option::Option<
+        unsafe extern "C" fn(
+            pEntryPoints: *mut IDRDYNVC_ENTRY_POINTS,
+        ) -> *mut ::std::os::raw::c_void,
+    >,
+}
+#[test]
+fn bindgen_test_layout__IDRDYNVC_ENTRY_POINTS() {
+    const UNINIT: ::std::mem::MaybeUninit<_IDRDYNVC_ENTRY_POINTS> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<_IDRDYNVC_ENTRY_POINTS>(),
+        32usize,
+        concat!("Size of: ", stringify!(_IDRDYNVC_ENTRY_POINTS))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<_IDRDYNVC_ENTRY_POINTS>(),
+        8usize,
+        concat!("Alignment of ", stringify!(_IDRDYNVC_ENTRY_POINTS))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).RegisterPlugin) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IDRDYNVC_ENTRY_POINTS),
+            "::",
+            stringify!(RegisterPlugin)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetPlugin) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IDRDYNVC_ENTRY_POINTS),
+            "::",
+            stringify!(GetPlugin)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetPluginData) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IDRDYNVC_ENTRY_POINTS),
+            "::",
+            stringify!(GetPluginData)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).GetRdpSettings) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(_IDRDYNVC_ENTRY_POINTS),
+            "::",
+            stringify!(GetRdpSettings)
+        )
+    );
+}
@@ -1,5 +1,10 @@
 #include <freerdp/freerdp.h>
+#include <freerdp/session.h>
+#include <freerdp/peer.h>
+#include <freerdp/listener.h>
+#include <freerdp/assistance.h>
 #include <freerdp/client/channels.h>
+#include <freerdp/dvc.h>
 #include <freerdp/client/disp.h>
 #include <freerdp/client/rdpei.h>
 #include <freerdp/client/rail.h>
@@ -8,6 +13,14 @@
 #include <freerdp/client/encomsp.h>
 #include <freerdp/locale/keyboard.h>
 #include <freerdp/locale/locale.h>
+#include <freerdp/codec/rfx.h>
+#include <freerdp/codec/nsc.h>
+#include <freerdp/codec/planar.h>
+#include <freerdp/codec/interleaved.h>
+#include <freerdp/codec/clear.h>
+#include <freerdp/codec/progressive.h>
+#include <freerdp/codec/region.h>
+#include <freerdp/codec/h264.h>
 #include <freerdp/gdi/gdi.h>
 #include <freerdp/gdi/gfx.h>
 #include <freerdp/gdi/video.h>
@@ -9,17 +9,34 @@ bindgen -o bindgen.rs freerdp.h  \
 	--allowlist-function 'PubSub.*' \
 	--allowlist-function 'WLog.*' \
 	--allowlist-function 'Wait.*' \
+	--allowlist-function 'bitmap_interleaved_.*' \
+	--allowlist-function 'clear_.*' \
 	--allowlist-function 'client_.*' \
 	--allowlist-function 'cliprdr_.*' \
 	--allowlist-function 'freerdp_.*' \
+	--allowlist-function 'freerdp_listener_.*' \
+	--allowlist-function 'freerdp_peer_.*' \
 	--allowlist-function 'gdi_.*' \
 	--allowlist-function 'graphics_.*' \
+	--allowlist-function 'interleaved_.*' \
+	--allowlist-function 'nsc_.*' \
+	--allowlist-function 'planar_.*' \
+	--allowlist-function 'progressive_.*' \
 	--allowlist-function 'rdpgfx_.*' \
+	--allowlist-function 'region16_.*' \
+	--allowlist-function 'rfx_.*' \
 	--allowlist-function 'stream_.*' \
 	--allowlist-type '.*ClientContext' \
 	--allowlist-type 'CLIP.*' \
 	--allowlist-type 'Disp.*' \
+	--allowlist-type 'IDRDYNVC_.*' \
+	--allowlist-type 'IWTS.*' \
+	--allowlist-type 'RDPGFX_.*' \
 	--allowlist-type 'Rdp.*' \
+	--allowlist-type 'SEC_WINNT_AUTH_IDENTITY' \
+	--allowlist-type 'freerdp_listener' \
+	--allowlist-type 'freerdp_peer' \
+	--allowlist-type 'logon_info.*' \
 	--allowlist-var 'AUDIN_.*' \
 	--allowlist-var 'CAT_.*' \
 	--allowlist-var 'CB_.*' \
@@ -31,8 +48,10 @@ bindgen -o bindgen.rs freerdp.h  \
 	--allowlist-var 'ERRINFO.*' \
 	--allowlist-var 'FREERDP.*' \
 	--allowlist-var 'FreeRDP.*' \
+	--allowlist-var 'INFO_TYPE_.*' \
 	--allowlist-var 'OS.*' \
 	--allowlist-var 'PIXEL_.*' \
 	--allowlist-var 'RDP.*' \
+	--allowlist-var 'SEC_WINNT_AUTH_IDENTITY_.*' \
 	--allowlist-var 'WAIT_.*' \
 	-- `pkg-config --cflags freerdp2`
@@ -0,0 +1,46 @@
+//! Connect and drive the session from tokio, Enter disconnects.
+//!
+//! Usage: async-client [FREERDP ARGS...]
+
+use freerdp2::{client::*, RdpError, Result, PIXEL_FORMAT_BGRA32};
+
+struct AsyncHandler;
+
+impl Handler for AsyncHandler {
+    fn post_connect(&mut self, context: &mut Context<Self>) -> Result<()> {
+        context.instance.gdi_init(PIXEL_FORMAT_BGRA32)?;
+        let gdi = context.gdi().ok_or(RdpError::Unsupported)?;
+        eprintln!("Connected, {:?}x{:?}", gdi.width(), gdi.height());
+        Ok(())
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let mut ctxt = Context::new(AsyncHandler);
+
+    ctxt.client_start().unwrap();
+    let args: Vec<_> = std::env::args().collect();
+    let args: Vec<_> = args.iter().map(|s| s.as_str()).collect();
+    ctxt.settings.parse_command_line(&args, true).unwrap();
+
+    let shutdown = ctxt.shutdown_handle();
+    std::thread::spawn(move || {
+        let _ = std::io::stdin().read_line(&mut String::new());
+        let _ = shutdown.abort_connect();
+        shutdown.disconnect();
+    });
+
+    let res = match ctxt.connect_async().await {
+        Ok(()) => ctxt.run_async().await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = res {
+        eprintln!(
+            "{}",
+            ctxt.last_error_string().unwrap_or_else(|| e.to_string())
+        );
+    }
+
+    ctxt.client_stop().unwrap();
+}
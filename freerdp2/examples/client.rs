@@ -1,6 +1,6 @@
 use freerdp2::{
     client::*, graphics::*, locale::keyboard_init_ex, update::*, winpr::wait_for_multiple_objects,
-    RdpError, Result, PIXEL_FORMAT_BGRA32,
+    RdpError, Rect, Result, PIXEL_FORMAT_BGRA32,
 };
 
 #[derive(Debug)]
@@ -28,23 +28,15 @@ struct MyUpdateHandler;
 impl UpdateHandler for MyUpdateHandler {
     type ContextHandler = MyContextHandler;
 
-    fn begin_paint(context: &mut Context<Self::ContextHandler>) -> Result<()> {
-        let gdi = context.gdi().ok_or(RdpError::Unsupported)?;
-        let mut primary = gdi.primary().ok_or(RdpError::Unsupported)?;
-        primary.hdc().hwnd().invalid().set_null(true);
-        Ok(())
-    }
-
     fn end_paint(context: &mut Context<Self::ContextHandler>) -> Result<()> {
         let gdi = context.gdi().ok_or(RdpError::Unsupported)?;
-        let mut primary = gdi.primary().ok_or(RdpError::Unsupported)?;
-        let invalid = primary.hdc().hwnd().invalid();
-        if invalid.null() {
-            return Ok(());
-        }
-        let (x, y, w, h) = (invalid.x(), invalid.y(), invalid.w(), invalid.h());
+        let mut fb = gdi.frame_buffer().ok_or(RdpError::Unsupported)?;
+        let rects: Vec<_> = fb.dirty_rects().collect();
 
-        context.handler.update_buffer(x, y, w, h)
+        for rect in rects {
+            context.handler.update_buffer(&rect)?;
+        }
+        Ok(())
     }
 
     fn set_bounds(_context: &mut Context<Self::ContextHandler>, bounds: &Bounds) -> Result<()> {
@@ -73,12 +65,8 @@ struct MyContextHandler {
 }
 
 impl MyContextHandler {
-    fn update_buffer(&mut self, x: i32, y: i32, w: i32, h: i32) -> Result<()> {
-        let x = u32::try_from(x)?;
-        let y = u32::try_from(y)?;
-        let w = u32::try_from(w)?;
-        let h = u32::try_from(h)?;
-        dbg!((x, y, w, h));
+    fn update_buffer(&mut self, rect: &Rect) -> Result<()> {
+        dbg!(rect);
         Ok(())
     }
 }
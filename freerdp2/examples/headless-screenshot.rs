@@ -0,0 +1,81 @@
+//! Connect, let the screen settle, and save it as a PNG.
+//!
+//! Usage: headless-screenshot [--settle SECS] [--output FILE] [FREERDP ARGS...]
+
+use std::time::{Duration, Instant};
+
+use freerdp2::{
+    client::*, winpr::wait_for_multiple_objects, RdpError, Result, PIXEL_FORMAT_BGRA32,
+};
+
+struct ScreenshotHandler;
+
+impl Handler for ScreenshotHandler {
+    fn post_connect(&mut self, context: &mut Context<Self>) -> Result<()> {
+        context.instance.gdi_init(PIXEL_FORMAT_BGRA32)
+    }
+}
+
+fn screenshot(context: &Context<ScreenshotHandler>, output: &str) -> Result<()> {
+    let gdi = context.gdi().ok_or(RdpError::Unsupported)?;
+    let image = gdi.snapshot().ok_or(RdpError::Unsupported)?;
+    std::fs::write(output, image.to_png()?)?;
+    eprintln!(
+        "Saved {}x{} screenshot to {}",
+        image.width, image.height, output
+    );
+    Ok(())
+}
+
+fn main() {
+    let mut settle = Duration::from_secs(5);
+    let mut output = "screenshot.png".to_string();
+    let mut args = vec![];
+
+    let mut argv = std::env::args();
+    args.extend(argv.next());
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "--settle" => {
+                let secs = argv.next().expect("--settle requires a value");
+                settle = Duration::from_secs_f64(secs.parse().expect("Invalid --settle value"));
+            }
+            "--output" => output = argv.next().expect("--output requires a value"),
+            _ => args.push(arg),
+        }
+    }
+
+    let mut ctxt = Context::new(ScreenshotHandler);
+
+    ctxt.client_start().unwrap();
+    let args: Vec<_> = args.iter().map(|s| s.as_str()).collect();
+    ctxt.settings.parse_command_line(&args, true).unwrap();
+
+    ctxt.instance.connect().unwrap();
+    // a static desktop may not be painted again, so don't wait for updates
+    let deadline = Instant::now() + settle;
+
+    while !ctxt.instance.shall_disconnect() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            if let Err(e) = screenshot(&ctxt, &output) {
+                eprintln!("Failed to save the screenshot: {:?}", e);
+            }
+            break;
+        }
+
+        let handles = ctxt.event_handles().unwrap();
+        let handles: Vec<_> = handles.iter().collect();
+        wait_for_multiple_objects(&handles, false, Some(&remaining)).unwrap();
+
+        if !ctxt.check_event_handles() {
+            if let Some(e) = ctxt.last_error() {
+                eprintln!("{:?}", e);
+                break;
+            }
+        }
+    }
+
+    ctxt.instance.disconnect().unwrap();
+    ctxt.client_stop().unwrap();
+}
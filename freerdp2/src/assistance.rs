@@ -0,0 +1,149 @@
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_void},
+    path::Path,
+};
+
+use crate::{
+    settings::path_to_cstring,
+    settings::typed::{Bool, Key, Str},
+    sys, RdpError, Result, Settings,
+};
+
+extern "C" {
+    fn free(ptr: *mut c_void);
+}
+
+/// A Remote Assistance invitation, as loaded from a `.msrcIncident` file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssistanceInvitation {
+    pub session_id: String,
+    /// The connection string (RC ticket) of the novice.
+    pub connection_string: Option<String>,
+    pub pass_stub: Option<String>,
+    pub password: Option<String>,
+}
+
+impl AssistanceInvitation {
+    /// Compute the expert blob sent to the novice over the remdesk channel.
+    ///
+    /// This is done by the remdesk channel itself during the connection,
+    /// `name` is the expert name presented to the novice.
+    pub fn expert_blob(&self, name: &str) -> Result<String> {
+        let password = CString::new(self.password.as_deref().ok_or(RdpError::Unsupported)?)?;
+        let pass_stub = CString::new(self.pass_stub.as_deref().ok_or(RdpError::Unsupported)?)?;
+        let name = CString::new(name)?;
+
+        unsafe {
+            let mut size = 0;
+            let encrypted = sys::freerdp_assistance_encrypt_pass_stub(
+                password.as_ptr(),
+                pass_stub.as_ptr(),
+                &mut size,
+            );
+            if encrypted.is_null() {
+                return Err(RdpError::Failed("Failed to encrypt pass stub".into()));
+            }
+            let pass = sys::freerdp_assistance_bin_to_hex_string(encrypted, size);
+            free(encrypted as _);
+            if pass.is_null() {
+                return Err(RdpError::Failed("Failed to encode pass stub".into()));
+            }
+            let blob = sys::freerdp_assistance_construct_expert_blob(name.as_ptr(), pass);
+            free(pass as _);
+            take_string(blob).ok_or_else(|| RdpError::Failed("Failed to build expert blob".into()))
+        }
+    }
+}
+
+unsafe fn take_string(ptr: *mut c_char) -> Option<String> {
+    if ptr.is_null() {
+        return None;
+    }
+    let s = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+    free(ptr as _);
+    Some(s)
+}
+
+impl Settings {
+    /// Load a Remote Assistance invitation file (`.msrcIncident`).
+    ///
+    /// The `password` is needed to decrypt invitations created with one.
+    pub fn parse_assistance_file<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        password: Option<&str>,
+    ) -> Result<()> {
+        let program = CString::default();
+        let path = path_to_cstring(path.as_ref())?;
+        let arg = password
+            .map(|p| CString::new(format!("/assistance:{}", p)))
+            .transpose()?;
+        let mut argv = vec![program.as_ptr(), path.as_ptr()];
+        if let Some(arg) = &arg {
+            argv.push(arg.as_ptr());
+        }
+
+        let res = unsafe {
+            sys::freerdp_client_settings_parse_assistance_file(
+                self.inner.as_ptr(),
+                argv.len() as _,
+                argv.as_mut_ptr() as _,
+            )
+        };
+        if res != 0 {
+            return Err(RdpError::Failed("Failed to parse assistance file".into()));
+        }
+
+        if let Some(password) = password {
+            self.set::<Str>(Key::RemoteAssistancePassword, Some(password.into()))?;
+        }
+        Ok(())
+    }
+
+    /// The Remote Assistance invitation, if one was loaded.
+    pub fn assistance_invitation(&self) -> Option<AssistanceInvitation> {
+        if !self.get::<Bool>(Key::RemoteAssistanceMode).ok()? {
+            return None;
+        }
+
+        Some(AssistanceInvitation {
+            session_id: self.get::<Str>(Key::RemoteAssistanceSessionId).ok()??,
+            connection_string: self.get::<Str>(Key::RemoteAssistanceRCTicket).ok()?,
+            pass_stub: self.get::<Str>(Key::RemoteAssistancePassStub).ok()?,
+            password: self.get::<Str>(Key::RemoteAssistancePassword).ok()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_invitation() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/invitation.msrcIncident");
+        let mut settings = Settings::new(true, unsafe { sys::freerdp_settings_new(0) });
+        assert!(settings.assistance_invitation().is_none());
+
+        settings
+            .parse_assistance_file(path, Some("Password1"))
+            .unwrap();
+        let invitation = settings.assistance_invitation().unwrap();
+        assert_eq!(
+            invitation.session_id,
+            "rb+v0oPmEISmi8N2zK/vuhgul/ABqlDt6wW0VxMyxK8="
+        );
+        assert_eq!(invitation.pass_stub.as_deref(), Some("RT=0PvIndan52*"));
+        assert_eq!(invitation.password.as_deref(), Some("Password1"));
+        assert!(invitation
+            .connection_string
+            .as_deref()
+            .unwrap()
+            .starts_with("65538,1,10.0.3.105:3389"));
+        assert!(invitation
+            .expert_blob("Expert")
+            .unwrap()
+            .contains(";NAME=Expert"));
+    }
+}
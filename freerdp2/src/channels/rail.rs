@@ -1 +1,316 @@
+use bitflags::bitflags;
+
+use crate::{sys, RdpError, Rect};
+
 pub const SVC_CHANNEL_NAME: &str = "rail";
+
+/// The build number sent in the client handshake.
+pub const CLIENT_BUILD_NUMBER: u32 = 0x00001db0;
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub struct ExecFlags: u16 {
+        const EXPAND_WORKING_DIRECTORY = 0x0001;
+        const TRANSLATE_FILES = 0x0002;
+        const FILE = 0x0004;
+        const EXPAND_ARGUMENTS = 0x0008;
+        const APP_USER_MODEL_ID = 0x0010;
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct HandshakeExFlags: u32 {
+        const HIDEF = 0x00000001;
+        const EXTENDED_SPI_SUPPORTED = 0x00000002;
+        const SNAP_ARRANGE_SUPPORTED = 0x00000004;
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct ClientStatusFlags: u32 {
+        const ALLOW_LOCAL_MOVE_SIZE = 0x00000001;
+        const AUTO_RECONNECT = 0x00000002;
+        const ZORDER_SYNC = 0x00000004;
+        const WINDOW_RESIZE_MARGIN_SUPPORTED = 0x00000010;
+        const HIGH_DPI_ICONS_SUPPORTED = 0x00000020;
+        const APPBAR_REMOTING_SUPPORTED = 0x00000040;
+        const POWER_DISPLAY_REQUEST_SUPPORTED = 0x00000080;
+        const BIDIRECTIONAL_CLOAK_SUPPORTED = 0x00000200;
+    }
+}
+
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExecResultCode {
+    Ok = 0x0000,
+    HookNotLoaded = 0x0001,
+    DecodeFailed = 0x0002,
+    NotInAllowList = 0x0003,
+    FileNotFound = 0x0005,
+    Fail = 0x0006,
+    SessionLocked = 0x0007,
+}
+
+impl TryFrom<u16> for ExecResultCode {
+    type Error = RdpError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            v if v == ExecResultCode::Ok as u16 => Ok(ExecResultCode::Ok),
+            v if v == ExecResultCode::HookNotLoaded as u16 => Ok(ExecResultCode::HookNotLoaded),
+            v if v == ExecResultCode::DecodeFailed as u16 => Ok(ExecResultCode::DecodeFailed),
+            v if v == ExecResultCode::NotInAllowList as u16 => Ok(ExecResultCode::NotInAllowList),
+            v if v == ExecResultCode::FileNotFound as u16 => Ok(ExecResultCode::FileNotFound),
+            v if v == ExecResultCode::Fail as u16 => Ok(ExecResultCode::Fail),
+            v if v == ExecResultCode::SessionLocked as u16 => Ok(ExecResultCode::SessionLocked),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SysCommand {
+    Size = 0xf000,
+    Move = 0xf010,
+    Minimize = 0xf020,
+    Maximize = 0xf030,
+    Close = 0xf060,
+    KeyMenu = 0xf100,
+    Restore = 0xf120,
+    Default = 0xf160,
+}
+
+/// The notification messages of a `NotifyEvent`.
+pub mod notify {
+    pub const WM_LBUTTONDOWN: u32 = 0x0201;
+    pub const WM_LBUTTONUP: u32 = 0x0202;
+    pub const WM_LBUTTONDBLCLK: u32 = 0x0203;
+    pub const WM_RBUTTONDOWN: u32 = 0x0204;
+    pub const WM_RBUTTONUP: u32 = 0x0205;
+    pub const WM_RBUTTONDBLCLK: u32 = 0x0206;
+    pub const WM_CONTEXTMENU: u32 = 0x007b;
+    pub const NIN_SELECT: u32 = 0x0400;
+    pub const NIN_KEYSELECT: u32 = 0x0401;
+    pub const NIN_BALLOONSHOW: u32 = 0x0402;
+    pub const NIN_BALLOONHIDE: u32 = 0x0403;
+    pub const NIN_BALLOONTIMEOUT: u32 = 0x0404;
+    pub const NIN_BALLOONUSERCLICK: u32 = 0x0405;
+}
+
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MoveSizeType {
+    Left = 0x0001,
+    Right = 0x0002,
+    Top = 0x0003,
+    TopLeft = 0x0004,
+    TopRight = 0x0005,
+    Bottom = 0x0006,
+    BottomLeft = 0x0007,
+    BottomRight = 0x0008,
+    Move = 0x0009,
+    KeyMove = 0x000a,
+    KeySize = 0x000b,
+}
+
+impl TryFrom<u16> for MoveSizeType {
+    type Error = RdpError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            v if v == MoveSizeType::Left as u16 => Ok(MoveSizeType::Left),
+            v if v == MoveSizeType::Right as u16 => Ok(MoveSizeType::Right),
+            v if v == MoveSizeType::Top as u16 => Ok(MoveSizeType::Top),
+            v if v == MoveSizeType::TopLeft as u16 => Ok(MoveSizeType::TopLeft),
+            v if v == MoveSizeType::TopRight as u16 => Ok(MoveSizeType::TopRight),
+            v if v == MoveSizeType::Bottom as u16 => Ok(MoveSizeType::Bottom),
+            v if v == MoveSizeType::BottomLeft as u16 => Ok(MoveSizeType::BottomLeft),
+            v if v == MoveSizeType::BottomRight as u16 => Ok(MoveSizeType::BottomRight),
+            v if v == MoveSizeType::Move as u16 => Ok(MoveSizeType::Move),
+            v if v == MoveSizeType::KeyMove as u16 => Ok(MoveSizeType::KeyMove),
+            v if v == MoveSizeType::KeySize as u16 => Ok(MoveSizeType::KeySize),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+pub(crate) const SPI_SETSCREENSAVEACTIVE: u32 = 0x0011;
+pub(crate) const SPI_SETSCREENSAVESECURE: u32 = 0x0077;
+
+pub(crate) const SPI_MASK_SET_DRAG_FULL_WINDOWS: u32 = 0x0001;
+pub(crate) const SPI_MASK_SET_KEYBOARD_CUES: u32 = 0x0002;
+pub(crate) const SPI_MASK_SET_KEYBOARD_PREF: u32 = 0x0004;
+pub(crate) const SPI_MASK_SET_MOUSE_BUTTON_SWAP: u32 = 0x0008;
+pub(crate) const SPI_MASK_SET_WORK_AREA: u32 = 0x0010;
+pub(crate) const SPI_MASK_DISPLAY_CHANGE: u32 = 0x0020;
+pub(crate) const SPI_MASK_TASKBAR_POS: u32 = 0x0040;
+pub(crate) const SPI_MASK_SET_HIGH_CONTRAST: u32 = 0x0080;
+
+/// A program to launch with [`crate::client::RailClientContext::send_exec`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Exec {
+    pub flags: ExecFlags,
+    pub program: String,
+    pub working_dir: Option<String>,
+    pub arguments: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecResult {
+    pub flags: ExecFlags,
+    pub result: Result<ExecResultCode, u16>,
+    pub raw_result: u32,
+    pub exe_or_file: String,
+}
+
+impl ExecResult {
+    pub(crate) fn new(r: &sys::RAIL_EXEC_RESULT_ORDER) -> Self {
+        Self {
+            flags: ExecFlags::from_bits_truncate(r.flags),
+            result: r.execResult.try_into().map_err(|_| r.execResult),
+            raw_result: r.rawResult,
+            exe_or_file: unicode_string(&r.exeOrFile),
+        }
+    }
+}
+
+pub(crate) fn unicode_string(s: &sys::RAIL_UNICODE_STRING) -> String {
+    if s.string.is_null() {
+        return String::new();
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(s.string, s.length as _) };
+    let wide: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .collect();
+    String::from_utf16_lossy(&wide)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ServerSystemParam {
+    ScreenSaveActive(bool),
+    ScreenSaveSecure(bool),
+    Unknown(u32),
+}
+
+impl ServerSystemParam {
+    pub(crate) fn new(p: &sys::RAIL_SYSPARAM_ORDER) -> Self {
+        match p.param {
+            SPI_SETSCREENSAVEACTIVE => Self::ScreenSaveActive(p.setScreenSaveActive != 0),
+            SPI_SETSCREENSAVESECURE => Self::ScreenSaveSecure(p.setScreenSaveSecure != 0),
+            param => Self::Unknown(param),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct HighContrast {
+    pub flags: u32,
+}
+
+/// The client system parameters, only the ones set are sent.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClientSystemParams {
+    pub drag_full_windows: Option<bool>,
+    pub keyboard_cues: Option<bool>,
+    pub keyboard_pref: Option<bool>,
+    pub mouse_button_swap: Option<bool>,
+    pub work_area: Option<Rect>,
+    pub display_change: Option<Rect>,
+    pub taskbar_pos: Option<Rect>,
+    pub high_contrast: Option<HighContrast>,
+}
+
+impl ClientSystemParams {
+    pub(crate) fn to_order(self) -> sys::RAIL_SYSPARAM_ORDER {
+        let mut order: sys::RAIL_SYSPARAM_ORDER = unsafe { std::mem::zeroed() };
+
+        if let Some(v) = self.drag_full_windows {
+            order.params |= SPI_MASK_SET_DRAG_FULL_WINDOWS;
+            order.dragFullWindows = v as _;
+        }
+        if let Some(v) = self.keyboard_cues {
+            order.params |= SPI_MASK_SET_KEYBOARD_CUES;
+            order.keyboardCues = v as _;
+        }
+        if let Some(v) = self.keyboard_pref {
+            order.params |= SPI_MASK_SET_KEYBOARD_PREF;
+            order.keyboardPref = v as _;
+        }
+        if let Some(v) = self.mouse_button_swap {
+            order.params |= SPI_MASK_SET_MOUSE_BUTTON_SWAP;
+            order.mouseButtonSwap = v as _;
+        }
+        if let Some(r) = self.work_area {
+            order.params |= SPI_MASK_SET_WORK_AREA;
+            order.workArea = r.into();
+        }
+        if let Some(r) = self.display_change {
+            order.params |= SPI_MASK_DISPLAY_CHANGE;
+            order.displayChange = r.into();
+        }
+        if let Some(r) = self.taskbar_pos {
+            order.params |= SPI_MASK_TASKBAR_POS;
+            order.taskbarPos = r.into();
+        }
+        if let Some(h) = self.high_contrast {
+            order.params |= SPI_MASK_SET_HIGH_CONTRAST;
+            order.highContrast.flags = h.flags;
+        }
+        order
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MinMaxInfo {
+    pub window_id: u32,
+    pub max_width: i16,
+    pub max_height: i16,
+    pub max_pos_x: i16,
+    pub max_pos_y: i16,
+    pub min_track_width: i16,
+    pub min_track_height: i16,
+    pub max_track_width: i16,
+    pub max_track_height: i16,
+}
+
+impl From<&sys::RAIL_MINMAXINFO_ORDER> for MinMaxInfo {
+    fn from(m: &sys::RAIL_MINMAXINFO_ORDER) -> Self {
+        Self {
+            window_id: m.windowId,
+            max_width: m.maxWidth,
+            max_height: m.maxHeight,
+            max_pos_x: m.maxPosX,
+            max_pos_y: m.maxPosY,
+            min_track_width: m.minTrackWidth,
+            min_track_height: m.minTrackHeight,
+            max_track_width: m.maxTrackWidth,
+            max_track_height: m.maxTrackHeight,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LocalMoveSize {
+    pub window_id: u32,
+    pub is_move_size_start: bool,
+    pub move_size_type: Result<MoveSizeType, u16>,
+    pub pos_x: i16,
+    pub pos_y: i16,
+}
+
+impl From<&sys::RAIL_LOCALMOVESIZE_ORDER> for LocalMoveSize {
+    fn from(m: &sys::RAIL_LOCALMOVESIZE_ORDER) -> Self {
+        Self {
+            window_id: m.windowId,
+            is_move_size_start: m.isMoveSizeStart != 0,
+            move_size_type: m.moveSizeType.try_into().map_err(|_| m.moveSizeType),
+            pos_x: m.posX,
+            pos_y: m.posY,
+        }
+    }
+}
@@ -1,3 +1,794 @@
-// use crate::sys;
+use bitflags::bitflags;
+use std::{slice, time::Duration};
+
+use crate::{sys, PixelFormat, RdpError, Rect, Result};
 
 pub const DVC_CHANNEL_NAME: &str = "Microsoft::Windows::RDS::Graphics";
+
+/// The frame acknowledgement queue depth, when it isn't known.
+pub const QUEUE_DEPTH_UNAVAILABLE: u32 = 0x0000_0000;
+/// The frame acknowledgement queue depth asking the server to stop waiting
+/// for acknowledgements.
+pub const SUSPEND_FRAME_ACKNOWLEDGEMENT: u32 = 0xFFFF_FFFF;
+
+/// The number of frames waiting to be decoded, reported in a frame
+/// acknowledgement. The server may slow down as it grows.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum QueueDepth {
+    Unavailable,
+    Frames(u32),
+    /// Stop sending acknowledgements, until one with another depth is sent.
+    Suspend,
+}
+
+impl From<QueueDepth> for u32 {
+    fn from(depth: QueueDepth) -> Self {
+        match depth {
+            QueueDepth::Unavailable => QUEUE_DEPTH_UNAVAILABLE,
+            // neither of the reserved values
+            QueueDepth::Frames(n) => n.clamp(1, SUSPEND_FRAME_ACKNOWLEDGEMENT - 1),
+            QueueDepth::Suspend => SUSPEND_FRAME_ACKNOWLEDGEMENT,
+        }
+    }
+}
+
+/// The timings of a frame, for the server quality of experience metrics.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct QoeFrameAcknowledge {
+    pub frame_id: u32,
+    /// The time the frame was received, in milliseconds of an arbitrary clock.
+    pub timestamp: u32,
+    /// From the StartFrame to the EndFrame PDU.
+    pub start_to_end: Duration,
+    /// From the EndFrame PDU to the frame being decoded and rendered.
+    pub end_to_render: Duration,
+}
+
+impl From<&QoeFrameAcknowledge> for sys::RDPGFX_QOE_FRAME_ACKNOWLEDGE_PDU {
+    fn from(ack: &QoeFrameAcknowledge) -> Self {
+        let millis = |d: Duration| d.as_millis().min(u16::MAX as _) as u16;
+
+        Self {
+            frameId: ack.frame_id,
+            timestamp: ack.timestamp,
+            timeDiffSE: millis(ack.start_to_end),
+            timeDiffEDR: millis(ack.end_to_render),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GfxCodecId {
+    Uncompressed = sys::RDPGFX_CODECID_UNCOMPRESSED as _,
+    CaVideo = sys::RDPGFX_CODECID_CAVIDEO as _,
+    ClearCodec = sys::RDPGFX_CODECID_CLEARCODEC as _,
+    CaProgressive = sys::RDPGFX_CODECID_CAPROGRESSIVE as _,
+    Planar = sys::RDPGFX_CODECID_PLANAR as _,
+    Avc420 = sys::RDPGFX_CODECID_AVC420 as _,
+    Alpha = sys::RDPGFX_CODECID_ALPHA as _,
+    CaProgressiveV2 = sys::RDPGFX_CODECID_CAPROGRESSIVE_V2 as _,
+    Avc444 = sys::RDPGFX_CODECID_AVC444 as _,
+    Avc444v2 = sys::RDPGFX_CODECID_AVC444v2 as _,
+}
+
+impl TryFrom<u32> for GfxCodecId {
+    type Error = RdpError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            v if v == GfxCodecId::Uncompressed as u32 => Ok(GfxCodecId::Uncompressed),
+            v if v == GfxCodecId::CaVideo as u32 => Ok(GfxCodecId::CaVideo),
+            v if v == GfxCodecId::ClearCodec as u32 => Ok(GfxCodecId::ClearCodec),
+            v if v == GfxCodecId::CaProgressive as u32 => Ok(GfxCodecId::CaProgressive),
+            v if v == GfxCodecId::Planar as u32 => Ok(GfxCodecId::Planar),
+            v if v == GfxCodecId::Avc420 as u32 => Ok(GfxCodecId::Avc420),
+            v if v == GfxCodecId::Alpha as u32 => Ok(GfxCodecId::Alpha),
+            v if v == GfxCodecId::CaProgressiveV2 as u32 => Ok(GfxCodecId::CaProgressiveV2),
+            v if v == GfxCodecId::Avc444 as u32 => Ok(GfxCodecId::Avc444),
+            v if v == GfxCodecId::Avc444v2 as u32 => Ok(GfxCodecId::Avc444v2),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub enum GfxCapVersion {
+    V8 = sys::RDPGFX_CAPVERSION_8 as _,
+    V81 = sys::RDPGFX_CAPVERSION_81 as _,
+    V10 = sys::RDPGFX_CAPVERSION_10 as _,
+    V101 = sys::RDPGFX_CAPVERSION_101 as _,
+    V102 = sys::RDPGFX_CAPVERSION_102 as _,
+    V103 = sys::RDPGFX_CAPVERSION_103 as _,
+    V104 = sys::RDPGFX_CAPVERSION_104 as _,
+    V105 = sys::RDPGFX_CAPVERSION_105 as _,
+    V106 = sys::RDPGFX_CAPVERSION_106 as _,
+    /// The value of 10.6 sent by some Windows versions.
+    V106Err = sys::RDPGFX_CAPVERSION_106_ERR as _,
+    V107 = sys::RDPGFX_CAPVERSION_107 as _,
+}
+
+impl TryFrom<u32> for GfxCapVersion {
+    type Error = RdpError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            v if v == GfxCapVersion::V8 as u32 => Ok(GfxCapVersion::V8),
+            v if v == GfxCapVersion::V81 as u32 => Ok(GfxCapVersion::V81),
+            v if v == GfxCapVersion::V10 as u32 => Ok(GfxCapVersion::V10),
+            v if v == GfxCapVersion::V101 as u32 => Ok(GfxCapVersion::V101),
+            v if v == GfxCapVersion::V102 as u32 => Ok(GfxCapVersion::V102),
+            v if v == GfxCapVersion::V103 as u32 => Ok(GfxCapVersion::V103),
+            v if v == GfxCapVersion::V104 as u32 => Ok(GfxCapVersion::V104),
+            v if v == GfxCapVersion::V105 as u32 => Ok(GfxCapVersion::V105),
+            v if v == GfxCapVersion::V106 as u32 => Ok(GfxCapVersion::V106),
+            v if v == GfxCapVersion::V106Err as u32 => Ok(GfxCapVersion::V106Err),
+            v if v == GfxCapVersion::V107 as u32 => Ok(GfxCapVersion::V107),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+bitflags! {
+    /// The capability flags, their meaning depends on the version: AVC420 is
+    /// opt-in for 8.1 and opt-out from 10.0.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct GfxCapsFlags: u32 {
+        const THIN_CLIENT = sys::RDPGFX_CAPS_FLAG_THINCLIENT;
+        const SMALL_CACHE = sys::RDPGFX_CAPS_FLAG_SMALL_CACHE;
+        const AVC420_ENABLED = sys::RDPGFX_CAPS_FLAG_AVC420_ENABLED;
+        const AVC_DISABLED = sys::RDPGFX_CAPS_FLAG_AVC_DISABLED;
+        const AVC_THIN_CLIENT = sys::RDPGFX_CAPS_FLAG_AVC_THINCLIENT;
+        const SCALEDMAP_DISABLE = sys::RDPGFX_CAPS_FLAG_SCALEDMAP_DISABLE;
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct GfxCapabilitySet {
+    pub version: GfxCapVersion,
+    pub flags: GfxCapsFlags,
+}
+
+impl GfxCapabilitySet {
+    pub fn new(version: GfxCapVersion, flags: GfxCapsFlags) -> Self {
+        Self { version, flags }
+    }
+
+    /// Whether the server may send AVC420 with this set.
+    pub fn avc420(&self) -> bool {
+        match self.version {
+            GfxCapVersion::V8 => false,
+            GfxCapVersion::V81 => self.flags.contains(GfxCapsFlags::AVC420_ENABLED),
+            _ => !self.flags.contains(GfxCapsFlags::AVC_DISABLED),
+        }
+    }
+
+    /// Whether the server may send AVC444v2 with this set.
+    pub fn avc444v2(&self) -> bool {
+        self.version >= GfxCapVersion::V106 && self.avc420()
+    }
+}
+
+impl TryFrom<&sys::RDPGFX_CAPSET> for GfxCapabilitySet {
+    type Error = RdpError;
+
+    fn try_from(c: &sys::RDPGFX_CAPSET) -> Result<Self> {
+        Ok(Self {
+            version: c.version.try_into()?,
+            flags: GfxCapsFlags::from_bits_retain(c.flags),
+        })
+    }
+}
+
+impl From<&GfxCapabilitySet> for sys::RDPGFX_CAPSET {
+    fn from(c: &GfxCapabilitySet) -> Self {
+        Self {
+            version: c.version as _,
+            // 10.1 has 16 reserved bytes instead of flags
+            length: if c.version == GfxCapVersion::V101 {
+                16
+            } else {
+                4
+            },
+            flags: c.flags.bits(),
+        }
+    }
+}
+
+/// The pixel format of a surface.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GfxPixelFormat {
+    Xrgb8888 = 0x20,
+    Argb8888 = 0x21,
+}
+
+impl TryFrom<u8> for GfxPixelFormat {
+    type Error = RdpError;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            v if v == GfxPixelFormat::Xrgb8888 as u8 => Ok(GfxPixelFormat::Xrgb8888),
+            v if v == GfxPixelFormat::Argb8888 as u8 => Ok(GfxPixelFormat::Argb8888),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct GfxColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// The alpha, or unused for `GfxPixelFormat::Xrgb8888` surfaces.
+    pub xa: u8,
+}
+
+impl From<&sys::RDPGFX_COLOR32> for GfxColor {
+    fn from(c: &sys::RDPGFX_COLOR32) -> Self {
+        Self {
+            r: c.R,
+            g: c.G,
+            b: c.B,
+            xa: c.XA,
+        }
+    }
+}
+
+/// A monitor, with inclusive bounds.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MonitorDef {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub flags: u32,
+}
+
+impl From<&sys::MONITOR_DEF> for MonitorDef {
+    fn from(m: &sys::MONITOR_DEF) -> Self {
+        Self {
+            left: m.left,
+            top: m.top,
+            right: m.right,
+            bottom: m.bottom,
+            flags: m.flags,
+        }
+    }
+}
+
+fn points(points: *const sys::RDPGFX_POINT16, len: u16) -> Vec<(u16, u16)> {
+    slice_or_empty(points, len as _)
+        .iter()
+        .map(|p| (p.x, p.y))
+        .collect()
+}
+
+/// The output size changed, all the surfaces are gone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResetGraphics {
+    pub width: u32,
+    pub height: u32,
+    pub monitors: Vec<MonitorDef>,
+}
+
+impl From<&sys::RDPGFX_RESET_GRAPHICS_PDU> for ResetGraphics {
+    fn from(p: &sys::RDPGFX_RESET_GRAPHICS_PDU) -> Self {
+        Self {
+            width: p.width,
+            height: p.height,
+            monitors: slice_or_empty(p.monitorDefArray, p.monitorCount as _)
+                .iter()
+                .map(MonitorDef::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateSurface {
+    pub surface_id: u16,
+    pub width: u16,
+    pub height: u16,
+    /// The pixel format, or its raw value if unknown.
+    pub pixel_format: std::result::Result<GfxPixelFormat, u8>,
+}
+
+impl From<&sys::RDPGFX_CREATE_SURFACE_PDU> for CreateSurface {
+    fn from(p: &sys::RDPGFX_CREATE_SURFACE_PDU) -> Self {
+        Self {
+            surface_id: p.surfaceId,
+            width: p.width,
+            height: p.height,
+            pixel_format: GfxPixelFormat::try_from(p.pixelFormat).map_err(|_| p.pixelFormat),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeleteSurface {
+    pub surface_id: u16,
+}
+
+impl From<&sys::RDPGFX_DELETE_SURFACE_PDU> for DeleteSurface {
+    fn from(p: &sys::RDPGFX_DELETE_SURFACE_PDU) -> Self {
+        Self {
+            surface_id: p.surfaceId,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapSurfaceToOutput {
+    pub surface_id: u16,
+    pub origin: (u32, u32),
+}
+
+impl From<&sys::RDPGFX_MAP_SURFACE_TO_OUTPUT_PDU> for MapSurfaceToOutput {
+    fn from(p: &sys::RDPGFX_MAP_SURFACE_TO_OUTPUT_PDU) -> Self {
+        Self {
+            surface_id: p.surfaceId,
+            origin: (p.outputOriginX, p.outputOriginY),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapSurfaceToScaledOutput {
+    pub surface_id: u16,
+    pub origin: (u32, u32),
+    pub target_width: u32,
+    pub target_height: u32,
+}
+
+impl From<&sys::RDPGFX_MAP_SURFACE_TO_SCALED_OUTPUT_PDU> for MapSurfaceToScaledOutput {
+    fn from(p: &sys::RDPGFX_MAP_SURFACE_TO_SCALED_OUTPUT_PDU) -> Self {
+        Self {
+            surface_id: p.surfaceId,
+            origin: (p.outputOriginX, p.outputOriginY),
+            target_width: p.targetWidth,
+            target_height: p.targetHeight,
+        }
+    }
+}
+
+/// Map a surface to a RAIL window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapSurfaceToWindow {
+    pub surface_id: u16,
+    pub window_id: u64,
+    pub mapped_width: u32,
+    pub mapped_height: u32,
+}
+
+impl From<&sys::RDPGFX_MAP_SURFACE_TO_WINDOW_PDU> for MapSurfaceToWindow {
+    fn from(p: &sys::RDPGFX_MAP_SURFACE_TO_WINDOW_PDU) -> Self {
+        Self {
+            surface_id: p.surfaceId,
+            window_id: p.windowId,
+            mapped_width: p.mappedWidth,
+            mapped_height: p.mappedHeight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapSurfaceToScaledWindow {
+    pub surface_id: u16,
+    pub window_id: u64,
+    pub mapped_width: u32,
+    pub mapped_height: u32,
+    pub target_width: u32,
+    pub target_height: u32,
+}
+
+impl From<&sys::RDPGFX_MAP_SURFACE_TO_SCALED_WINDOW_PDU> for MapSurfaceToScaledWindow {
+    fn from(p: &sys::RDPGFX_MAP_SURFACE_TO_SCALED_WINDOW_PDU) -> Self {
+        Self {
+            surface_id: p.surfaceId,
+            window_id: p.windowId,
+            mapped_width: p.mappedWidth,
+            mapped_height: p.mappedHeight,
+            target_width: p.targetWidth,
+            target_height: p.targetHeight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolidFill {
+    pub surface_id: u16,
+    pub color: GfxColor,
+    pub rects: Vec<Rect>,
+}
+
+impl From<&sys::RDPGFX_SOLID_FILL_PDU> for SolidFill {
+    fn from(p: &sys::RDPGFX_SOLID_FILL_PDU) -> Self {
+        Self {
+            surface_id: p.surfaceId,
+            color: (&p.fillPixel).into(),
+            rects: slice_or_empty(p.fillRects, p.fillRectCount as _)
+                .iter()
+                .map(|r| Rect::from(*r))
+                .collect(),
+        }
+    }
+}
+
+/// Copy `src_rect` to each of the destination points.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SurfaceToSurface {
+    pub src_surface_id: u16,
+    pub dst_surface_id: u16,
+    pub src_rect: Rect,
+    pub dst_points: Vec<(u16, u16)>,
+}
+
+impl From<&sys::RDPGFX_SURFACE_TO_SURFACE_PDU> for SurfaceToSurface {
+    fn from(p: &sys::RDPGFX_SURFACE_TO_SURFACE_PDU) -> Self {
+        Self {
+            src_surface_id: p.surfaceIdSrc,
+            dst_surface_id: p.surfaceIdDest,
+            src_rect: p.rectSrc.into(),
+            dst_points: points(p.destPts, p.destPtsCount),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SurfaceToCache {
+    pub surface_id: u16,
+    pub cache_key: u64,
+    pub cache_slot: u16,
+    pub src_rect: Rect,
+}
+
+impl From<&sys::RDPGFX_SURFACE_TO_CACHE_PDU> for SurfaceToCache {
+    fn from(p: &sys::RDPGFX_SURFACE_TO_CACHE_PDU) -> Self {
+        Self {
+            surface_id: p.surfaceId,
+            cache_key: p.cacheKey,
+            cache_slot: p.cacheSlot,
+            src_rect: p.rectSrc.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheToSurface {
+    pub cache_slot: u16,
+    pub surface_id: u16,
+    pub dst_points: Vec<(u16, u16)>,
+}
+
+impl From<&sys::RDPGFX_CACHE_TO_SURFACE_PDU> for CacheToSurface {
+    fn from(p: &sys::RDPGFX_CACHE_TO_SURFACE_PDU) -> Self {
+        Self {
+            cache_slot: p.cacheSlot,
+            surface_id: p.surfaceId,
+            dst_points: points(p.destPts, p.destPtsCount),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvictCacheEntry {
+    pub cache_slot: u16,
+}
+
+impl From<&sys::RDPGFX_EVICT_CACHE_ENTRY_PDU> for EvictCacheEntry {
+    fn from(p: &sys::RDPGFX_EVICT_CACHE_ENTRY_PDU) -> Self {
+        Self {
+            cache_slot: p.cacheSlot,
+        }
+    }
+}
+
+/// The cache slots the server kept from the offered entries, in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheImportReply {
+    pub cache_slots: Vec<u16>,
+}
+
+impl From<&sys::RDPGFX_CACHE_IMPORT_REPLY_PDU> for CacheImportReply {
+    fn from(p: &sys::RDPGFX_CACHE_IMPORT_REPLY_PDU) -> Self {
+        Self {
+            cache_slots: slice_or_empty(p.cacheSlots, p.importedEntriesCount as _).to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartFrame {
+    /// The encoded UTC time of the frame.
+    pub timestamp: u32,
+    pub frame_id: u32,
+}
+
+impl From<&sys::RDPGFX_START_FRAME_PDU> for StartFrame {
+    fn from(p: &sys::RDPGFX_START_FRAME_PDU) -> Self {
+        Self {
+            timestamp: p.timestamp,
+            frame_id: p.frameId,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndFrame {
+    pub frame_id: u32,
+}
+
+impl From<&sys::RDPGFX_END_FRAME_PDU> for EndFrame {
+    fn from(p: &sys::RDPGFX_END_FRAME_PDU) -> Self {
+        Self {
+            frame_id: p.frameId,
+        }
+    }
+}
+
+/// The quantization and quality of an H.264 region.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct H264QuantQuality {
+    pub qp: u8,
+    pub progressive: bool,
+    pub quality: u8,
+}
+
+impl From<&sys::RDPGFX_H264_QUANT_QUALITY> for H264QuantQuality {
+    fn from(q: &sys::RDPGFX_H264_QUANT_QUALITY) -> Self {
+        Self {
+            qp: q.qp,
+            progressive: q.p != 0,
+            quality: q.qualityVal,
+        }
+    }
+}
+
+/// An H.264 bitstream, with the regions it updates.
+#[derive(Debug)]
+pub struct Avc420Stream<'a> {
+    pub rects: Vec<Rect>,
+    pub quant_quality: Vec<H264QuantQuality>,
+    pub data: &'a [u8],
+}
+
+fn slice_or_empty<'a, T>(data: *const T, len: usize) -> &'a [T] {
+    if data.is_null() || len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(data, len) }
+    }
+}
+
+impl<'a> Avc420Stream<'a> {
+    // The stream borrows the data of `s`, owned by the channel.
+    pub(crate) fn new(s: &'a sys::RDPGFX_AVC420_BITMAP_STREAM) -> Self {
+        let n = s.meta.numRegionRects as usize;
+
+        Self {
+            rects: slice_or_empty(s.meta.regionRects, n)
+                .iter()
+                .map(|r| Rect::from(*r))
+                .collect(),
+            quant_quality: slice_or_empty(s.meta.quantQualityVals, n)
+                .iter()
+                .map(H264QuantQuality::from)
+                .collect(),
+            data: slice_or_empty(s.data, s.length as _),
+        }
+    }
+}
+
+/// The streams of an AVC444 surface command, the luma stream is a YUV420
+/// picture and the chroma stream carries the remaining chroma samples.
+#[derive(Debug)]
+pub enum Avc444Streams<'a> {
+    Both {
+        luma: Avc420Stream<'a>,
+        chroma: Avc420Stream<'a>,
+    },
+    Luma(Avc420Stream<'a>),
+    Chroma(Avc420Stream<'a>),
+}
+
+impl<'a> Avc444Streams<'a> {
+    pub(crate) fn new(s: &'a sys::RDPGFX_AVC444_BITMAP_STREAM) -> Result<Self> {
+        match s.LC {
+            0 => Ok(Avc444Streams::Both {
+                luma: Avc420Stream::new(&s.bitstream[0]),
+                chroma: Avc420Stream::new(&s.bitstream[1]),
+            }),
+            1 => Ok(Avc444Streams::Luma(Avc420Stream::new(&s.bitstream[0]))),
+            2 => Ok(Avc444Streams::Chroma(Avc420Stream::new(&s.bitstream[0]))),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+/// The parsed payload of the H.264 codecs.
+#[derive(Debug)]
+pub enum SurfaceStream<'a> {
+    Avc420(Avc420Stream<'a>),
+    Avc444(Avc444Streams<'a>),
+}
+
+/// A WireToSurface PDU, before it's decoded.
+#[derive(Debug)]
+pub struct SurfaceCommand<'a> {
+    pub surface_id: u32,
+    /// The codec, or its raw ID if unknown.
+    pub codec_id: std::result::Result<GfxCodecId, u32>,
+    pub context_id: u32,
+    pub format: Option<PixelFormat>,
+    /// The destination rectangle on the surface.
+    pub rect: Rect,
+    /// The encoded bitmap, as received.
+    pub data: &'a [u8],
+    pub stream: Option<SurfaceStream<'a>>,
+}
+
+impl<'a> SurfaceCommand<'a> {
+    // The command borrows the data of `cmd`, owned by the channel.
+    pub(crate) fn new(cmd: &'a sys::RDPGFX_SURFACE_COMMAND) -> Result<Self> {
+        let codec_id = GfxCodecId::try_from(cmd.codecId).map_err(|_| cmd.codecId);
+        let stream = match (&codec_id, cmd.extra.is_null()) {
+            (Ok(GfxCodecId::Avc420), false) => {
+                let s = unsafe { &*(cmd.extra as *const sys::RDPGFX_AVC420_BITMAP_STREAM) };
+                Some(SurfaceStream::Avc420(Avc420Stream::new(s)))
+            }
+            (Ok(GfxCodecId::Avc444 | GfxCodecId::Avc444v2), false) => {
+                let s = unsafe { &*(cmd.extra as *const sys::RDPGFX_AVC444_BITMAP_STREAM) };
+                Some(SurfaceStream::Avc444(Avc444Streams::new(s)?))
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            surface_id: cmd.surfaceId,
+            codec_id,
+            context_id: cmd.contextId,
+            format: PixelFormat::try_from(cmd.format).ok(),
+            rect: Rect {
+                left: cmd.left.try_into()?,
+                top: cmd.top.try_into()?,
+                right: cmd.right.try_into()?,
+                bottom: cmd.bottom.try_into()?,
+            },
+            data: slice_or_empty(cmd.data, cmd.length as _),
+            stream,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qoe_frame_acknowledge() {
+        assert_eq!(u32::from(QueueDepth::Frames(0)), 1);
+        assert_eq!(
+            u32::from(QueueDepth::Suspend),
+            SUSPEND_FRAME_ACKNOWLEDGEMENT
+        );
+
+        let ack = sys::RDPGFX_QOE_FRAME_ACKNOWLEDGE_PDU::from(&QoeFrameAcknowledge {
+            frame_id: 3,
+            timestamp: 1000,
+            start_to_end: Duration::from_micros(2500),
+            end_to_render: Duration::from_secs(120),
+        });
+        assert_eq!(ack.timeDiffSE, 2);
+        assert_eq!(ack.timeDiffEDR, u16::MAX);
+    }
+
+    #[test]
+    fn capability_set() {
+        let caps = GfxCapabilitySet::new(GfxCapVersion::V81, GfxCapsFlags::AVC420_ENABLED);
+        assert!(caps.avc420());
+        assert!(!caps.avc444v2());
+
+        let caps = GfxCapabilitySet::new(GfxCapVersion::V106, GfxCapsFlags::SMALL_CACHE);
+        assert!(caps.avc444v2());
+        let raw = sys::RDPGFX_CAPSET::from(&caps);
+        assert_eq!(raw.length, 4);
+        assert_eq!(GfxCapabilitySet::try_from(&raw).unwrap(), caps);
+
+        let caps = GfxCapabilitySet::new(GfxCapVersion::V10, GfxCapsFlags::AVC_DISABLED);
+        assert!(!caps.avc420());
+    }
+
+    #[test]
+    fn solid_fill() {
+        let mut rects = [sys::RECTANGLE_16 {
+            left: 1,
+            top: 2,
+            right: 3,
+            bottom: 4,
+        }];
+        let pdu = sys::RDPGFX_SOLID_FILL_PDU {
+            surfaceId: 7,
+            fillPixel: sys::RDPGFX_COLOR32 {
+                B: 1,
+                G: 2,
+                R: 3,
+                XA: 0xff,
+            },
+            fillRectCount: 1,
+            fillRects: rects.as_mut_ptr(),
+        };
+
+        let fill = SolidFill::from(&pdu);
+        assert_eq!(fill.surface_id, 7);
+        assert_eq!(
+            fill.color,
+            GfxColor {
+                r: 3,
+                g: 2,
+                b: 1,
+                xa: 0xff
+            }
+        );
+        assert_eq!(fill.rects, [Rect::new(1, 2, 2, 2)]);
+    }
+
+    #[test]
+    fn avc420_command() {
+        let mut rects = [sys::RECTANGLE_16 {
+            left: 0,
+            top: 0,
+            right: 64,
+            bottom: 32,
+        }];
+        let mut quality = [sys::RDPGFX_H264_QUANT_QUALITY {
+            qpVal: 0x96,
+            qualityVal: 100,
+            qp: 22,
+            r: 0,
+            p: 1,
+        }];
+        let mut data = [0, 0, 0, 1, 0x67];
+        let mut stream = sys::RDPGFX_AVC420_BITMAP_STREAM {
+            meta: sys::RDPGFX_H264_METABLOCK {
+                numRegionRects: 1,
+                regionRects: rects.as_mut_ptr(),
+                quantQualityVals: quality.as_mut_ptr(),
+            },
+            length: data.len() as _,
+            data: data.as_mut_ptr(),
+        };
+        let cmd = sys::RDPGFX_SURFACE_COMMAND {
+            surfaceId: 1,
+            codecId: sys::RDPGFX_CODECID_AVC420,
+            contextId: 0,
+            format: crate::PIXEL_FORMAT_BGRX32.into(),
+            left: 0,
+            top: 0,
+            right: 64,
+            bottom: 32,
+            width: 64,
+            height: 32,
+            length: data.len() as _,
+            data: data.as_mut_ptr(),
+            extra: &mut stream as *mut _ as *mut _,
+        };
+
+        let cmd = SurfaceCommand::new(&cmd).unwrap();
+        assert_eq!(cmd.codec_id, Ok(GfxCodecId::Avc420));
+        assert_eq!(cmd.rect, Rect::new(0, 0, 64, 32));
+        match cmd.stream {
+            Some(SurfaceStream::Avc420(s)) => {
+                assert_eq!(s.rects, [Rect::new(0, 0, 64, 32)]);
+                assert_eq!(
+                    s.quant_quality,
+                    [H264QuantQuality {
+                        qp: 22,
+                        progressive: true,
+                        quality: 100
+                    }]
+                );
+                assert_eq!(s.data, [0, 0, 0, 1, 0x67]);
+            }
+            _ => panic!("no AVC420 stream"),
+        }
+    }
+}
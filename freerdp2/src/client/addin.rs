@@ -0,0 +1,36 @@
+use std::ffi::CStr;
+
+use crate::{
+    client::{dvc, svc},
+    sys,
+};
+
+// Our provider falls back to the static channels, as it replaces the default one.
+pub(super) fn register_provider() {
+    unsafe {
+        sys::freerdp_register_addin_provider(Some(rdp_load_channel_addin_entry), 0);
+    }
+}
+
+extern "C" fn rdp_load_channel_addin_entry(
+    name: sys::LPCSTR,
+    subsystem: sys::LPCSTR,
+    type_: sys::LPCSTR,
+    flags: sys::DWORD,
+) -> sys::PVIRTUALCHANNELENTRY {
+    if !name.is_null() {
+        let rname = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+        let entry = if flags & sys::FREERDP_ADDIN_CHANNEL_DYNAMIC != 0 {
+            dvc::addin_entry(&rname)
+        } else if flags & sys::FREERDP_ADDIN_CHANNEL_ENTRYEX != 0 {
+            svc::addin_entry(&rname)
+        } else {
+            None
+        };
+        if entry.is_some() {
+            return entry;
+        }
+    }
+
+    unsafe { sys::freerdp_channels_load_static_addin_entry(name, subsystem, type_, flags) }
+}
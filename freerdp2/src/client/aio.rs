@@ -0,0 +1,320 @@
+use std::{
+    future::poll_fn,
+    os::{raw::c_void, unix::prelude::RawFd},
+    ptr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    task::Poll,
+    time::Duration,
+};
+
+use tokio::{
+    io::{unix::AsyncFd, Interest},
+    sync::Notify,
+    task,
+};
+
+use crate::{
+    client::{Context, Handler},
+    sys,
+    winpr::{wait_for_multiple_objects, WaitResult},
+    FreeRdp, RdpCode, RdpError, Result,
+};
+
+#[derive(Debug)]
+struct Fd(RawFd);
+
+impl std::os::unix::prelude::AsRawFd for Fd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Shutdown {
+    instance: Mutex<Option<FreeRdp>>,
+    disconnect: AtomicBool,
+    notify: Notify,
+}
+
+impl Shutdown {
+    pub(crate) fn new(instance: *mut sys::freerdp) -> Arc<Self> {
+        Arc::new(Self {
+            instance: Mutex::new(Some(FreeRdp::new(instance))),
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn invalidate(&self) {
+        self.instance.lock().unwrap().take();
+    }
+}
+
+/// A handle to stop a [`Context`] from another task or thread.
+///
+/// The handle may outlive the context, in which case its methods do nothing.
+#[derive(Clone, Debug)]
+pub struct ShutdownHandle {
+    shared: Arc<Shutdown>,
+}
+
+impl ShutdownHandle {
+    /// Abort a pending `connect()` and make the session loop return.
+    pub fn abort_connect(&self) -> Result<()> {
+        let res = match self.shared.instance.lock().unwrap().as_mut() {
+            Some(instance) => instance.abort_connect(),
+            None => Ok(()),
+        };
+        self.shared.notify.notify_one();
+        res
+    }
+
+    /// Ask [`Context::run_async`] to disconnect and return.
+    pub fn disconnect(&self) {
+        self.shared.disconnect.store(true, Ordering::SeqCst);
+        self.shared.notify.notify_one();
+    }
+}
+
+// Abort a connect() still running on the blocking pool when its future is
+// dropped, and wait for it to return since it uses the context.
+struct AbortConnect {
+    handle: ShutdownHandle,
+    done: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl Drop for AbortConnect {
+    fn drop(&mut self) {
+        let (done, cond) = &*self.done;
+        let mut done = done.lock().unwrap();
+        if !*done {
+            let _ = self.handle.abort_connect();
+            while !*done {
+                done = cond.wait(done).unwrap();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct AsyncFds {
+    raw: Vec<RawFd>,
+    fds: Vec<AsyncFd<Fd>>,
+}
+
+impl AsyncFds {
+    fn update(&mut self, mut raw: Vec<RawFd>) -> Result<()> {
+        raw.sort_unstable();
+        raw.dedup();
+        if raw == self.raw {
+            return Ok(());
+        }
+
+        self.fds.clear();
+        for fd in &raw {
+            self.fds
+                .push(AsyncFd::with_interest(Fd(*fd), Interest::READABLE)?);
+        }
+        self.raw = raw;
+        Ok(())
+    }
+
+    async fn readable(&self) -> Result<()> {
+        poll_fn(|cx| {
+            for fd in &self.fds {
+                match fd.poll_read_ready(cx) {
+                    Poll::Ready(Ok(mut guard)) => {
+                        guard.clear_ready();
+                        return Poll::Ready(Ok(()));
+                    }
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e.into())),
+                    Poll::Pending => {}
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl<H: Handler> Context<H> {
+    /// Return a handle that can stop the session from another task.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        ShutdownHandle {
+            shared: self.shutdown.clone(),
+        }
+    }
+
+    /// The file descriptors to watch for the transport and channels.
+    pub fn fds(&self) -> Result<Vec<RawFd>> {
+        const MAX_FDS: usize = 32;
+        let mut rfds: [*mut c_void; MAX_FDS] = [ptr::null_mut(); MAX_FDS];
+        let mut wfds: [*mut c_void; MAX_FDS] = [ptr::null_mut(); MAX_FDS];
+        let mut rcount = 0;
+        let mut wcount = 0;
+
+        unsafe {
+            let context = &self.rdp_context.as_ref().rdp_context;
+            if sys::freerdp_get_fds(
+                context.instance,
+                rfds.as_mut_ptr(),
+                &mut rcount,
+                wfds.as_mut_ptr(),
+                &mut wcount,
+            ) == 0
+            {
+                return Err(RdpError::Failed("freerdp_get_fds() failed".into()));
+            }
+            if sys::freerdp_channels_get_fds(
+                context.channels,
+                context.instance,
+                rfds.as_mut_ptr(),
+                &mut rcount,
+                wfds.as_mut_ptr(),
+                &mut wcount,
+            ) == 0
+            {
+                return Err(RdpError::Failed("freerdp_channels_get_fds() failed".into()));
+            }
+        }
+
+        let mut fds: Vec<RawFd> = rfds[0..rcount as usize]
+            .iter()
+            .map(|fd| *fd as usize as RawFd)
+            .collect();
+        // the channel errors are only checked by check_event_handles()
+        let error = unsafe {
+            sys::GetEventFileDescriptor(sys::getChannelErrorEventHandle(
+                self.rdp_context.as_ptr().cast(),
+            ))
+        };
+        if error >= 0 {
+            fds.push(error);
+        }
+        Ok(fds)
+    }
+
+    /// Connect without blocking the executor.
+    ///
+    /// The TCP, TLS and NLA handshakes of `instance.connect()` run on the
+    /// blocking thread pool, along with the `pre_connect()` and
+    /// `post_connect()` callbacks. [`ShutdownHandle::abort_connect`] makes it
+    /// return early, as does dropping the future.
+    pub async fn connect_async(&mut self) -> Result<()>
+    where
+        H: Send,
+    {
+        self.spawn_instance(FreeRdp::connect).await
+    }
+
+    /// Run `f` on the instance from the blocking thread pool.
+    pub(crate) async fn spawn_instance(&mut self, f: fn(&mut FreeRdp) -> Result<()>) -> Result<()>
+    where
+        H: Send,
+    {
+        let mut instance = FreeRdp::new(self.instance.inner.as_ptr());
+        let guard = AbortConnect {
+            handle: self.shutdown_handle(),
+            done: Arc::new((Mutex::new(false), Condvar::new())),
+        };
+        let done = guard.done.clone();
+
+        let res = task::spawn_blocking(move || {
+            let res = f(&mut instance);
+            let (lock, cond) = &*done;
+            *lock.lock().unwrap() = true;
+            cond.notify_all();
+            res
+        })
+        .await;
+        drop(guard);
+
+        res.map_err(|e| RdpError::Failed(format!("Connection task failed: {}", e)))?
+    }
+
+    // Whether an event handle is still signaled, without waiting.
+    fn events_pending(&self) -> Result<bool> {
+        let handles = self.event_handles()?;
+        let handles: Vec<_> = handles.iter().collect();
+        let res = wait_for_multiple_objects(&handles, false, Some(&Duration::ZERO))?;
+        Ok(!matches!(res, WaitResult::Timeout))
+    }
+
+    /// Drive the connected session until it is disconnected.
+    ///
+    /// This is the asynchronous equivalent of waiting on
+    /// [`Context::event_handles`] and calling [`Context::check_event_handles`].
+    /// The session can be stopped with a [`ShutdownHandle`].
+    pub async fn run_async(&mut self) -> Result<()> {
+        let shared = self.shutdown.clone();
+        let mut fds = AsyncFds::default();
+        let mut pending = false;
+
+        loop {
+            if shared.disconnect.swap(false, Ordering::SeqCst) {
+                break;
+            }
+            if self.instance.shall_disconnect() {
+                break;
+            }
+
+            let delay = self.frame_delay();
+            if !delay.is_zero() {
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = shared.notify.notified() => continue,
+                }
+            }
+
+            if pending {
+                task::yield_now().await;
+            } else {
+                fds.update(self.fds()?)?;
+                tokio::select! {
+                    res = fds.readable() => res?,
+                    _ = shared.notify.notified() => continue,
+                }
+            }
+
+            if !self.check_event_handles() {
+                let code = unsafe { sys::freerdp_get_last_error(self.rdp_context.as_ptr().cast()) };
+                return Err(match code {
+                    0 => RdpError::Failed("freerdp_check_event_handles() failed".into()),
+                    code => RdpError::Code(RdpCode(code)),
+                });
+            }
+            // the readiness is cleared, but a time bounded check may leave
+            // data behind: check again until the handles are idle
+            pending = self.events_pending()?;
+        }
+
+        self.instance.disconnect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopHandler;
+
+    impl Handler for NoopHandler {
+        fn post_connect(&mut self, _context: &mut Context<Self>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_refused() {
+        let mut ctxt = Context::new(NoopHandler);
+        ctxt.settings
+            .set_server_hostname(Some("127.0.0.1"))
+            .unwrap();
+        // nothing listens on the discard port
+        ctxt.settings.set_server_port(9);
+
+        assert!(ctxt.connect_async().await.is_err());
+    }
+}
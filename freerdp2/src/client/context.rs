@@ -3,37 +3,49 @@ use std::{
     mem::{size_of, MaybeUninit},
     os::raw::{c_char, c_int},
     ptr,
+    time::{Duration, Instant},
 };
 
 use crate::{
-    channels,
+    channels::{
+        self,
+        disp::{MonitorFlags, MonitorLayout, Orientation},
+    },
     client::{
-        CliprdrClientContext, DispClientContext, EncomspClientContext, EventChannelConnected,
-        EventChannelDisconnected, GeometryClientContext, PubSub, PubSubHandle, PubSubHandler,
-        RdpeiClientContext, RdpgfxClientContext, VideoClientContext,
+        addin, CliprdrClientContext, DispClientContext, EncomspClientContext,
+        EventChannelConnected, EventChannelDisconnected, FrameThrottle, GeometryClientContext,
+        PubSub, PubSubHandle, PubSubHandler, RailClientContext, RdpeiClientContext, RdpgfxChain,
+        RdpgfxClientContext, VideoClientContext,
     },
     gdi::{self, Gdi},
     graphics::Graphics,
     input::Input,
-    sys,
-    update::Update,
+    primary, sys,
+    update::{self, Update},
     winpr::{self, Handle},
-    FreeRdp, RdpCode, RdpError, Result, Settings,
+    CursorCache, FreeRdp, RdpCode, RdpError, Rect, Result, Settings,
 };
 
 // this struct is allocated from C/freerdp, to improve
 #[repr(C)]
 #[derive(Debug)]
 pub(crate) struct RdpContext<H: Handler> {
-    rdp_context: sys::rdpContext,
+    pub(crate) rdp_context: sys::rdpContext,
     context: ptr::NonNull<Context<H>>,
+    // the callbacks we replaced, zeroed by freerdp
+    pub(crate) update_chain: update::Chain,
+    pub(crate) primary_chain: primary::Chain,
+    pub(crate) secondary_chain: update::SecondaryChain,
+    pub(crate) altsec_chain: update::AltSecChain,
+    pub(crate) pointer_chain: update::PointerChain,
+    pub(crate) rdpgfx_chain: RdpgfxChain,
 }
 
 unsafe impl<H> Send for RdpContext<H> where H: Handler + Send {}
 unsafe impl<H> Sync for RdpContext<H> where H: Handler + Sync {}
 
 impl<H: Handler> RdpContext<H> {
-    fn from_ptr<'a>(ptr: *mut sys::rdpContext) -> &'a mut Self {
+    pub(crate) fn from_ptr<'a>(ptr: *mut sys::rdpContext) -> &'a mut Self {
         let ptr = ptr as *mut Self;
         unsafe { ptr::NonNull::new(ptr).unwrap().as_mut() }
     }
@@ -53,10 +65,23 @@ pub struct Context<H: Handler> {
     pub disp: Option<DispClientContext>,
     pub cliprdr: Option<CliprdrClientContext>,
     pub encomsp: Option<EncomspClientContext>,
-
-    rdp_context: ptr::NonNull<RdpContext<H>>,
+    pub rail: Option<RailClientContext>,
+    pub rdpgfx: Option<RdpgfxClientContext>,
+    /// The pointers received, when a `PointerUpdateHandler` is registered.
+    /// Cleared on disconnection and reconnection.
+    pub cursors: CursorCache,
+    /// Limits the frame rate, counting the GFX frames when an
+    /// `RdpgfxHandler` is registered, and the surface frame markers when an
+    /// `UpdateHandler` is. See `Context::frame_delay()`.
+    pub frame_throttle: Option<FrameThrottle>,
+
+    // the last area given to suppress_output(), updated on resize_display()
+    output_area: Option<Option<Rect>>,
+    pub(crate) rdp_context: ptr::NonNull<RdpContext<H>>,
     default_channel_connected: Option<PubSubHandle>,
     default_channel_disconnected: Option<PubSubHandle>,
+    #[cfg(all(unix, feature = "tokio"))]
+    pub(crate) shutdown: std::sync::Arc<super::aio::Shutdown>,
 }
 
 unsafe impl<H> Send for Context<H> where H: Handler + Send {}
@@ -64,6 +89,10 @@ unsafe impl<H> Sync for Context<H> where H: Handler + Sync {}
 
 impl<H: Handler> Drop for Context<H> {
     fn drop(&mut self) {
+        #[cfg(all(unix, feature = "tokio"))]
+        self.shutdown.invalidate();
+        super::dvc::unregister(self.rdp_context.as_ptr().cast());
+        super::svc::unregister(self.rdp_context.as_ptr().cast());
         unsafe {
             sys::freerdp_client_context_free(self.rdp_context.as_ptr().cast());
         }
@@ -125,11 +154,18 @@ pub trait Handler {
                         context.rdpei = Some(iface);
                     }
                     channels::rdpgfx::DVC_CHANNEL_NAME => {
-                        let iface =
+                        let mut iface =
                             unsafe { RdpgfxClientContext::from_ptr(event.interface as *mut _) };
-                        gdi::gfx::graphics_pipeline_init(&context.gdi().unwrap(), &iface).unwrap()
+                        gdi::gfx::graphics_pipeline_init(&context.gdi().unwrap(), &iface).unwrap();
+                        context.handler.rdpgfx_connected(&mut iface);
+                        context.rdpgfx = Some(iface);
+                    }
+                    channels::rail::SVC_CHANNEL_NAME => {
+                        let mut iface =
+                            unsafe { RailClientContext::from_ptr(event.interface as *mut _, true) };
+                        context.handler.rail_connected(&mut iface);
+                        context.rail = Some(iface);
                     }
-                    channels::rail::SVC_CHANNEL_NAME => {}
                     channels::cliprdr::SVC_CHANNEL_NAME => {
                         let mut iface = unsafe {
                             CliprdrClientContext::from_ptr(event.interface as *mut _, true)
@@ -188,9 +224,13 @@ pub trait Handler {
                     channels::rdpgfx::DVC_CHANNEL_NAME => {
                         let iface =
                             unsafe { RdpgfxClientContext::from_ptr(event.interface as *mut _) };
-                        gdi::gfx::graphics_pipeline_uninit(&context.gdi().unwrap(), &iface).unwrap()
+                        gdi::gfx::graphics_pipeline_uninit(&context.gdi().unwrap(), &iface)
+                            .unwrap();
+                        context.rdpgfx = None;
+                    }
+                    channels::rail::SVC_CHANNEL_NAME => {
+                        context.rail = None;
                     }
-                    channels::rail::SVC_CHANNEL_NAME => {}
                     channels::cliprdr::SVC_CHANNEL_NAME => {
                         context.cliprdr = None;
                     }
@@ -240,6 +280,20 @@ pub trait Handler {
     {
     }
 
+    fn rail_connected(&mut self, _rail: &mut RailClientContext)
+    where
+        Self: Sized,
+    {
+    }
+
+    /// The graphics pipeline is set up, this is the time to register a
+    /// [`crate::client::RdpgfxHandler`].
+    fn rdpgfx_connected(&mut self, _gfx: &mut RdpgfxClientContext)
+    where
+        Self: Sized,
+    {
+    }
+
     fn authenticate(&mut self, _context: &mut Context<Self>) -> Result<()>
     where
         Self: Sized,
@@ -303,6 +357,13 @@ pub trait Handler {
     fn logon_error_info(&mut self, _data: u32, _type: u32) -> i32 {
         1
     }
+
+    /// Called before each attempt of [`Context::auto_reconnect`], an error cancels it.
+    fn reconnecting(&mut self, _attempt: u32) -> Result<()> {
+        Ok(())
+    }
+
+    fn reconnected(&mut self) {}
 }
 
 fn cvt_nz(error: u32) -> Result<()> {
@@ -343,6 +404,15 @@ impl<H: Handler> Context<H> {
         }
     }
 
+    /// How long to wait before checking the event handles, to keep the frame
+    /// rate under `frame_throttle`. `Context::run()` and
+    /// `Context::run_async()` already do.
+    pub fn frame_delay(&self) -> Duration {
+        self.frame_throttle
+            .as_ref()
+            .map_or(Duration::ZERO, |t| t.remaining(Instant::now()))
+    }
+
     pub fn check_event_handles(&mut self) -> bool {
         unsafe { sys::freerdp_check_event_handles(self.rdp_context.as_ptr().cast()) > 0 }
     }
@@ -355,6 +425,15 @@ impl<H: Handler> Context<H> {
         }
     }
 
+    /// The description of the last error, as shown by xfreerdp.
+    pub fn last_error_string(&self) -> Option<String> {
+        match cvt_nz(unsafe { sys::freerdp_get_last_error(self.rdp_context.as_ptr().cast()) as _ })
+        {
+            Err(RdpError::Code(code)) => Some(code.description().to_string()),
+            _ => None,
+        }
+    }
+
     pub fn input(&self) -> Option<Input> {
         let input = unsafe { self.rdp_context.as_ref() }.rdp_context.input;
         if input.is_null() {
@@ -391,7 +470,104 @@ impl<H: Handler> Context<H> {
         }
     }
 
+    /// Stop the graphics updates, with `None`, or resume them for the visible
+    /// `area` of the desktop, when the session is hidden or shown.
+    pub fn suppress_output(&mut self, area: Option<Rect>) -> Result<()> {
+        let update = unsafe { self.rdp_context.as_ref() }.rdp_context.update;
+        let f = unsafe { update.as_ref() }
+            .and_then(|u| u.SuppressOutput)
+            .ok_or(RdpError::Unsupported)?;
+        let rect = area.map(sys::RECTANGLE_16::from);
+        let res = unsafe {
+            f(
+                self.rdp_context.as_ptr().cast(),
+                area.is_some() as _,
+                rect.as_ref().map_or(ptr::null(), |r| r as *const _),
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("SuppressOutput() failed".into()))
+        } else {
+            self.output_area = Some(area);
+            Ok(())
+        }
+    }
+
+    /// Ask the server to repaint `rects`.
+    pub fn refresh_rects(&mut self, rects: &[Rect]) -> Result<()> {
+        let update = unsafe { self.rdp_context.as_ref() }.rdp_context.update;
+        let f = unsafe { update.as_ref() }
+            .and_then(|u| u.RefreshRect)
+            .ok_or(RdpError::Unsupported)?;
+        let rects: Vec<sys::RECTANGLE_16> = rects.iter().map(|r| (*r).into()).collect();
+
+        // the count is a byte
+        for chunk in rects.chunks(u8::MAX as _) {
+            let res = unsafe {
+                f(
+                    self.rdp_context.as_ptr().cast(),
+                    chunk.len() as _,
+                    chunk.as_ptr(),
+                )
+            };
+            if res == 0 {
+                return Err(RdpError::Failed("RefreshRect() failed".into()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Ask the server to repaint the whole desktop.
+    pub fn refresh_desktop(&mut self) -> Result<()> {
+        let rect = Rect {
+            left: 0,
+            top: 0,
+            right: self.settings.desktop_width().try_into()?,
+            bottom: self.settings.desktop_height().try_into()?,
+        };
+        self.refresh_rects(&[rect])
+    }
+
+    /// Ask the server to resize the desktop to a single monitor with the
+    /// display control channel. `UpdateHandler::desktop_resize()` follows.
+    /// The last area given to `suppress_output()` is sent again, clipped to
+    /// the new size, and the new desktop is refreshed unless the output is
+    /// suppressed.
+    pub fn resize_display(&mut self, width: u32, height: u32) -> Result<()> {
+        let scale = |factor: u32| if factor == 0 { 100 } else { factor };
+        let layout = MonitorLayout::new(
+            MonitorFlags::PRIMARY,
+            0,
+            0,
+            width,
+            height,
+            0,
+            0,
+            Orientation::Landscape,
+            scale(self.settings.desktop_scale_factor()),
+            scale(self.settings.device_scale_factor()),
+        );
+
+        self.disp
+            .as_mut()
+            .ok_or(RdpError::Unsupported)?
+            .send_monitor_layout(&[layout])?;
+
+        // the desktop size in the settings is only updated once resized
+        let desktop = Rect::new(0, 0, width.try_into()?, height.try_into()?);
+        match self.output_area {
+            Some(None) => self.suppress_output(None),
+            Some(Some(area)) => {
+                self.suppress_output(Some(area.intersection(&desktop)))?;
+                self.refresh_rects(&[desktop])
+            }
+            None => self.refresh_rects(&[desktop]),
+        }
+    }
+
     fn load_addins(&mut self) -> Result<()> {
+        // another context may have registered the default provider since
+        addin::register_provider();
         unsafe {
             if sys::freerdp_client_load_addins(
                 self.rdp_context.as_ref().rdp_context.channels,
@@ -441,8 +617,15 @@ impl<H: Handler> Context<H> {
             disp: None,
             cliprdr: None,
             encomsp: None,
+            rail: None,
+            rdpgfx: None,
+            cursors: CursorCache::default(),
+            frame_throttle: None,
+            output_area: None,
             default_channel_connected: None,
             default_channel_disconnected: None,
+            #[cfg(all(unix, feature = "tokio"))]
+            shutdown: super::aio::Shutdown::new(rdp_context.instance),
         });
         RdpContext::<H>::from_ptr(ptr).context =
             ptr::NonNull::new(&*res as *const _ as *mut _).unwrap();
@@ -482,6 +665,7 @@ extern "C" fn rdp_instance_post_disconnect<H: Handler>(instance: *mut sys::freer
     let ptr = unsafe { (*instance).context };
     let ctxt = Context::<H>::from_ptr(ptr);
 
+    ctxt.cursors.clear();
     ctxt.handler.post_disconnect(Context::<H>::from_ptr(ptr))
 }
 
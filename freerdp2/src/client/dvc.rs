@@ -0,0 +1,455 @@
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    io,
+    os::raw::c_char,
+    ptr,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::{
+    client::{Context, Handler},
+    settings::typed::{Bool, Key},
+    sys, RdpError, Result,
+};
+
+const CHANNEL_RC_OK: u32 = 0;
+const ERROR_INTERNAL_ERROR: u32 = 1359;
+
+#[derive(Debug)]
+struct ChannelPtr(ptr::NonNull<sys::IWTSVirtualChannel>);
+
+// drdynvc serializes the writes to a channel with its own lock
+unsafe impl Send for ChannelPtr {}
+
+/// A dynamic virtual channel opened by the server.
+///
+/// The handle may be cloned and kept to write from another thread, until the
+/// channel is closed: `write()` then fails with `NotConnected`.
+#[derive(Clone, Debug)]
+pub struct DvcChannel {
+    inner: Arc<Mutex<Option<ChannelPtr>>>,
+}
+
+impl DvcChannel {
+    fn new(channel: ptr::NonNull<sys::IWTSVirtualChannel>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Some(ChannelPtr(channel)))),
+        }
+    }
+
+    fn call(&self, f: impl FnOnce(*mut sys::IWTSVirtualChannel) -> Result<u32>) -> Result<()> {
+        // keep the lock while calling, so the channel isn't closed meanwhile
+        let guard = self.inner.lock().unwrap();
+        let channel = guard
+            .as_ref()
+            .ok_or_else(|| RdpError::IOError(io::ErrorKind::NotConnected.into()))?;
+        match f(channel.0.as_ptr())? {
+            CHANNEL_RC_OK => Ok(()),
+            res => Err(RdpError::IOError(io::Error::from_raw_os_error(res as _))),
+        }
+    }
+
+    pub fn write(&self, data: &[u8]) -> Result<()> {
+        self.call(|channel| {
+            let len = data.len().try_into()?;
+            Ok(unsafe {
+                let f = (*channel).Write.unwrap();
+                f(channel, len, data.as_ptr(), ptr::null_mut())
+            })
+        })
+    }
+
+    pub fn close(&self) -> Result<()> {
+        self.call(|channel| {
+            Ok(unsafe {
+                let f = (*channel).Close.unwrap();
+                f(channel)
+            })
+        })
+    }
+
+    /// Whether the channel is still open.
+    pub fn is_open(&self) -> bool {
+        self.inner.lock().unwrap().is_some()
+    }
+
+    fn invalidate(&self) {
+        self.inner.lock().unwrap().take();
+    }
+}
+
+/// Accepts the instances of a dynamic virtual channel opened by the server.
+pub trait DynamicChannelListener: Send {
+    /// Return `None` to refuse the channel.
+    fn on_new_channel(&mut self, channel: &DvcChannel) -> Option<Box<dyn DynamicChannel>>;
+}
+
+pub trait DynamicChannel: Send {
+    fn on_open(&mut self, _channel: &DvcChannel) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_data_received(&mut self, channel: &DvcChannel, data: &[u8]) -> Result<()>;
+
+    /// The channel is closed, the [`DvcChannel`] handles are already
+    /// invalidated.
+    fn on_close(&mut self) {}
+}
+
+type ListenerFactory = Box<dyn Fn() -> Box<dyn DynamicChannelListener> + Send + Sync>;
+
+// drdynvc loads its plugins by name: keep the factories in a global
+// registry, looked up from our addin provider. The context is given to the
+// plugin as its first argument, so each context has its own factories.
+fn registry() -> &'static Mutex<HashMap<(usize, String), ListenerFactory>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<(usize, String), ListenerFactory>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+pub(super) fn unregister(context: *mut sys::rdpContext) {
+    registry()
+        .lock()
+        .unwrap()
+        .retain(|(c, _), _| *c != context as usize);
+}
+
+#[repr(C)]
+struct Plugin {
+    iface: sys::IWTSPlugin,
+    name: CString,
+    listener: *mut ListenerCallback,
+}
+
+#[repr(C)]
+struct ListenerCallback {
+    iface: sys::IWTSListenerCallback,
+    listener: Box<dyn DynamicChannelListener>,
+}
+
+#[repr(C)]
+struct ChannelCallback {
+    iface: sys::IWTSVirtualChannelCallback,
+    channel: DvcChannel,
+    handler: Box<dyn DynamicChannel>,
+}
+
+impl<H: Handler> Context<H> {
+    /// Load a dynamic virtual channel implemented in Rust.
+    ///
+    /// `name` is both the addin name and the channel name. `factory` is
+    /// called for each connection, to create the channel listener.
+    pub fn add_dynamic_channel<F, L>(&mut self, name: &str, factory: F) -> Result<()>
+    where
+        F: Fn() -> L + Send + Sync + 'static,
+        L: DynamicChannelListener + 'static,
+    {
+        let id = self.rdp_context.as_ptr() as usize;
+        let cname = CString::new(name)?;
+        let cid = CString::new(id.to_string())?;
+        registry()
+            .lock()
+            .unwrap()
+            .insert((id, name.into()), Box::new(move || Box::new(factory())));
+
+        let mut params = [cname.as_ptr() as *mut c_char, cid.as_ptr() as *mut c_char];
+        let res = unsafe {
+            sys::freerdp_client_add_dynamic_channel(
+                self.settings.as_ptr(),
+                params.len(),
+                params.as_mut_ptr(),
+            )
+        };
+        if res == 0 {
+            return Err(RdpError::Failed("Failed to add dynamic channel".into()));
+        }
+        self.settings.set::<Bool>(Key::SupportDynamicChannels, true)
+    }
+}
+
+pub(super) fn addin_entry(name: &str) -> sys::PVIRTUALCHANNELENTRY {
+    if !registry().lock().unwrap().keys().any(|(_, n)| n == name) {
+        return None;
+    }
+    let entry: extern "C" fn(*mut sys::IDRDYNVC_ENTRY_POINTS) -> u32 = rdp_dvc_plugin_entry;
+    unsafe {
+        std::mem::transmute::<
+            extern "C" fn(*mut sys::IDRDYNVC_ENTRY_POINTS) -> u32,
+            sys::PVIRTUALCHANNELENTRY,
+        >(entry)
+    }
+}
+
+extern "C" fn rdp_dvc_plugin_entry(entry_points: *mut sys::IDRDYNVC_ENTRY_POINTS) -> u32 {
+    let ep = unsafe { &*entry_points };
+    let args = unsafe { ep.GetPluginData.unwrap()(entry_points) };
+    if args.is_null() || unsafe { (*args).argc } < 2 {
+        return ERROR_INTERNAL_ERROR;
+    }
+    let (name, id) = unsafe {
+        (
+            CStr::from_ptr(*(*args).argv),
+            CStr::from_ptr(*(*args).argv.add(1)),
+        )
+    };
+    if !unsafe { ep.GetPlugin.unwrap()(entry_points, name.as_ptr()) }.is_null() {
+        return CHANNEL_RC_OK;
+    }
+
+    let key = match id.to_str().ok().and_then(|id| id.parse().ok()) {
+        Some(id) => (id, name.to_string_lossy().into_owned()),
+        None => return ERROR_INTERNAL_ERROR,
+    };
+    let listener = match registry().lock().unwrap().get(&key) {
+        Some(factory) => factory(),
+        None => return ERROR_INTERNAL_ERROR,
+    };
+    let listener = Box::into_raw(Box::new(ListenerCallback {
+        iface: sys::IWTSListenerCallback {
+            OnNewChannelConnection: Some(rdp_on_new_channel_connection),
+        },
+        listener,
+    }));
+    let plugin = Box::into_raw(Box::new(Plugin {
+        iface: sys::IWTSPlugin {
+            Initialize: Some(rdp_plugin_initialize),
+            Connected: None,
+            Disconnected: None,
+            Terminated: Some(rdp_plugin_terminated),
+            Attached: None,
+            Detached: None,
+            pInterface: ptr::null_mut(),
+        },
+        name: name.into(),
+        listener,
+    }));
+
+    let res = unsafe { ep.RegisterPlugin.unwrap()(entry_points, name.as_ptr(), plugin.cast()) };
+    if res != CHANNEL_RC_OK {
+        rdp_plugin_terminated(plugin.cast());
+    }
+    res
+}
+
+extern "C" fn rdp_plugin_initialize(
+    plugin: *mut sys::IWTSPlugin,
+    mgr: *mut sys::IWTSVirtualChannelManager,
+) -> u32 {
+    unsafe {
+        let plugin = &mut *(plugin as *mut Plugin);
+        (*mgr).CreateListener.unwrap()(
+            mgr,
+            plugin.name.as_ptr(),
+            0,
+            plugin.listener.cast(),
+            ptr::null_mut(),
+        )
+    }
+}
+
+extern "C" fn rdp_plugin_terminated(plugin: *mut sys::IWTSPlugin) -> u32 {
+    unsafe {
+        let plugin = Box::from_raw(plugin as *mut Plugin);
+        drop(Box::from_raw(plugin.listener));
+    }
+    CHANNEL_RC_OK
+}
+
+extern "C" fn rdp_on_new_channel_connection(
+    callback: *mut sys::IWTSListenerCallback,
+    channel: *mut sys::IWTSVirtualChannel,
+    _data: *mut u8,
+    accept: *mut sys::BOOL,
+    channel_callback: *mut *mut sys::IWTSVirtualChannelCallback,
+) -> u32 {
+    let callback = unsafe { &mut *(callback as *mut ListenerCallback) };
+    let channel = match ptr::NonNull::new(channel) {
+        Some(channel) => DvcChannel::new(channel),
+        None => return ERROR_INTERNAL_ERROR,
+    };
+
+    match callback.listener.on_new_channel(&channel) {
+        Some(handler) => {
+            let cb = Box::into_raw(Box::new(ChannelCallback {
+                iface: sys::IWTSVirtualChannelCallback {
+                    OnDataReceived: Some(rdp_on_data_received),
+                    OnOpen: Some(rdp_on_open),
+                    OnClose: Some(rdp_on_close),
+                },
+                channel,
+                handler,
+            }));
+            unsafe {
+                *accept = 1;
+                *channel_callback = cb.cast();
+            }
+        }
+        None => unsafe { *accept = 0 },
+    }
+    CHANNEL_RC_OK
+}
+
+extern "C" fn rdp_on_open(callback: *mut sys::IWTSVirtualChannelCallback) -> u32 {
+    let callback = unsafe { &mut *(callback as *mut ChannelCallback) };
+    match callback.handler.on_open(&callback.channel) {
+        Ok(()) => CHANNEL_RC_OK,
+        Err(_) => ERROR_INTERNAL_ERROR,
+    }
+}
+
+extern "C" fn rdp_on_data_received(
+    callback: *mut sys::IWTSVirtualChannelCallback,
+    data: *mut sys::wStream,
+) -> u32 {
+    let callback = unsafe { &mut *(callback as *mut ChannelCallback) };
+    let data = unsafe {
+        let s = &*data;
+        let pos = s.pointer.offset_from(s.buffer) as usize;
+        std::slice::from_raw_parts(s.pointer, s.length.saturating_sub(pos))
+    };
+    match callback.handler.on_data_received(&callback.channel, data) {
+        Ok(()) => CHANNEL_RC_OK,
+        Err(_) => ERROR_INTERNAL_ERROR,
+    }
+}
+
+extern "C" fn rdp_on_close(callback: *mut sys::IWTSVirtualChannelCallback) -> u32 {
+    let mut callback = unsafe { Box::from_raw(callback as *mut ChannelCallback) };
+    callback.channel.invalidate();
+    callback.handler.on_close();
+    CHANNEL_RC_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::raw::c_void, sync::mpsc};
+
+    use super::*;
+
+    // A stand-in for the drdynvc channel, recording the writes.
+    #[repr(C)]
+    struct FakeChannel {
+        iface: sys::IWTSVirtualChannel,
+        written: Mutex<Vec<u8>>,
+    }
+
+    unsafe extern "C" fn fake_write(
+        channel: *mut sys::IWTSVirtualChannel,
+        size: u32,
+        buffer: *const u8,
+        _reserved: *mut c_void,
+    ) -> u32 {
+        let channel = &*(channel as *mut FakeChannel);
+        let data = std::slice::from_raw_parts(buffer, size as _);
+        channel.written.lock().unwrap().extend_from_slice(data);
+        CHANNEL_RC_OK
+    }
+
+    unsafe extern "C" fn fake_close(_channel: *mut sys::IWTSVirtualChannel) -> u32 {
+        CHANNEL_RC_OK
+    }
+
+    struct Echo {
+        channels: mpsc::Sender<DvcChannel>,
+    }
+
+    impl DynamicChannelListener for Echo {
+        fn on_new_channel(&mut self, channel: &DvcChannel) -> Option<Box<dyn DynamicChannel>> {
+            self.channels.send(channel.clone()).unwrap();
+            Some(Box::new(EchoChannel))
+        }
+    }
+
+    struct EchoChannel;
+
+    impl DynamicChannel for EchoChannel {
+        fn on_data_received(&mut self, channel: &DvcChannel, data: &[u8]) -> Result<()> {
+            channel.write(data)
+        }
+    }
+
+    #[test]
+    fn channel_callbacks() {
+        let (tx, rx) = mpsc::channel();
+        let mut listener = ListenerCallback {
+            iface: sys::IWTSListenerCallback {
+                OnNewChannelConnection: Some(rdp_on_new_channel_connection),
+            },
+            listener: Box::new(Echo { channels: tx }),
+        };
+        let mut channel = FakeChannel {
+            iface: sys::IWTSVirtualChannel {
+                Write: Some(fake_write),
+                Close: Some(fake_close),
+            },
+            written: Default::default(),
+        };
+
+        let mut accept = 0;
+        let mut callback = ptr::null_mut();
+        let res = unsafe {
+            listener.iface.OnNewChannelConnection.unwrap()(
+                &mut listener.iface,
+                &mut channel.iface,
+                ptr::null_mut(),
+                &mut accept,
+                &mut callback,
+            )
+        };
+        assert_eq!(res, CHANNEL_RC_OK);
+        assert_eq!(accept, 1);
+        let handle = rx.recv().unwrap();
+
+        let cb = unsafe { &*callback };
+        assert_eq!(unsafe { cb.OnOpen.unwrap()(callback) }, CHANNEL_RC_OK);
+
+        let mut data = *b"ping";
+        let mut stream: sys::wStream = unsafe { std::mem::zeroed() };
+        stream.buffer = data.as_mut_ptr();
+        stream.pointer = data.as_mut_ptr();
+        stream.length = data.len();
+        stream.capacity = data.len();
+        let res = unsafe { cb.OnDataReceived.unwrap()(callback, &mut stream) };
+        assert_eq!(res, CHANNEL_RC_OK);
+
+        // the handle can be used from another thread until the channel closes
+        let writer = handle.clone();
+        std::thread::spawn(move || writer.write(b"pong"))
+            .join()
+            .unwrap()
+            .unwrap();
+        assert!(handle.is_open());
+        assert_eq!(unsafe { cb.OnClose.unwrap()(callback) }, CHANNEL_RC_OK);
+        assert!(!handle.is_open());
+        assert!(handle.write(b"late").is_err());
+
+        assert_eq!(*channel.written.lock().unwrap(), b"pingpong");
+    }
+
+    #[test]
+    fn registry_per_context() {
+        let a = 0x1000 as *mut sys::rdpContext;
+        let b = 0x2000 as *mut sys::rdpContext;
+        let name = "test::registry_per_context";
+        for id in [a, b] {
+            registry().lock().unwrap().insert(
+                (id as usize, name.into()),
+                Box::new(|| {
+                    let (channels, _) = mpsc::channel();
+                    Box::new(Echo { channels })
+                }),
+            );
+        }
+        assert!(addin_entry(name).is_some());
+
+        unregister(a);
+        assert!(!registry()
+            .lock()
+            .unwrap()
+            .contains_key(&(a as usize, name.into())));
+        assert!(addin_entry(name).is_some());
+
+        unregister(b);
+        assert!(addin_entry(name).is_none());
+    }
+}
@@ -1,3 +1,10 @@
+#[cfg(all(unix, feature = "tokio"))]
+mod aio;
+#[cfg(all(unix, feature = "tokio"))]
+pub use aio::*;
+
+mod addin;
+
 mod cliprdr;
 pub use cliprdr::*;
 
@@ -9,18 +16,33 @@ mod custom;
 mod disp;
 pub use disp::*;
 
+mod dvc;
+pub use dvc::*;
+
 mod encomsp;
 pub use encomsp::*;
 
 mod pub_sub;
 pub use pub_sub::*;
 
+mod rail;
+pub use rail::*;
+
 mod rdpei;
 pub use rdpei::*;
 
 mod rdpgfx;
 pub use rdpgfx::*;
 
+mod reconnect;
+pub use reconnect::*;
+
+mod svc;
+pub use svc::*;
+
+mod throttle;
+pub use throttle::*;
+
 mod video;
 pub use video::*;
 
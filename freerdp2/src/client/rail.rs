@@ -0,0 +1,482 @@
+use std::{ffi::CString, ptr};
+
+use crate::{
+    channels::rail::{
+        ClientStatusFlags, ClientSystemParams, Exec, ExecResult, HandshakeExFlags, LocalMoveSize,
+        MinMaxInfo, ServerSystemParam, SysCommand, CLIENT_BUILD_NUMBER,
+    },
+    client::custom::Custom,
+    sys, RdpError, Result,
+};
+
+#[derive(Debug)]
+pub struct RailClientContext {
+    pub(crate) inner: ptr::NonNull<sys::RailClientContext>,
+    owned: bool,
+}
+
+unsafe impl Send for RailClientContext {}
+unsafe impl Sync for RailClientContext {}
+
+impl Drop for RailClientContext {
+    fn drop(&mut self) {
+        if !self.owned {
+            return;
+        }
+        unsafe {
+            let inner = self.inner.as_mut();
+            if !inner.custom.is_null() {
+                drop(Box::from_raw(inner.custom as *mut Custom));
+                inner.custom = ptr::null_mut();
+            }
+        }
+    }
+}
+
+pub trait RailHandler {
+    /// The server handshake. The channel answers with its own handshake,
+    /// this is the time to send the client information and system parameters.
+    fn server_handshake(&mut self, _context: &mut RailClientContext, _build: u32) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_handshake_ex(
+        &mut self,
+        _context: &mut RailClientContext,
+        _build: u32,
+        _flags: HandshakeExFlags,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_exec_result(
+        &mut self,
+        _context: &mut RailClientContext,
+        _result: &ExecResult,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_system_param(
+        &mut self,
+        _context: &mut RailClientContext,
+        _param: ServerSystemParam,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_min_max_info(
+        &mut self,
+        _context: &mut RailClientContext,
+        _info: &MinMaxInfo,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_local_move_size(
+        &mut self,
+        _context: &mut RailClientContext,
+        _move_size: &LocalMoveSize,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_language_bar_info(
+        &mut self,
+        _context: &mut RailClientContext,
+        _status: u32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_zorder_sync(
+        &mut self,
+        _context: &mut RailClientContext,
+        _window_id_marker: u32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_cloak(
+        &mut self,
+        _context: &mut RailClientContext,
+        _window_id: u32,
+        _cloak: bool,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn result(res: u32) -> Result<()> {
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(RdpError::IOError(std::io::Error::from_raw_os_error(
+            res as _,
+        )))
+    }
+}
+
+impl RailClientContext {
+    /// # Safety
+    ///
+    /// * The memory pointed to by `ctxt` must contain a valid pointer.
+    /// * `ctxt` must be [valid] for both reads and writes for the whole lifetime `'a` FIXME.
+    pub unsafe fn from_ptr(ctxt: *mut sys::RailClientContext, owned: bool) -> Self {
+        Self {
+            inner: ptr::NonNull::new(ctxt).unwrap(),
+            owned,
+        }
+    }
+
+    pub fn register_handler<H: RailHandler>(&mut self, handler: H) {
+        let inner = unsafe { self.inner.as_mut() };
+        assert!(inner.custom.is_null());
+        inner.ServerHandshake = Some(rdp_rail_server_handshake::<H>);
+        inner.ServerHandshakeEx = Some(rdp_rail_server_handshake_ex::<H>);
+        inner.ServerExecuteResult = Some(rdp_rail_server_execute_result::<H>);
+        inner.ServerSystemParam = Some(rdp_rail_server_system_param::<H>);
+        inner.ServerMinMaxInfo = Some(rdp_rail_server_min_max_info::<H>);
+        inner.ServerLocalMoveSize = Some(rdp_rail_server_local_move_size::<H>);
+        inner.ServerLanguageBarInfo = Some(rdp_rail_server_language_bar_info::<H>);
+        inner.ServerZOrderSync = Some(rdp_rail_server_zorder_sync::<H>);
+        inner.ServerCloak = Some(rdp_rail_server_cloak::<H>);
+        inner.custom = Custom::new(handler);
+    }
+
+    // should be safe as long as inner.custom is set only once
+    unsafe fn handler<'a, H: RailHandler>(&mut self) -> &'a mut H {
+        let custom = (self.inner.as_mut().custom as *mut Custom)
+            .as_mut()
+            .unwrap();
+        (custom.handler as *mut H).as_mut().unwrap()
+    }
+
+    /// Send the client handshake, FreeRDP already does it when the server
+    /// handshake is received.
+    pub fn send_handshake(&mut self) -> Result<()> {
+        let handshake = sys::RAIL_HANDSHAKE_ORDER {
+            buildNumber: CLIENT_BUILD_NUMBER,
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientHandshake.unwrap();
+            f(self.inner.as_ptr(), &handshake)
+        };
+        result(res)
+    }
+
+    pub fn send_client_information(&mut self, flags: ClientStatusFlags) -> Result<()> {
+        let status = sys::RAIL_CLIENT_STATUS_ORDER {
+            flags: flags.bits(),
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientInformation.unwrap();
+            f(self.inner.as_ptr(), &status)
+        };
+        result(res)
+    }
+
+    /// Send the system parameters that are set, one order each.
+    pub fn send_client_system_params(&mut self, params: &ClientSystemParams) -> Result<()> {
+        let sysparam = params.to_order();
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientSystemParam.unwrap();
+            f(self.inner.as_ptr(), &sysparam)
+        };
+        result(res)
+    }
+
+    /// Launch a remote application, the result is given to
+    /// [`RailHandler::server_exec_result`].
+    pub fn send_exec(&mut self, exec: &Exec) -> Result<()> {
+        let program = CString::new(exec.program.as_str())?;
+        let working_dir = exec.working_dir.as_deref().map(CString::new).transpose()?;
+        let arguments = exec.arguments.as_deref().map(CString::new).transpose()?;
+
+        let order = sys::RAIL_EXEC_ORDER {
+            flags: exec.flags.bits(),
+            RemoteApplicationProgram: program.as_ptr() as *mut _,
+            RemoteApplicationWorkingDir: working_dir
+                .as_ref()
+                .map_or(ptr::null_mut(), |s| s.as_ptr() as *mut _),
+            RemoteApplicationArguments: arguments
+                .as_ref()
+                .map_or(ptr::null_mut(), |s| s.as_ptr() as *mut _),
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientExecute.unwrap();
+            f(self.inner.as_ptr(), &order)
+        };
+        result(res)
+    }
+
+    pub fn send_activate(&mut self, window_id: u32, enabled: bool) -> Result<()> {
+        let activate = sys::RAIL_ACTIVATE_ORDER {
+            windowId: window_id,
+            enabled: enabled as _,
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientActivate.unwrap();
+            f(self.inner.as_ptr(), &activate)
+        };
+        result(res)
+    }
+
+    pub fn send_system_command(&mut self, window_id: u32, command: SysCommand) -> Result<()> {
+        let syscommand = sys::RAIL_SYSCOMMAND_ORDER {
+            windowId: window_id,
+            command: command as _,
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientSystemCommand.unwrap();
+            f(self.inner.as_ptr(), &syscommand)
+        };
+        result(res)
+    }
+
+    /// Show the system menu of a window at the given position.
+    pub fn send_system_menu(&mut self, window_id: u32, left: i16, top: i16) -> Result<()> {
+        let sysmenu = sys::RAIL_SYSMENU_ORDER {
+            windowId: window_id,
+            left,
+            top,
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientSystemMenu.unwrap();
+            f(self.inner.as_ptr(), &sysmenu)
+        };
+        result(res)
+    }
+
+    /// Report the new position of a window, typically at the end of a local
+    /// move or resize.
+    pub fn send_window_move(
+        &mut self,
+        window_id: u32,
+        left: i16,
+        top: i16,
+        right: i16,
+        bottom: i16,
+    ) -> Result<()> {
+        let window_move = sys::RAIL_WINDOW_MOVE_ORDER {
+            windowId: window_id,
+            left,
+            top,
+            right,
+            bottom,
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientWindowMove.unwrap();
+            f(self.inner.as_ptr(), &window_move)
+        };
+        result(res)
+    }
+
+    /// Forward a notification icon event, `message` is one of
+    /// [`crate::channels::rail::notify`].
+    pub fn send_notify_event(
+        &mut self,
+        window_id: u32,
+        notify_icon_id: u32,
+        message: u32,
+    ) -> Result<()> {
+        let event = sys::RAIL_NOTIFY_EVENT_ORDER {
+            windowId: window_id,
+            notifyIconId: notify_icon_id,
+            message,
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientNotifyEvent.unwrap();
+            f(self.inner.as_ptr(), &event)
+        };
+        result(res)
+    }
+
+    pub fn send_language_bar_info(&mut self, status: u32) -> Result<()> {
+        let info = sys::RAIL_LANGBAR_INFO_ORDER {
+            languageBarStatus: status,
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientLanguageBarInfo.unwrap();
+            f(self.inner.as_ptr(), &info)
+        };
+        result(res)
+    }
+
+    pub fn send_cloak(&mut self, window_id: u32, cloak: bool) -> Result<()> {
+        let cloak = sys::RAIL_CLOAK {
+            windowId: window_id,
+            cloak: cloak as _,
+        };
+        let res = unsafe {
+            let f = self.inner.as_ref().ClientCloak.unwrap();
+            f(self.inner.as_ptr(), &cloak)
+        };
+        result(res)
+    }
+}
+
+extern "C" fn rdp_rail_server_handshake<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    handshake: *const sys::RAIL_HANDSHAKE_ORDER,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let build = unsafe { (*handshake).buildNumber };
+
+    if handler.server_handshake(&mut ctxt, build).is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_rail_server_handshake_ex<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    handshake: *const sys::RAIL_HANDSHAKE_EX_ORDER,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let handshake = unsafe { handshake.as_ref().unwrap() };
+    let flags = HandshakeExFlags::from_bits_truncate(handshake.railHandshakeFlags);
+
+    if handler
+        .server_handshake_ex(&mut ctxt, handshake.buildNumber, flags)
+        .is_ok()
+    {
+        0
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_rail_server_execute_result<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    exec_result: *const sys::RAIL_EXEC_RESULT_ORDER,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let exec_result = ExecResult::new(unsafe { exec_result.as_ref().unwrap() });
+
+    if handler.server_exec_result(&mut ctxt, &exec_result).is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_rail_server_system_param<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    sysparam: *const sys::RAIL_SYSPARAM_ORDER,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let param = ServerSystemParam::new(unsafe { sysparam.as_ref().unwrap() });
+
+    if handler.server_system_param(&mut ctxt, param).is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_rail_server_min_max_info<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    info: *const sys::RAIL_MINMAXINFO_ORDER,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let info = MinMaxInfo::from(unsafe { info.as_ref().unwrap() });
+
+    if handler.server_min_max_info(&mut ctxt, &info).is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_rail_server_local_move_size<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    move_size: *const sys::RAIL_LOCALMOVESIZE_ORDER,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let move_size = LocalMoveSize::from(unsafe { move_size.as_ref().unwrap() });
+
+    if handler
+        .server_local_move_size(&mut ctxt, &move_size)
+        .is_ok()
+    {
+        0
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_rail_server_language_bar_info<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    info: *const sys::RAIL_LANGBAR_INFO_ORDER,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let status = unsafe { (*info).languageBarStatus };
+
+    if handler.server_language_bar_info(&mut ctxt, status).is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_rail_server_zorder_sync<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    zorder: *const sys::RAIL_ZORDER_SYNC,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let marker = unsafe { (*zorder).windowIdMarker };
+
+    if handler.server_zorder_sync(&mut ctxt, marker).is_ok() {
+        0
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_rail_server_cloak<H: RailHandler>(
+    context: *mut sys::RailClientContext,
+    cloak: *const sys::RAIL_CLOAK,
+) -> u32 {
+    let mut ctxt = unsafe { RailClientContext::from_ptr(context, false) };
+    let handler = unsafe { ctxt.handler::<H>() };
+    let cloak = unsafe { cloak.as_ref().unwrap() };
+
+    if handler
+        .server_cloak(&mut ctxt, cloak.windowId, cloak.cloak != 0)
+        .is_ok()
+    {
+        0
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rect;
+
+    #[test]
+    fn sysparams() {
+        let params = ClientSystemParams {
+            mouse_button_swap: Some(true),
+            work_area: Some(Rect::new(0, 0, 1024, 768)),
+            ..Default::default()
+        };
+        let order = params.to_order();
+        assert_eq!(order.params, 0x0018);
+        assert_eq!(order.mouseButtonSwap, 1);
+        assert_eq!(order.workArea.right, 1024);
+        assert_eq!(order.workArea.bottom, 768);
+    }
+}
@@ -1,11 +1,206 @@
-use std::ptr;
+use std::{ptr, slice, time::Instant};
 
-use crate::sys;
+use crate::{
+    channels::rdpgfx::{
+        CacheImportReply, CacheToSurface, CreateSurface, DeleteSurface, EndFrame, EvictCacheEntry,
+        GfxCapabilitySet, MapSurfaceToOutput, MapSurfaceToScaledOutput, MapSurfaceToScaledWindow,
+        MapSurfaceToWindow, QoeFrameAcknowledge, QueueDepth, ResetGraphics, SolidFill, StartFrame,
+        SurfaceCommand, SurfaceToCache, SurfaceToSurface,
+    },
+    client::{Context, Handler, RdpContext},
+    sys, RdpError, Result,
+};
 
+const CHANNEL_RC_OK: u32 = 0;
+const ERROR_INTERNAL_ERROR: u32 = 1359;
+
+// The callbacks set before ours, by gdi::gfx.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct RdpgfxChain {
+    reset_graphics: sys::pcRdpgfxResetGraphics,
+    start_frame: sys::pcRdpgfxStartFrame,
+    end_frame: sys::pcRdpgfxEndFrame,
+    surface_command: sys::pcRdpgfxSurfaceCommand,
+    create_surface: sys::pcRdpgfxCreateSurface,
+    delete_surface: sys::pcRdpgfxDeleteSurface,
+    solid_fill: sys::pcRdpgfxSolidFill,
+    surface_to_surface: sys::pcRdpgfxSurfaceToSurface,
+    surface_to_cache: sys::pcRdpgfxSurfaceToCache,
+    cache_to_surface: sys::pcRdpgfxCacheToSurface,
+    cache_import_reply: sys::pcRdpgfxCacheImportReply,
+    evict_cache_entry: sys::pcRdpgfxEvictCacheEntry,
+    map_surface_to_output: sys::pcRdpgfxMapSurfaceToOutput,
+    map_surface_to_scaled_output: sys::pcRdpgfxMapSurfaceToScaledOutput,
+    map_surface_to_window: sys::pcRdpgfxMapSurfaceToWindow,
+    map_surface_to_scaled_window: sys::pcRdpgfxMapSurfaceToScaledWindow,
+    caps_advertise: sys::pcRdpgfxCapsAdvertise,
+    caps_confirm: sys::pcRdpgfxCapsConfirm,
+}
+
+#[derive(Debug)]
 pub struct RdpgfxClientContext {
     pub(crate) inner: ptr::NonNull<sys::RdpgfxClientContext>,
 }
 
+unsafe impl Send for RdpgfxClientContext {}
+unsafe impl Sync for RdpgfxClientContext {}
+
+/// What to do once the handler has seen a PDU.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GfxAction {
+    /// Call the previous callback, the GDI implementation of `gdi::gfx`.
+    Chain,
+    /// Stop there, the handler replaces the GDI. The GDI surfaces and cache
+    /// aren't updated, so a handler usually replaces all of the PDUs.
+    Replace,
+}
+
+pub trait RdpgfxHandler {
+    type ContextHandler: Handler;
+
+    /// Edit the capability sets advertised to the server, from the settings,
+    /// the server picks one. At most `RDPGFX_NUMBER_CAPSETS` sets are sent.
+    fn caps_advertise(
+        _context: &mut Context<Self::ContextHandler>,
+        _caps: &mut Vec<GfxCapabilitySet>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// The capability set the server picked.
+    fn caps_confirm(
+        _context: &mut Context<Self::ContextHandler>,
+        _caps: &GfxCapabilitySet,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn reset_graphics(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &ResetGraphics,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn start_frame(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &StartFrame,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    /// The end of a frame. FreeRDP acknowledges it once this returns,
+    /// whatever the result, there is no way to turn it off.
+    fn end_frame(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &EndFrame,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    /// A WireToSurface PDU, `GfxAction::Replace` skips its decoding.
+    fn surface_command(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &SurfaceCommand,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn create_surface(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &CreateSurface,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn delete_surface(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &DeleteSurface,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn solid_fill(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &SolidFill,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn surface_to_surface(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &SurfaceToSurface,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn surface_to_cache(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &SurfaceToCache,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn cache_to_surface(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &CacheToSurface,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn cache_import_reply(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &CacheImportReply,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn evict_cache_entry(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &EvictCacheEntry,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn map_surface_to_output(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &MapSurfaceToOutput,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn map_surface_to_scaled_output(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &MapSurfaceToScaledOutput,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn map_surface_to_window(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &MapSurfaceToWindow,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+
+    fn map_surface_to_scaled_window(
+        _context: &mut Context<Self::ContextHandler>,
+        _pdu: &MapSurfaceToScaledWindow,
+    ) -> Result<GfxAction> {
+        Ok(GfxAction::Chain)
+    }
+}
+
+// gdi::gfx keeps the rdpGdi in the custom field, which leads to our context.
+fn rdp_context(gfx: *mut sys::RdpgfxClientContext) -> Option<*mut sys::rdpContext> {
+    let gdi = unsafe { gfx.as_ref() }?.custom as *mut sys::rdpGdi;
+    unsafe { gdi.as_ref() }.map(|gdi| gdi.context)
+}
+
+fn chain<H: Handler>(gfx: *mut sys::RdpgfxClientContext) -> Option<RdpgfxChain> {
+    rdp_context(gfx).map(|context| RdpContext::<H>::from_ptr(context).rdpgfx_chain)
+}
+
 impl RdpgfxClientContext {
     /// # Safety
     ///
@@ -16,4 +211,324 @@ impl RdpgfxClientContext {
             inner: ptr::NonNull::new(ctxt).unwrap(),
         }
     }
+
+    /// Observe or replace the graphics pipeline, after
+    /// `gdi::gfx::graphics_pipeline_init()` and before the channel is opened,
+    /// see `Handler::rdpgfx_connected()`. The GDI callbacks are called when
+    /// the handler returns `GfxAction::Chain`.
+    pub fn register<H: RdpgfxHandler>(&mut self) -> Result<()> {
+        let context = rdp_context(self.inner.as_ptr()).ok_or(RdpError::Unsupported)?;
+        let inner = unsafe { self.inner.as_mut() };
+
+        let chain = &mut RdpContext::<H::ContextHandler>::from_ptr(context).rdpgfx_chain;
+        chain.reset_graphics = inner.ResetGraphics;
+        chain.start_frame = inner.StartFrame;
+        chain.end_frame = inner.EndFrame;
+        chain.surface_command = inner.SurfaceCommand;
+        chain.create_surface = inner.CreateSurface;
+        chain.delete_surface = inner.DeleteSurface;
+        chain.solid_fill = inner.SolidFill;
+        chain.surface_to_surface = inner.SurfaceToSurface;
+        chain.surface_to_cache = inner.SurfaceToCache;
+        chain.cache_to_surface = inner.CacheToSurface;
+        chain.cache_import_reply = inner.CacheImportReply;
+        chain.evict_cache_entry = inner.EvictCacheEntry;
+        chain.map_surface_to_output = inner.MapSurfaceToOutput;
+        chain.map_surface_to_scaled_output = inner.MapSurfaceToScaledOutput;
+        chain.map_surface_to_window = inner.MapSurfaceToWindow;
+        chain.map_surface_to_scaled_window = inner.MapSurfaceToScaledWindow;
+        chain.caps_advertise = inner.CapsAdvertise;
+        chain.caps_confirm = inner.CapsConfirm;
+
+        inner.ResetGraphics = Some(rdp_gfx_reset_graphics::<H>);
+        inner.StartFrame = Some(rdp_gfx_start_frame::<H>);
+        inner.EndFrame = Some(rdp_gfx_end_frame::<H>);
+        inner.SurfaceCommand = Some(rdp_gfx_surface_command::<H>);
+        inner.CreateSurface = Some(rdp_gfx_create_surface::<H>);
+        inner.DeleteSurface = Some(rdp_gfx_delete_surface::<H>);
+        inner.SolidFill = Some(rdp_gfx_solid_fill::<H>);
+        inner.SurfaceToSurface = Some(rdp_gfx_surface_to_surface::<H>);
+        inner.SurfaceToCache = Some(rdp_gfx_surface_to_cache::<H>);
+        inner.CacheToSurface = Some(rdp_gfx_cache_to_surface::<H>);
+        inner.CacheImportReply = Some(rdp_gfx_cache_import_reply::<H>);
+        inner.EvictCacheEntry = Some(rdp_gfx_evict_cache_entry::<H>);
+        inner.MapSurfaceToOutput = Some(rdp_gfx_map_surface_to_output::<H>);
+        inner.MapSurfaceToScaledOutput = Some(rdp_gfx_map_surface_to_scaled_output::<H>);
+        inner.MapSurfaceToWindow = Some(rdp_gfx_map_surface_to_window::<H>);
+        inner.MapSurfaceToScaledWindow = Some(rdp_gfx_map_surface_to_scaled_window::<H>);
+        inner.CapsAdvertise = Some(rdp_gfx_caps_advertise::<H>);
+        inner.CapsConfirm = Some(rdp_gfx_caps_confirm::<H>);
+        Ok(())
+    }
+
+    /// Send an extra frame acknowledgement. A handler can't acknowledge the
+    /// frames itself: FreeRDP acknowledges each of them once
+    /// `RdpgfxHandler::end_frame()` returns, with an unavailable queue depth,
+    /// so `queue_depth` only holds until the end of the next frame.
+    pub fn send_frame_acknowledge(
+        &mut self,
+        frame_id: u32,
+        total_frames_decoded: u32,
+        queue_depth: QueueDepth,
+    ) -> Result<()> {
+        let ack = sys::RDPGFX_FRAME_ACKNOWLEDGE_PDU {
+            queueDepth: queue_depth.into(),
+            frameId: frame_id,
+            totalFramesDecoded: total_frames_decoded,
+        };
+        let res = unsafe {
+            let f = self
+                .inner
+                .as_ref()
+                .FrameAcknowledge
+                .ok_or(RdpError::Unsupported)?;
+            f(self.inner.as_ptr(), &ack)
+        };
+        result(res)
+    }
+
+    /// Report the decoding and rendering times of a frame, if
+    /// `Settings::gfx_send_qoe_ack()` is set.
+    pub fn send_qoe_frame_acknowledge(&mut self, ack: &QoeFrameAcknowledge) -> Result<()> {
+        let ack = ack.into();
+        let res = unsafe {
+            let f = self
+                .inner
+                .as_ref()
+                .QoeFrameAcknowledge
+                .ok_or(RdpError::Unsupported)?;
+            f(self.inner.as_ptr(), &ack)
+        };
+        result(res)
+    }
+}
+
+fn result(res: u32) -> Result<()> {
+    if res == CHANNEL_RC_OK {
+        Ok(())
+    } else {
+        Err(RdpError::IOError(std::io::Error::from_raw_os_error(
+            res as _,
+        )))
+    }
+}
+
+macro_rules! callback {
+    ($name:ident, $method:ident, $sys:ty, $pdu:ty) => {
+        extern "C" fn $name<H: RdpgfxHandler>(
+            gfx: *mut sys::RdpgfxClientContext,
+            pdu: *const $sys,
+        ) -> u32 {
+            let (context, chain) = match (rdp_context(gfx), chain::<H::ContextHandler>(gfx)) {
+                (Some(context), Some(chain)) => (context, chain),
+                _ => return ERROR_INTERNAL_ERROR,
+            };
+            let ctxt = Context::from_ptr(context);
+            let p = <$pdu>::from(unsafe { pdu.as_ref() }.unwrap());
+
+            match H::$method(ctxt, &p) {
+                Ok(GfxAction::Chain) => {}
+                Ok(GfxAction::Replace) => return CHANNEL_RC_OK,
+                Err(_) => return ERROR_INTERNAL_ERROR,
+            }
+            match chain.$method {
+                Some(f) => unsafe { f(gfx, pdu) },
+                None => CHANNEL_RC_OK,
+            }
+        }
+    };
+}
+
+callback!(
+    rdp_gfx_reset_graphics,
+    reset_graphics,
+    sys::RDPGFX_RESET_GRAPHICS_PDU,
+    ResetGraphics
+);
+callback!(
+    rdp_gfx_start_frame,
+    start_frame,
+    sys::RDPGFX_START_FRAME_PDU,
+    StartFrame
+);
+callback!(
+    rdp_gfx_handle_end_frame,
+    end_frame,
+    sys::RDPGFX_END_FRAME_PDU,
+    EndFrame
+);
+
+// counts the frame for the throttle, whatever the handler returns
+extern "C" fn rdp_gfx_end_frame<H: RdpgfxHandler>(
+    gfx: *mut sys::RdpgfxClientContext,
+    pdu: *const sys::RDPGFX_END_FRAME_PDU,
+) -> u32 {
+    let res = rdp_gfx_handle_end_frame::<H>(gfx, pdu);
+    if let Some(context) = rdp_context(gfx) {
+        let ctxt = Context::<H::ContextHandler>::from_ptr(context);
+        if let Some(throttle) = ctxt.frame_throttle.as_mut() {
+            throttle.end_frame(Instant::now());
+        }
+    }
+    res
+}
+callback!(
+    rdp_gfx_create_surface,
+    create_surface,
+    sys::RDPGFX_CREATE_SURFACE_PDU,
+    CreateSurface
+);
+callback!(
+    rdp_gfx_delete_surface,
+    delete_surface,
+    sys::RDPGFX_DELETE_SURFACE_PDU,
+    DeleteSurface
+);
+callback!(
+    rdp_gfx_solid_fill,
+    solid_fill,
+    sys::RDPGFX_SOLID_FILL_PDU,
+    SolidFill
+);
+callback!(
+    rdp_gfx_surface_to_surface,
+    surface_to_surface,
+    sys::RDPGFX_SURFACE_TO_SURFACE_PDU,
+    SurfaceToSurface
+);
+callback!(
+    rdp_gfx_surface_to_cache,
+    surface_to_cache,
+    sys::RDPGFX_SURFACE_TO_CACHE_PDU,
+    SurfaceToCache
+);
+callback!(
+    rdp_gfx_cache_to_surface,
+    cache_to_surface,
+    sys::RDPGFX_CACHE_TO_SURFACE_PDU,
+    CacheToSurface
+);
+callback!(
+    rdp_gfx_cache_import_reply,
+    cache_import_reply,
+    sys::RDPGFX_CACHE_IMPORT_REPLY_PDU,
+    CacheImportReply
+);
+callback!(
+    rdp_gfx_evict_cache_entry,
+    evict_cache_entry,
+    sys::RDPGFX_EVICT_CACHE_ENTRY_PDU,
+    EvictCacheEntry
+);
+callback!(
+    rdp_gfx_map_surface_to_output,
+    map_surface_to_output,
+    sys::RDPGFX_MAP_SURFACE_TO_OUTPUT_PDU,
+    MapSurfaceToOutput
+);
+callback!(
+    rdp_gfx_map_surface_to_scaled_output,
+    map_surface_to_scaled_output,
+    sys::RDPGFX_MAP_SURFACE_TO_SCALED_OUTPUT_PDU,
+    MapSurfaceToScaledOutput
+);
+callback!(
+    rdp_gfx_map_surface_to_window,
+    map_surface_to_window,
+    sys::RDPGFX_MAP_SURFACE_TO_WINDOW_PDU,
+    MapSurfaceToWindow
+);
+callback!(
+    rdp_gfx_map_surface_to_scaled_window,
+    map_surface_to_scaled_window,
+    sys::RDPGFX_MAP_SURFACE_TO_SCALED_WINDOW_PDU,
+    MapSurfaceToScaledWindow
+);
+
+// hand-written, the surface command may not parse
+extern "C" fn rdp_gfx_surface_command<H: RdpgfxHandler>(
+    gfx: *mut sys::RdpgfxClientContext,
+    cmd: *const sys::RDPGFX_SURFACE_COMMAND,
+) -> u32 {
+    let (context, chain) = match (rdp_context(gfx), chain::<H::ContextHandler>(gfx)) {
+        (Some(context), Some(chain)) => (context, chain),
+        _ => return ERROR_INTERNAL_ERROR,
+    };
+    let ctxt = Context::from_ptr(context);
+    let command = match SurfaceCommand::new(unsafe { cmd.as_ref() }.unwrap()) {
+        Ok(command) => command,
+        Err(_) => return ERROR_INTERNAL_ERROR,
+    };
+
+    match H::surface_command(ctxt, &command) {
+        Ok(GfxAction::Chain) => {}
+        Ok(GfxAction::Replace) => return CHANNEL_RC_OK,
+        Err(_) => return ERROR_INTERNAL_ERROR,
+    }
+    match chain.surface_command {
+        Some(f) => unsafe { f(gfx, cmd) },
+        None => CHANNEL_RC_OK,
+    }
+}
+
+extern "C" fn rdp_gfx_caps_advertise<H: RdpgfxHandler>(
+    gfx: *mut sys::RdpgfxClientContext,
+    advertise: *const sys::RDPGFX_CAPS_ADVERTISE_PDU,
+) -> u32 {
+    let (context, chain) = match (rdp_context(gfx), chain::<H::ContextHandler>(gfx)) {
+        (Some(context), Some(chain)) => (context, chain),
+        _ => return ERROR_INTERNAL_ERROR,
+    };
+    let ctxt = Context::from_ptr(context);
+    // the sets are in an array of RDPGFX_NUMBER_CAPSETS owned by the channel,
+    // edited in place
+    let pdu = unsafe { (advertise as *mut sys::RDPGFX_CAPS_ADVERTISE_PDU).as_mut() }.unwrap();
+    if pdu.capsSets.is_null() {
+        return ERROR_INTERNAL_ERROR;
+    }
+    let max = sys::RDPGFX_NUMBER_CAPSETS as usize;
+    let count = (pdu.capsSetCount as usize).min(max);
+    let mut caps: Vec<_> = unsafe { slice::from_raw_parts(pdu.capsSets, count) }
+        .iter()
+        .filter_map(|c| GfxCapabilitySet::try_from(c).ok())
+        .collect();
+
+    if H::caps_advertise(ctxt, &mut caps).is_err() {
+        return ERROR_INTERNAL_ERROR;
+    }
+    caps.truncate(max);
+    for (i, caps) in caps.iter().enumerate() {
+        unsafe { pdu.capsSets.add(i).write(caps.into()) };
+    }
+    pdu.capsSetCount = caps.len() as _;
+
+    match chain.caps_advertise {
+        Some(f) => unsafe { f(gfx, advertise) },
+        None => CHANNEL_RC_OK,
+    }
+}
+
+extern "C" fn rdp_gfx_caps_confirm<H: RdpgfxHandler>(
+    gfx: *mut sys::RdpgfxClientContext,
+    confirm: *const sys::RDPGFX_CAPS_CONFIRM_PDU,
+) -> u32 {
+    let (context, chain) = match (rdp_context(gfx), chain::<H::ContextHandler>(gfx)) {
+        (Some(context), Some(chain)) => (context, chain),
+        _ => return ERROR_INTERNAL_ERROR,
+    };
+    let ctxt = Context::from_ptr(context);
+    let caps = match unsafe { confirm.as_ref().and_then(|c| c.capsSet.as_ref()) }
+        .map(GfxCapabilitySet::try_from)
+    {
+        Some(Ok(caps)) => caps,
+        _ => return ERROR_INTERNAL_ERROR,
+    };
+
+    if H::caps_confirm(ctxt, &caps).is_err() {
+        return ERROR_INTERNAL_ERROR;
+    }
+    match chain.caps_confirm {
+        Some(f) => unsafe { f(gfx, confirm) },
+        None => CHANNEL_RC_OK,
+    }
 }
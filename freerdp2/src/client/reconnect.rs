@@ -0,0 +1,312 @@
+use std::time::Duration;
+
+use crate::{
+    client::{Context, Handler},
+    settings::typed::{Bool, Key, UInt32},
+    winpr::wait_for_multiple_objects,
+    RdpErr, RdpErrInfo, RdpError, Result,
+};
+
+/// How [`Context::auto_reconnect`] retries after a network drop.
+#[derive(Clone, Debug)]
+pub struct ReconnectPolicy {
+    /// The number of attempts, the `AutoReconnectMaxRetries` setting when
+    /// `None`. 0 retries until the handler's `reconnecting()` fails.
+    pub max_retries: Option<u32>,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: None,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// The delay to wait before the given attempt, starting from 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .checked_pow(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |d| d.min(self.max_delay))
+    }
+}
+
+// 0 retries without limit
+fn is_last_attempt(attempt: u32, max_retries: u32) -> bool {
+    max_retries > 0 && attempt >= max_retries
+}
+
+impl<H: Handler> Context<H> {
+    /// Whether the session was lost because of the network, or a transient
+    /// server failure, rather than closed by the server or the user.
+    ///
+    /// The reason sent by the server decides when there is one, otherwise
+    /// the transport error recorded by FreeRDP. A session ending without any
+    /// error is not a drop, see [`RdpErr::is_retryable`].
+    pub fn is_network_drop(&self) -> bool {
+        match self.instance.error_info() {
+            0 => self.last_error().is_some_and(|e| e.is_retryable()),
+            info => RdpErrInfo::try_from(info).is_ok_and(|e| RdpErr::RdpErrInfo(e).is_retryable()),
+        }
+    }
+
+    /// Reconnect after the transport was lost, retrying with backoff.
+    ///
+    /// Fails with [`RdpError::Unsupported`] if `AutoReconnectionEnabled` is
+    /// not set. FreeRDP sends the auto-reconnect cookie received from the
+    /// server, so the session is resumed where the server allows it.
+    ///
+    /// The retries are unlimited when the maximum from the policy, or the
+    /// settings, is 0.
+    pub fn auto_reconnect(&mut self, policy: &ReconnectPolicy) -> Result<()> {
+        self.retry(policy, |context| context.instance.reconnect())
+    }
+
+    fn retry(
+        &mut self,
+        policy: &ReconnectPolicy,
+        mut reconnect: impl FnMut(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        let max_retries = self.max_retries(policy)?;
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            self.start_attempt(attempt, max_retries)?;
+            if reconnect(self).is_ok() {
+                self.handler.reconnected();
+                return Ok(());
+            }
+            if !is_last_attempt(attempt, max_retries) {
+                std::thread::sleep(policy.delay(attempt));
+            }
+        }
+    }
+
+    fn max_retries(&self, policy: &ReconnectPolicy) -> Result<u32> {
+        if !self.settings.get::<Bool>(Key::AutoReconnectionEnabled)? {
+            return Err(RdpError::Unsupported);
+        }
+        match policy.max_retries {
+            Some(max) => Ok(max),
+            None => self.settings.get::<UInt32>(Key::AutoReconnectMaxRetries),
+        }
+    }
+
+    fn start_attempt(&mut self, attempt: u32, max_retries: u32) -> Result<()> {
+        if max_retries > 0 && attempt > max_retries {
+            return Err(match self.last_error() {
+                Some(e) => RdpError::Code(e.code()),
+                None => RdpError::Failed("Failed to reconnect".into()),
+            });
+        }
+        // the pointer cache of the server doesn't survive the connection
+        self.cursors.clear();
+        self.handler.reconnecting(attempt)
+    }
+
+    /// Run the session until it is disconnected, reconnecting on network drops.
+    pub fn run(&mut self, policy: &ReconnectPolicy) -> Result<()> {
+        while !self.instance.shall_disconnect() {
+            let delay = self.frame_delay();
+            if !delay.is_zero() {
+                std::thread::sleep(delay);
+            }
+            let handles = self.event_handles()?;
+            let handles: Vec<_> = handles.iter().collect();
+            wait_for_multiple_objects(&handles, false, None)?;
+
+            if self.check_event_handles() {
+                continue;
+            }
+            if !self.is_network_drop() {
+                return Err(match self.last_error() {
+                    Some(e) => RdpError::Code(e.code()),
+                    None => RdpError::Failed("freerdp_check_event_handles() failed".into()),
+                });
+            }
+            self.auto_reconnect(policy)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(unix, feature = "tokio"))]
+impl<H: Handler + Send> Context<H> {
+    /// The asynchronous equivalent of [`Context::auto_reconnect`], the
+    /// reconnection runs on the blocking thread pool.
+    pub async fn auto_reconnect_async(&mut self, policy: &ReconnectPolicy) -> Result<()> {
+        let max_retries = self.max_retries(policy)?;
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            self.start_attempt(attempt, max_retries)?;
+            if self.spawn_instance(crate::FreeRdp::reconnect).await.is_ok() {
+                self.handler.reconnected();
+                return Ok(());
+            }
+            if !is_last_attempt(attempt, max_retries) {
+                tokio::time::sleep(policy.delay(attempt)).await;
+            }
+        }
+    }
+
+    /// The asynchronous equivalent of [`Context::run`], driving the session
+    /// with [`Context::run_async`].
+    pub async fn run_async_with_reconnect(&mut self, policy: &ReconnectPolicy) -> Result<()> {
+        loop {
+            match self.run_async().await {
+                Err(_) if self.is_network_drop() => self.auto_reconnect_async(policy).await?,
+                res => return res,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::TcpListener,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+    };
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct DropHandler {
+        attempts: Vec<u32>,
+        reconnected: bool,
+    }
+
+    impl Handler for DropHandler {
+        fn post_connect(&mut self, _context: &mut Context<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        fn reconnecting(&mut self, attempt: u32) -> Result<()> {
+            self.attempts.push(attempt);
+            Ok(())
+        }
+
+        fn reconnected(&mut self) {
+            self.reconnected = true;
+        }
+    }
+
+    #[test]
+    fn policy_delay() {
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(100), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn retry_until_accepted() {
+        let mut ctxt = Context::new(DropHandler::default());
+        ctxt.settings
+            .set::<Bool>(Key::AutoReconnectionEnabled, true)
+            .unwrap();
+        let policy = ReconnectPolicy {
+            max_retries: Some(0),
+            initial_delay: Duration::from_millis(1),
+            ..Default::default()
+        };
+
+        // a stand-in transport, dropped once before it accepts
+        let mut drops = 1;
+        ctxt.retry(&policy, |_| match drops {
+            0 => Ok(()),
+            _ => {
+                drops -= 1;
+                Err(RdpError::Failed("dropped".into()))
+            }
+        })
+        .unwrap();
+        assert_eq!(ctxt.handler.attempts, [1, 2]);
+        assert!(ctxt.handler.reconnected);
+
+        ctxt.settings
+            .set::<Bool>(Key::AutoReconnectionEnabled, false)
+            .unwrap();
+        assert!(matches!(
+            ctxt.auto_reconnect(&policy),
+            Err(RdpError::Unsupported)
+        ));
+    }
+
+    #[test]
+    fn no_delay_after_last_attempt() {
+        let mut ctxt = Context::new(DropHandler::default());
+        ctxt.settings
+            .set::<Bool>(Key::AutoReconnectionEnabled, true)
+            .unwrap();
+        let policy = ReconnectPolicy {
+            max_retries: Some(1),
+            initial_delay: Duration::from_secs(60),
+            ..Default::default()
+        };
+
+        let start = std::time::Instant::now();
+        assert!(ctxt
+            .retry(&policy, |_| Err(RdpError::Failed("dropped".into())))
+            .is_err());
+        assert_eq!(ctxt.handler.attempts, [1]);
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn reconnect_dropped_socket() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let accepted = Arc::new(AtomicU32::new(0));
+        let count = accepted.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                count.fetch_add(1, Ordering::SeqCst);
+                drop(stream);
+            }
+        });
+
+        let mut ctxt = Context::new(DropHandler::default());
+        ctxt.client_start().unwrap();
+        ctxt.settings
+            .set_server_hostname(Some("127.0.0.1"))
+            .unwrap();
+        ctxt.settings.set_server_port(port as _);
+        ctxt.settings
+            .set::<Bool>(Key::AutoReconnectionEnabled, true)
+            .unwrap();
+        ctxt.settings
+            .set::<UInt32>(Key::AutoReconnectMaxRetries, 3)
+            .unwrap();
+
+        assert!(ctxt.instance.connect().is_err());
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_millis(10),
+            ..Default::default()
+        };
+        assert!(ctxt.auto_reconnect(&policy).is_err());
+        assert_eq!(ctxt.handler.attempts, [1, 2, 3]);
+        assert!(!ctxt.handler.reconnected);
+        assert!(accepted.load(Ordering::SeqCst) >= 4);
+
+        ctxt.client_stop().unwrap();
+    }
+}
@@ -0,0 +1,584 @@
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    io,
+    os::raw::{c_char, c_void},
+    ptr,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::{
+    client::{Context, Handler},
+    sys, RdpError, Result,
+};
+
+const CHANNEL_RC_OK: u32 = 0;
+const CHANNEL_NAME_LEN: usize = 7;
+const VIRTUAL_CHANNEL_VERSION_WIN2000: u32 = 1;
+
+const CHANNEL_EVENT_INITIALIZED: u32 = 0;
+const CHANNEL_EVENT_CONNECTED: u32 = 1;
+const CHANNEL_EVENT_DISCONNECTED: u32 = 3;
+const CHANNEL_EVENT_TERMINATED: u32 = 4;
+const CHANNEL_EVENT_DATA_RECEIVED: u32 = 10;
+const CHANNEL_EVENT_WRITE_COMPLETE: u32 = 11;
+const CHANNEL_EVENT_WRITE_CANCELLED: u32 = 12;
+
+const CHANNEL_FLAG_FIRST: u32 = 0x01;
+const CHANNEL_FLAG_LAST: u32 = 0x02;
+
+const CHANNEL_OPTION_INITIALIZED: u32 = 0x80000000;
+const CHANNEL_OPTION_ENCRYPT_RDP: u32 = 0x40000000;
+const CHANNEL_OPTION_COMPRESS_RDP: u32 = 0x00800000;
+
+// CHANNEL_ENTRY_POINTS_FREERDP_EX from <freerdp/svc.h>, the extended fields
+// are not part of the generated bindings.
+#[repr(C)]
+struct ChannelEntryPointsFreeRdpEx {
+    ep: sys::tagCHANNEL_ENTRY_POINTS_EX,
+    magic_number: u32,
+    extended_data: *mut c_void,
+    interface: *mut c_void,
+    context: *mut sys::rdpContext,
+}
+
+#[derive(Debug)]
+struct Open {
+    ep: sys::tagCHANNEL_ENTRY_POINTS_EX,
+    init_handle: *mut c_void,
+    open_handle: u32,
+}
+
+// VirtualChannelWriteEx() queues the data for the channel thread
+unsafe impl Send for Open {}
+
+/// A static virtual channel.
+///
+/// The handle may be cloned and kept to write from another thread, while the
+/// channel is connected: `write()` fails with `NotConnected` otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct SvcChannel {
+    inner: Arc<Mutex<Option<Open>>>,
+}
+
+impl SvcChannel {
+    /// Send a message to the server. The data is split in chunks by FreeRDP.
+    pub fn write(&self, data: &[u8]) -> Result<()> {
+        let guard = self.inner.lock().unwrap();
+        let open = guard
+            .as_ref()
+            .ok_or_else(|| RdpError::IOError(io::ErrorKind::NotConnected.into()))?;
+
+        let len = data.len().try_into()?;
+        // FreeRDP owns the data until the write completes or is cancelled,
+        // the user data must be a thin pointer to it
+        let data = Box::new(Box::<[u8]>::from(data));
+        let buf = data.as_ptr();
+        let data = Box::into_raw(data);
+        let res = unsafe {
+            let f = open.ep.pVirtualChannelWriteEx.unwrap();
+            f(
+                open.init_handle,
+                open.open_handle,
+                buf as *mut u8 as _,
+                len,
+                data as _,
+            )
+        };
+        if res == CHANNEL_RC_OK {
+            Ok(())
+        } else {
+            drop(unsafe { Box::from_raw(data) });
+            Err(RdpError::IOError(io::Error::from_raw_os_error(res as _)))
+        }
+    }
+
+    /// Whether the channel is connected.
+    pub fn is_open(&self) -> bool {
+        self.inner.lock().unwrap().is_some()
+    }
+
+    // Close the channel, if it is open.
+    fn close(&self) {
+        if let Some(open) = self.inner.lock().unwrap().take() {
+            unsafe {
+                let f = open.ep.pVirtualChannelCloseEx.unwrap();
+                f(open.init_handle, open.open_handle);
+            }
+        }
+    }
+}
+
+pub trait SvcHandler: Send {
+    /// An error keeps the channel from being opened.
+    fn init(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The channel is open, an error closes it.
+    fn connected(&mut self, _channel: &SvcChannel) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called with each complete message, once its chunks are reassembled.
+    /// An error closes the channel.
+    fn data_received(&mut self, channel: &SvcChannel, data: &[u8]) -> Result<()>;
+
+    /// The session is disconnected and the channel closed, it is connected
+    /// again on reconnection.
+    fn disconnected(&mut self) {}
+
+    fn terminated(&mut self) {}
+}
+
+type HandlerFactory = Box<dyn Fn() -> Box<dyn SvcHandler> + Send + Sync>;
+
+// The addin is loaded by name, its factory is found in a global registry.
+// As with the dynamic channels, the context is the addin second argument.
+fn registry() -> &'static Mutex<HashMap<(usize, String), HandlerFactory>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<(usize, String), HandlerFactory>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+pub(super) fn unregister(context: *mut sys::rdpContext) {
+    registry()
+        .lock()
+        .unwrap()
+        .retain(|(c, _), _| *c != context as usize);
+}
+
+pub(super) fn addin_entry(name: &str) -> sys::PVIRTUALCHANNELENTRY {
+    if !registry().lock().unwrap().keys().any(|(_, n)| n == name) {
+        return None;
+    }
+    let entry: extern "C" fn(sys::PCHANNEL_ENTRY_POINTS_EX, sys::PVOID) -> sys::BOOL =
+        rdp_svc_entry_ex;
+    unsafe {
+        std::mem::transmute::<
+            extern "C" fn(sys::PCHANNEL_ENTRY_POINTS_EX, sys::PVOID) -> sys::BOOL,
+            sys::PVIRTUALCHANNELENTRY,
+        >(entry)
+    }
+}
+
+struct Plugin {
+    ep: sys::tagCHANNEL_ENTRY_POINTS_EX,
+    init_handle: *mut c_void,
+    channel: SvcChannel,
+    def: sys::CHANNEL_DEF,
+    handler: Box<dyn SvcHandler>,
+    data: Vec<u8>,
+    failed: bool,
+}
+
+impl Plugin {
+    fn close(&mut self) {
+        self.channel.close();
+        self.data.clear();
+    }
+}
+
+impl<H: Handler> Context<H> {
+    /// Load a static virtual channel implemented in Rust.
+    ///
+    /// `name` is both the addin name and the channel name, it is limited
+    /// to 7 characters. `factory` is called for each connection.
+    pub fn add_static_channel<F, S>(&mut self, name: &str, factory: F) -> Result<()>
+    where
+        F: Fn() -> S + Send + Sync + 'static,
+        S: SvcHandler + 'static,
+    {
+        if name.len() > CHANNEL_NAME_LEN {
+            return Err(RdpError::Failed(format!("Invalid channel name {}", name)));
+        }
+        let id = self.rdp_context.as_ptr() as usize;
+        let cname = CString::new(name)?;
+        let cid = CString::new(id.to_string())?;
+        registry()
+            .lock()
+            .unwrap()
+            .insert((id, name.into()), Box::new(move || Box::new(factory())));
+
+        let mut params = [cname.as_ptr() as *mut c_char, cid.as_ptr() as *mut c_char];
+        let res = unsafe {
+            sys::freerdp_client_add_static_channel(
+                self.settings.as_ptr(),
+                params.len(),
+                params.as_mut_ptr(),
+            )
+        };
+        if res == 0 {
+            return Err(RdpError::Failed("Failed to add static channel".into()));
+        }
+        Ok(())
+    }
+}
+
+extern "C" fn rdp_svc_entry_ex(
+    entry_points: sys::PCHANNEL_ENTRY_POINTS_EX,
+    init_handle: sys::PVOID,
+) -> sys::BOOL {
+    let ep = unsafe { &*(entry_points as *mut ChannelEntryPointsFreeRdpEx) };
+    let args = ep.extended_data as *mut sys::ADDIN_ARGV;
+    if args.is_null() || unsafe { (*args).argc } < 2 {
+        return 0;
+    }
+    let (name, id) = unsafe {
+        (
+            CStr::from_ptr(*(*args).argv),
+            CStr::from_ptr(*(*args).argv.add(1)),
+        )
+    };
+    let key = match id.to_str().ok().and_then(|id| id.parse().ok()) {
+        Some(id) => (id, name.to_string_lossy().into_owned()),
+        None => return 0,
+    };
+    let handler = match registry().lock().unwrap().get(&key) {
+        Some(factory) => factory(),
+        None => return 0,
+    };
+
+    let mut def = sys::CHANNEL_DEF {
+        name: [0; 8],
+        options: CHANNEL_OPTION_INITIALIZED
+            | CHANNEL_OPTION_ENCRYPT_RDP
+            | CHANNEL_OPTION_COMPRESS_RDP,
+    };
+    for (d, s) in def.name.iter_mut().zip(name.to_bytes()) {
+        *d = *s as _;
+    }
+
+    let plugin = Box::into_raw(Box::new(Plugin {
+        ep: ep.ep,
+        init_handle,
+        channel: SvcChannel::default(),
+        def,
+        handler,
+        data: Vec::new(),
+        failed: false,
+    }));
+    let res = unsafe {
+        let f = ep.ep.pVirtualChannelInitEx.unwrap();
+        f(
+            plugin as _,
+            ptr::null_mut(),
+            init_handle,
+            &mut (*plugin).def,
+            1,
+            VIRTUAL_CHANNEL_VERSION_WIN2000,
+            Some(rdp_svc_init_event_ex),
+        )
+    };
+    if res != CHANNEL_RC_OK {
+        drop(unsafe { Box::from_raw(plugin) });
+        return 0;
+    }
+    1
+}
+
+extern "C" fn rdp_svc_init_event_ex(
+    user_param: sys::LPVOID,
+    _init_handle: sys::LPVOID,
+    event: sys::UINT,
+    _data: sys::LPVOID,
+    _data_length: sys::UINT,
+) {
+    let plugin = user_param as *mut Plugin;
+    let p = unsafe { &mut *plugin };
+
+    match event {
+        CHANNEL_EVENT_INITIALIZED => {
+            p.failed = p.handler.init().is_err();
+        }
+        CHANNEL_EVENT_CONNECTED if !p.failed => {
+            let mut open_handle = 0;
+            let res = unsafe {
+                let f = p.ep.pVirtualChannelOpenEx.unwrap();
+                f(
+                    p.init_handle,
+                    &mut open_handle,
+                    p.def.name.as_mut_ptr(),
+                    Some(rdp_svc_open_event_ex),
+                )
+            };
+            if res == CHANNEL_RC_OK {
+                *p.channel.inner.lock().unwrap() = Some(Open {
+                    ep: p.ep,
+                    init_handle: p.init_handle,
+                    open_handle,
+                });
+                if p.handler.connected(&p.channel).is_err() {
+                    p.close();
+                }
+            }
+        }
+        CHANNEL_EVENT_DISCONNECTED => {
+            p.close();
+            p.handler.disconnected();
+        }
+        CHANNEL_EVENT_TERMINATED => {
+            let mut plugin = unsafe { Box::from_raw(plugin) };
+            plugin.close();
+            plugin.handler.terminated();
+        }
+        _ => {}
+    }
+}
+
+extern "C" fn rdp_svc_open_event_ex(
+    user_param: sys::LPVOID,
+    _open_handle: sys::DWORD,
+    event: sys::UINT,
+    data: sys::LPVOID,
+    data_length: sys::UINT32,
+    total_length: sys::UINT32,
+    data_flags: sys::UINT32,
+) {
+    let p = unsafe { &mut *(user_param as *mut Plugin) };
+
+    match event {
+        CHANNEL_EVENT_DATA_RECEIVED => {
+            let chunk = unsafe { std::slice::from_raw_parts(data as *const u8, data_length as _) };
+            if data_flags & CHANNEL_FLAG_FIRST != 0 {
+                p.data.clear();
+                p.data.reserve(total_length as _);
+            }
+            p.data.extend_from_slice(chunk);
+            if data_flags & CHANNEL_FLAG_LAST != 0 {
+                let data = std::mem::take(&mut p.data);
+                if p.handler.data_received(&p.channel, &data).is_err() {
+                    p.close();
+                }
+            }
+        }
+        CHANNEL_EVENT_WRITE_COMPLETE | CHANNEL_EVENT_WRITE_CANCELLED => {
+            // the user data given to SvcChannel::write()
+            drop(unsafe { Box::from_raw(data as *mut Box<[u8]>) });
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    };
+
+    use super::*;
+
+    // A stand-in for the FreeRDP channels, its address is the init handle.
+    #[derive(Default)]
+    struct Fake {
+        procs: Mutex<Procs>,
+        closed: AtomicBool,
+        written: Mutex<Vec<u8>>,
+    }
+
+    #[derive(Copy, Clone)]
+    struct Procs {
+        user: sys::LPVOID,
+        init: sys::PCHANNEL_INIT_EVENT_EX_FN,
+        open: sys::PCHANNEL_OPEN_EVENT_EX_FN,
+    }
+
+    impl Default for Procs {
+        fn default() -> Self {
+            Self {
+                user: ptr::null_mut(),
+                init: None,
+                open: None,
+            }
+        }
+    }
+
+    impl Fake {
+        fn procs(&self) -> Procs {
+            *self.procs.lock().unwrap()
+        }
+
+        fn init(&self, event: u32) {
+            let procs = self.procs();
+            unsafe {
+                procs.init.unwrap()(procs.user, self as *const _ as _, event, ptr::null_mut(), 0)
+            }
+        }
+
+        fn open(&self, event: u32, data: sys::LPVOID, len: usize, total: usize, flags: u32) {
+            let procs = self.procs();
+            unsafe { procs.open.unwrap()(procs.user, 42, event, data, len as _, total as _, flags) }
+        }
+
+        fn receive(&self, data: &[u8], flags: u32, total: usize) {
+            let len = data.len();
+            self.open(
+                CHANNEL_EVENT_DATA_RECEIVED,
+                data.as_ptr() as _,
+                len,
+                total,
+                flags,
+            )
+        }
+    }
+
+    unsafe extern "C" fn fake_init(
+        user: sys::LPVOID,
+        _client_context: sys::LPVOID,
+        init_handle: sys::LPVOID,
+        _channel: sys::PCHANNEL_DEF,
+        _count: sys::INT,
+        _version: sys::ULONG,
+        proc_: sys::PCHANNEL_INIT_EVENT_EX_FN,
+    ) -> sys::UINT {
+        let fake = &*(init_handle as *const Fake);
+        let mut procs = fake.procs.lock().unwrap();
+        procs.user = user;
+        procs.init = proc_;
+        CHANNEL_RC_OK
+    }
+
+    unsafe extern "C" fn fake_open(
+        init_handle: sys::LPVOID,
+        open_handle: sys::LPDWORD,
+        _name: sys::PCHAR,
+        proc_: sys::PCHANNEL_OPEN_EVENT_EX_FN,
+    ) -> sys::UINT {
+        let fake = &*(init_handle as *const Fake);
+        fake.procs.lock().unwrap().open = proc_;
+        fake.closed.store(false, Ordering::SeqCst);
+        *open_handle = 42;
+        CHANNEL_RC_OK
+    }
+
+    unsafe extern "C" fn fake_close(
+        init_handle: sys::LPVOID,
+        open_handle: sys::DWORD,
+    ) -> sys::UINT {
+        assert_eq!(open_handle, 42);
+        let fake = &*(init_handle as *const Fake);
+        fake.closed.store(true, Ordering::SeqCst);
+        CHANNEL_RC_OK
+    }
+
+    unsafe extern "C" fn fake_write(
+        init_handle: sys::LPVOID,
+        _open_handle: sys::DWORD,
+        data: sys::LPVOID,
+        len: sys::ULONG,
+        user_data: sys::LPVOID,
+    ) -> sys::UINT {
+        let fake = &*(init_handle as *const Fake);
+        let data = std::slice::from_raw_parts(data as *const u8, len as _);
+        fake.written.lock().unwrap().extend_from_slice(data);
+        // FreeRDP gives back the user data, with the size of a pointer
+        let size = size_of::<*mut c_void>();
+        fake.open(CHANNEL_EVENT_WRITE_COMPLETE, user_data, size, size, 0);
+        CHANNEL_RC_OK
+    }
+
+    struct Audit {
+        events: mpsc::Sender<String>,
+        channels: mpsc::Sender<SvcChannel>,
+    }
+
+    impl SvcHandler for Audit {
+        fn connected(&mut self, channel: &SvcChannel) -> Result<()> {
+            self.channels.send(channel.clone()).unwrap();
+            Ok(())
+        }
+
+        fn data_received(&mut self, _channel: &SvcChannel, data: &[u8]) -> Result<()> {
+            self.events
+                .send(String::from_utf8_lossy(data).into_owned())
+                .unwrap();
+            match data {
+                b"bad" => Err(RdpError::Unsupported),
+                _ => Ok(()),
+            }
+        }
+
+        fn disconnected(&mut self) {
+            self.events.send("disconnected".into()).unwrap();
+        }
+
+        fn terminated(&mut self) {
+            self.events.send("terminated".into()).unwrap();
+        }
+    }
+
+    #[test]
+    fn channel_events() {
+        let (events, rx_events) = mpsc::channel();
+        let (channels, rx_channels) = mpsc::channel();
+        let id = 0x1000usize;
+        registry().lock().unwrap().insert(
+            (id, "audit".into()),
+            Box::new(move || {
+                Box::new(Audit {
+                    events: events.clone(),
+                    channels: channels.clone(),
+                })
+            }),
+        );
+
+        let fake = Fake::default();
+        let name = CString::new("audit").unwrap();
+        let cid = CString::new(id.to_string()).unwrap();
+        let mut argv = [name.as_ptr() as *mut c_char, cid.as_ptr() as *mut c_char];
+        let mut args = sys::ADDIN_ARGV {
+            argc: 2,
+            argv: argv.as_mut_ptr(),
+        };
+        let mut ep = ChannelEntryPointsFreeRdpEx {
+            ep: sys::tagCHANNEL_ENTRY_POINTS_EX {
+                cbSize: size_of::<sys::tagCHANNEL_ENTRY_POINTS_EX>() as _,
+                protocolVersion: VIRTUAL_CHANNEL_VERSION_WIN2000,
+                pVirtualChannelInitEx: Some(fake_init),
+                pVirtualChannelOpenEx: Some(fake_open),
+                pVirtualChannelCloseEx: Some(fake_close),
+                pVirtualChannelWriteEx: Some(fake_write),
+            },
+            magic_number: 0,
+            extended_data: &mut args as *mut _ as _,
+            interface: ptr::null_mut(),
+            context: ptr::null_mut(),
+        };
+        let init_handle = &fake as *const Fake as *mut c_void;
+        assert_eq!(rdp_svc_entry_ex(&mut ep as *mut _ as _, init_handle), 1);
+        unregister(id as _);
+
+        fake.init(CHANNEL_EVENT_INITIALIZED);
+        fake.init(CHANNEL_EVENT_CONNECTED);
+        let channel = rx_channels.recv().unwrap();
+
+        // written from another thread, outside of the callbacks
+        let writer = channel.clone();
+        std::thread::spawn(move || writer.write(b"hello"))
+            .join()
+            .unwrap()
+            .unwrap();
+        assert_eq!(*fake.written.lock().unwrap(), b"hello");
+
+        fake.receive(b"chu", CHANNEL_FLAG_FIRST, 5);
+        fake.receive(b"nk", CHANNEL_FLAG_LAST, 5);
+        assert_eq!(rx_events.recv().unwrap(), "chunk");
+
+        // a handler error closes the channel
+        fake.receive(b"bad", CHANNEL_FLAG_FIRST | CHANNEL_FLAG_LAST, 3);
+        assert_eq!(rx_events.recv().unwrap(), "bad");
+        assert!(fake.closed.load(Ordering::SeqCst));
+        assert!(!channel.is_open());
+        assert!(channel.write(b"late").is_err());
+
+        fake.init(CHANNEL_EVENT_CONNECTED);
+        let channel = rx_channels.recv().unwrap();
+        assert!(channel.is_open());
+        fake.init(CHANNEL_EVENT_DISCONNECTED);
+        assert_eq!(rx_events.recv().unwrap(), "disconnected");
+        assert!(!channel.is_open());
+
+        fake.init(CHANNEL_EVENT_TERMINATED);
+        assert_eq!(rx_events.recv().unwrap(), "terminated");
+    }
+}
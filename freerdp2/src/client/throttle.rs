@@ -0,0 +1,146 @@
+use std::time::{Duration, Instant};
+
+use crate::{RdpError, Result};
+
+// bounds the delays of the tiny rates
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// A token bucket limiting the frame rate. Each frame ending takes a token,
+/// see `Context::frame_delay()`, and the events aren't checked until the
+/// next one is available. That holds back the next frames and their
+/// acknowledgements, and so the server.
+#[derive(Debug, Clone)]
+pub struct FrameThrottle {
+    max_fps: f64,
+    burst: f64,
+    tokens: f64,
+    last: Instant,
+    // when the token of the last frame is available
+    resume: Instant,
+}
+
+impl FrameThrottle {
+    /// `max_fps` must be positive and finite.
+    pub fn new(max_fps: f64) -> Result<Self> {
+        Self::with_burst(max_fps, 1)
+    }
+
+    /// A throttle letting up to `burst` frames through at once.
+    pub fn with_burst(max_fps: f64, burst: u32) -> Result<Self> {
+        let burst = burst.max(1) as f64;
+
+        let now = Instant::now();
+
+        Ok(Self {
+            max_fps: check_fps(max_fps)?,
+            burst,
+            tokens: burst,
+            last: now,
+            resume: now,
+        })
+    }
+
+    pub fn max_fps(&self) -> f64 {
+        self.max_fps
+    }
+
+    pub fn set_max_fps(&mut self, max_fps: f64) -> Result<()> {
+        self.max_fps = check_fps(max_fps)?;
+        Ok(())
+    }
+
+    /// Take a token for a frame at `now`, returns how long to wait before
+    /// the frame is allowed, up to a minute.
+    pub fn delay(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.last = self.last.max(now);
+        self.tokens = (self.tokens + elapsed * self.max_fps).min(self.burst) - 1.0;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(-self.tokens / self.max_fps)
+                .map_or(MAX_DELAY, |d| d.min(MAX_DELAY))
+        }
+    }
+
+    /// Take a token for a frame ending at `now`, the next events wait for
+    /// it, see `FrameThrottle::remaining()`.
+    pub fn end_frame(&mut self, now: Instant) {
+        self.resume = now + self.delay(now);
+    }
+
+    /// How long until the token of the last frame is available, at `now`.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.resume.saturating_duration_since(now)
+    }
+}
+
+fn check_fps(max_fps: f64) -> Result<f64> {
+    if max_fps > 0.0 && max_fps.is_finite() {
+        Ok(max_fps)
+    } else {
+        Err(RdpError::Failed(format!("Invalid frame rate {}", max_fps)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay() {
+        let mut throttle = FrameThrottle::with_burst(10.0, 2).unwrap();
+        let start = throttle.last;
+
+        assert_eq!(throttle.delay(start), Duration::ZERO);
+        assert_eq!(throttle.delay(start), Duration::ZERO);
+        let d = throttle.delay(start);
+        assert!((d.as_secs_f64() - 0.1).abs() < 1e-6);
+
+        // the waited token is already taken
+        let d = throttle.delay(start + Duration::from_millis(100));
+        assert!((d.as_secs_f64() - 0.1).abs() < 1e-6);
+
+        let d = throttle.delay(start + Duration::from_secs(10));
+        assert_eq!(d, Duration::ZERO);
+    }
+
+    #[test]
+    fn remaining() {
+        let mut throttle = FrameThrottle::new(10.0).unwrap();
+        let start = throttle.last;
+
+        throttle.end_frame(start);
+        assert_eq!(throttle.remaining(start), Duration::ZERO);
+        throttle.end_frame(start);
+        let d = throttle.remaining(start + Duration::from_millis(40));
+        assert!((d.as_secs_f64() - 0.06).abs() < 1e-6);
+        assert_eq!(
+            throttle.remaining(start + Duration::from_millis(100)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn invalid_rate() {
+        assert!(FrameThrottle::new(0.0).is_err());
+        assert!(FrameThrottle::new(-1.0).is_err());
+        assert!(FrameThrottle::new(f64::NAN).is_err());
+        assert!(FrameThrottle::new(f64::INFINITY).is_err());
+
+        let mut throttle = FrameThrottle::new(10.0).unwrap();
+        assert!(throttle.set_max_fps(0.0).is_err());
+        assert_eq!(throttle.max_fps(), 10.0);
+    }
+
+    #[test]
+    fn max_delay() {
+        let mut throttle = FrameThrottle::new(1e-300).unwrap();
+        let start = throttle.last;
+
+        throttle.end_frame(start);
+        throttle.end_frame(start);
+        assert_eq!(throttle.remaining(start), MAX_DELAY);
+    }
+}
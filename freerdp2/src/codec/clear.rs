@@ -0,0 +1,95 @@
+use std::ptr;
+
+use super::check_dst;
+use crate::{sys, PixelFormat, RdpError, Result};
+
+/// A ClearCodec decoder. It keeps glyph and vbar caches between calls.
+#[derive(Debug)]
+pub struct ClearContext {
+    inner: ptr::NonNull<sys::CLEAR_CONTEXT>,
+}
+
+unsafe impl Send for ClearContext {}
+
+impl Drop for ClearContext {
+    fn drop(&mut self) {
+        unsafe { sys::clear_context_free(self.inner.as_ptr()) }
+    }
+}
+
+impl ClearContext {
+    pub fn new() -> Result<Self> {
+        let inner = unsafe { sys::clear_context_new(0) };
+
+        Ok(Self {
+            inner: ptr::NonNull::new(inner).ok_or(RdpError::Unsupported)?,
+        })
+    }
+
+    /// Decode a `width`x`height` bitmap at the origin of `dst`.
+    pub fn decode(
+        &mut self,
+        src: &[u8],
+        width: u32,
+        height: u32,
+        dst: &mut [u8],
+        format: &PixelFormat,
+        stride: u32,
+    ) -> Result<()> {
+        check_dst(dst, format, stride, width, height)?;
+
+        let res = unsafe {
+            sys::clear_decompress(
+                self.inner.as_ptr(),
+                src.as_ptr(),
+                src.len().try_into()?,
+                width,
+                height,
+                dst.as_mut_ptr(),
+                format.into(),
+                stride,
+                0,
+                0,
+                width,
+                height,
+                ptr::null(),
+            )
+        };
+        if res < 0 {
+            Err(RdpError::Failed(format!(
+                "clear_decompress() failed: {}",
+                res
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PIXEL_FORMAT_BGRA32;
+
+    #[test]
+    fn decode_malformed() {
+        let mut clear = ClearContext::new().unwrap();
+        let mut dst = vec![0x55; 16 * 16 * 4];
+
+        // a glyph hit on an empty cache
+        assert!(clear
+            .decode(&[0xff; 4], 16, 16, &mut dst, PIXEL_FORMAT_BGRA32, 16 * 4)
+            .is_err());
+        assert!(clear
+            .decode(
+                &[0xff; 4],
+                16,
+                16,
+                &mut dst[4..],
+                PIXEL_FORMAT_BGRA32,
+                16 * 4
+            )
+            .is_err());
+        assert!(dst.iter().all(|b| *b == 0x55));
+    }
+}
@@ -0,0 +1,151 @@
+use std::ptr;
+
+use super::check_dst;
+use crate::{gdi::GdiPalette, image::palette_ptr, sys, PixelFormat, RdpError, Result};
+
+/// An interleaved RLE (pre RDP 6.0 bitmap) codec.
+#[derive(Debug)]
+pub struct InterleavedContext {
+    inner: ptr::NonNull<sys::BITMAP_INTERLEAVED_CONTEXT>,
+}
+
+unsafe impl Send for InterleavedContext {}
+
+impl Drop for InterleavedContext {
+    fn drop(&mut self) {
+        unsafe { sys::bitmap_interleaved_context_free(self.inner.as_ptr()) }
+    }
+}
+
+impl InterleavedContext {
+    pub fn new() -> Result<Self> {
+        let inner = unsafe { sys::bitmap_interleaved_context_new(1) };
+
+        Ok(Self {
+            inner: ptr::NonNull::new(inner).ok_or(RdpError::Unsupported)?,
+        })
+    }
+
+    /// Decode a `width`x`height` bitmap of `bpp` (8, 15, 16 or 24) at the
+    /// origin of `dst`. The palette is needed for 8 bpp bitmaps.
+    #[allow(clippy::too_many_arguments)]
+    pub fn decode(
+        &mut self,
+        src: &[u8],
+        width: u32,
+        height: u32,
+        bpp: u32,
+        dst: &mut [u8],
+        format: &PixelFormat,
+        stride: u32,
+        palette: Option<&GdiPalette>,
+    ) -> Result<()> {
+        check_dst(dst, format, stride, width, height)?;
+
+        let res = unsafe {
+            sys::interleaved_decompress(
+                self.inner.as_ptr(),
+                src.as_ptr(),
+                src.len().try_into()?,
+                width,
+                height,
+                bpp,
+                dst.as_mut_ptr(),
+                format.into(),
+                stride,
+                0,
+                0,
+                width,
+                height,
+                palette_ptr(palette),
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("interleaved_decompress() failed".into()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Compress a `width`x`height` bitmap to `bpp`. The width must be a
+    /// multiple of 4, and both dimensions at most 64.
+    #[allow(clippy::too_many_arguments)]
+    pub fn compress(
+        &mut self,
+        data: &[u8],
+        format: &PixelFormat,
+        width: u32,
+        height: u32,
+        stride: u32,
+        bpp: u32,
+        palette: Option<&GdiPalette>,
+    ) -> Result<Vec<u8>> {
+        check_dst(data, format, stride, width, height)?;
+
+        let mut size = width * height * 4 + 16;
+        let mut out = vec![0; size as usize];
+        let res = unsafe {
+            sys::interleaved_compress(
+                self.inner.as_ptr(),
+                out.as_mut_ptr(),
+                &mut size,
+                width,
+                height,
+                data.as_ptr(),
+                format.into(),
+                stride,
+                0,
+                0,
+                palette_ptr(palette),
+                bpp,
+            )
+        };
+        if res == 0 {
+            return Err(RdpError::Failed("interleaved_compress() failed".into()));
+        }
+        out.truncate(size as usize);
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PIXEL_FORMAT_BGR24;
+
+    #[test]
+    fn round_trip() {
+        let (width, height) = (16, 8);
+        let data: Vec<u8> = (0..width * height)
+            .flat_map(|i| [(i / 5) as u8, 0x20, (i % 3) as u8])
+            .collect();
+        let mut interleaved = InterleavedContext::new().unwrap();
+
+        let compressed = interleaved
+            .compress(
+                &data,
+                PIXEL_FORMAT_BGR24,
+                width,
+                height,
+                width * 3,
+                24,
+                None,
+            )
+            .unwrap();
+        let mut decoded = vec![0; data.len()];
+        interleaved
+            .decode(
+                &compressed,
+                width,
+                height,
+                24,
+                &mut decoded,
+                PIXEL_FORMAT_BGR24,
+                width * 3,
+                None,
+            )
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+}
@@ -0,0 +1,66 @@
+//! FreeRDP bitmap codecs, usable without a connection.
+
+use std::{mem::MaybeUninit, slice};
+
+use crate::{sys, PixelFormat, RdpError, Rect, Result};
+
+mod clear;
+pub use clear::*;
+
+mod interleaved;
+pub use interleaved::*;
+
+mod nsc;
+pub use nsc::*;
+
+mod planar;
+pub use planar::*;
+
+mod progressive;
+pub use progressive::*;
+
+mod rfx;
+pub use rfx::*;
+
+// Check that `dst` can hold `width`x`height` pixels of `format`.
+fn check_dst(dst: &[u8], format: &PixelFormat, stride: u32, width: u32, height: u32) -> Result<()> {
+    let row = width as usize * format.bytes_per_pixel() as usize;
+    if (stride as usize) < row {
+        return Err(RdpError::Failed("Destination stride too small".into()));
+    }
+    if height > 0 && dst.len() < (height as usize - 1) * stride as usize + row {
+        return Err(RdpError::Failed("Destination buffer too small".into()));
+    }
+    Ok(())
+}
+
+// The region updated by a decoder.
+struct Region16(sys::REGION16);
+
+impl Region16 {
+    fn new() -> Self {
+        let mut region = MaybeUninit::uninit();
+        unsafe {
+            sys::region16_init(region.as_mut_ptr());
+            Self(region.assume_init())
+        }
+    }
+
+    fn rects(&self) -> Vec<Rect> {
+        let mut n = 0;
+        let rects = unsafe { sys::region16_rects(&self.0, &mut n) };
+        if rects.is_null() {
+            return vec![];
+        }
+        unsafe { slice::from_raw_parts(rects, n as _) }
+            .iter()
+            .map(|r| Rect::from(*r))
+            .collect()
+    }
+}
+
+impl Drop for Region16 {
+    fn drop(&mut self) {
+        unsafe { sys::region16_uninit(&mut self.0) }
+    }
+}
@@ -0,0 +1,88 @@
+use std::ptr;
+
+use super::check_dst;
+use crate::{sys, PixelFormat, RdpError, Result};
+
+/// An NSCodec decoder.
+#[derive(Debug)]
+pub struct NscContext {
+    inner: ptr::NonNull<sys::NSC_CONTEXT>,
+}
+
+unsafe impl Send for NscContext {}
+
+impl Drop for NscContext {
+    fn drop(&mut self) {
+        unsafe { sys::nsc_context_free(self.inner.as_ptr()) }
+    }
+}
+
+impl NscContext {
+    pub fn new() -> Result<Self> {
+        let inner = unsafe { sys::nsc_context_new() };
+
+        Ok(Self {
+            inner: ptr::NonNull::new(inner).ok_or(RdpError::Unsupported)?,
+        })
+    }
+
+    /// Decode a `width`x`height` bitmap at the origin of `dst`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn decode(
+        &mut self,
+        src: &[u8],
+        width: u32,
+        height: u32,
+        bpp: u16,
+        dst: &mut [u8],
+        format: &PixelFormat,
+        stride: u32,
+    ) -> Result<()> {
+        check_dst(dst, format, stride, width, height)?;
+
+        let res = unsafe {
+            sys::nsc_process_message(
+                self.inner.as_ptr(),
+                bpp,
+                width,
+                height,
+                src.as_ptr(),
+                src.len().try_into()?,
+                dst.as_mut_ptr(),
+                format.into(),
+                stride,
+                0,
+                0,
+                width,
+                height,
+                sys::FREERDP_FLIP_NONE,
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("nsc_process_message() failed".into()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PIXEL_FORMAT_BGRA32;
+
+    #[test]
+    fn decode_malformed() {
+        let mut nsc = NscContext::new().unwrap();
+        let mut dst = vec![0x55; 16 * 16 * 4];
+
+        // shorter than the stream header
+        assert!(nsc
+            .decode(&[0; 4], 16, 16, 32, &mut dst, PIXEL_FORMAT_BGRA32, 16 * 4)
+            .is_err());
+        assert!(nsc
+            .decode(&[0; 4], 16, 16, 32, &mut dst, PIXEL_FORMAT_BGRA32, 15 * 4)
+            .is_err());
+        assert!(dst.iter().all(|b| *b == 0x55));
+    }
+}
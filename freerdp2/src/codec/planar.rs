@@ -0,0 +1,145 @@
+use bitflags::bitflags;
+use std::ptr;
+
+use super::check_dst;
+use crate::{sys, PixelFormat, RdpError, Result};
+
+bitflags! {
+    /// The planar format header flags, allowed for compression.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct PlanarFlags: u32 {
+        const COLOR_SUBSAMPLING = 0x08;
+        const RLE = 0x10;
+        const NO_ALPHA = 0x20;
+    }
+}
+
+/// A planar (RDP 6.0 bitmap) codec.
+#[derive(Debug)]
+pub struct PlanarContext {
+    inner: ptr::NonNull<sys::BITMAP_PLANAR_CONTEXT>,
+}
+
+unsafe impl Send for PlanarContext {}
+
+impl Drop for PlanarContext {
+    fn drop(&mut self) {
+        unsafe { sys::freerdp_bitmap_planar_context_free(self.inner.as_ptr()) }
+    }
+}
+
+impl PlanarContext {
+    /// A context for bitmaps of at most `max_width`x`max_height`.
+    pub fn new(flags: PlanarFlags, max_width: u32, max_height: u32) -> Result<Self> {
+        let inner =
+            unsafe { sys::freerdp_bitmap_planar_context_new(flags.bits(), max_width, max_height) };
+
+        Ok(Self {
+            inner: ptr::NonNull::new(inner).ok_or(RdpError::Unsupported)?,
+        })
+    }
+
+    /// Decode a `width`x`height` bitmap at the origin of `dst`.
+    pub fn decode(
+        &mut self,
+        src: &[u8],
+        width: u32,
+        height: u32,
+        dst: &mut [u8],
+        format: &PixelFormat,
+        stride: u32,
+    ) -> Result<()> {
+        check_dst(dst, format, stride, width, height)?;
+
+        let res = unsafe {
+            sys::planar_decompress(
+                self.inner.as_ptr(),
+                src.as_ptr(),
+                src.len().try_into()?,
+                width,
+                height,
+                dst.as_mut_ptr(),
+                format.into(),
+                stride,
+                0,
+                0,
+                width,
+                height,
+                0,
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("planar_decompress() failed".into()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Compress a `width`x`height` bitmap.
+    pub fn compress(
+        &mut self,
+        data: &[u8],
+        format: &PixelFormat,
+        width: u32,
+        height: u32,
+        stride: u32,
+    ) -> Result<Vec<u8>> {
+        check_dst(data, format, stride, width, height)?;
+
+        // format header, the four planes and the padding byte of raw planes
+        let mut size = u32::try_from(width as u64 * height as u64 * 4 + 2)?;
+        let mut out = vec![0; size as usize];
+        let res = unsafe {
+            sys::freerdp_bitmap_compress_planar(
+                self.inner.as_ptr(),
+                data.as_ptr(),
+                format.into(),
+                width,
+                height,
+                stride,
+                out.as_mut_ptr(),
+                &mut size,
+            )
+        };
+        if res.is_null() {
+            return Err(RdpError::Failed(
+                "freerdp_bitmap_compress_planar() failed".into(),
+            ));
+        }
+        out.truncate(size as usize);
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PIXEL_FORMAT_BGRA32;
+
+    #[test]
+    fn round_trip() {
+        let (width, height) = (16, 8);
+        let data: Vec<u8> = (0..width * height)
+            .flat_map(|i| [(i % 7) as u8, (i / 3) as u8, 0x40, 0xff])
+            .collect();
+        let mut planar =
+            PlanarContext::new(PlanarFlags::RLE | PlanarFlags::NO_ALPHA, width, height).unwrap();
+
+        let compressed = planar
+            .compress(&data, PIXEL_FORMAT_BGRA32, width, height, width * 4)
+            .unwrap();
+        let mut decoded = vec![0; data.len()];
+        planar
+            .decode(
+                &compressed,
+                width,
+                height,
+                &mut decoded,
+                PIXEL_FORMAT_BGRA32,
+                width * 4,
+            )
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
+}
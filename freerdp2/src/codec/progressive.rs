@@ -0,0 +1,147 @@
+use std::{collections::HashMap, ptr};
+
+use super::Region16;
+use crate::{sys, PixelFormat, RdpError, Rect, Result};
+
+/// A progressive RemoteFX decoder. Tiles are refined over several frames,
+/// so the state is kept by surface.
+#[derive(Debug)]
+pub struct ProgressiveContext {
+    inner: ptr::NonNull<sys::PROGRESSIVE_CONTEXT>,
+    // the width and height of each surface, to check the destination
+    surfaces: HashMap<u16, (u32, u32)>,
+}
+
+unsafe impl Send for ProgressiveContext {}
+
+impl Drop for ProgressiveContext {
+    fn drop(&mut self) {
+        unsafe { sys::progressive_context_free(self.inner.as_ptr()) }
+    }
+}
+
+impl ProgressiveContext {
+    pub fn new() -> Result<Self> {
+        let inner = unsafe { sys::progressive_context_new(0) };
+
+        Ok(Self {
+            inner: ptr::NonNull::new(inner).ok_or(RdpError::Unsupported)?,
+            surfaces: HashMap::new(),
+        })
+    }
+
+    /// Create the state of a surface. An existing surface is kept as is.
+    pub fn create_surface(&mut self, surface_id: u16, width: u32, height: u32) -> Result<()> {
+        let res = unsafe {
+            sys::progressive_create_surface_context(self.inner.as_ptr(), surface_id, width, height)
+        };
+        if res < 0 {
+            Err(RdpError::Failed(format!(
+                "progressive_create_surface_context() failed: {}",
+                res
+            )))
+        } else {
+            // like FreeRDP, keep the size of an existing surface
+            self.surfaces.entry(surface_id).or_insert((width, height));
+            Ok(())
+        }
+    }
+
+    pub fn delete_surface(&mut self, surface_id: u16) -> Result<()> {
+        let res =
+            unsafe { sys::progressive_delete_surface_context(self.inner.as_ptr(), surface_id) };
+        if res < 0 {
+            Err(RdpError::Failed(format!(
+                "progressive_delete_surface_context() failed: {}",
+                res
+            )))
+        } else {
+            self.surfaces.remove(&surface_id);
+            Ok(())
+        }
+    }
+
+    /// Decode a message of `surface_id`, created with `create_surface()`, to
+    /// `dst`, which must be as large as the surface. Returns the updated
+    /// rectangles.
+    pub fn decode(
+        &mut self,
+        src: &[u8],
+        surface_id: u16,
+        frame_id: u32,
+        dst: &mut [u8],
+        format: &PixelFormat,
+        stride: u32,
+    ) -> Result<Vec<Rect>> {
+        let (width, height) = *self
+            .surfaces
+            .get(&surface_id)
+            .ok_or_else(|| RdpError::Failed(format!("Unknown surface {}", surface_id)))?;
+        if (stride as usize) < width as usize * format.bytes_per_pixel() as usize {
+            return Err(RdpError::Failed("Destination stride too small".into()));
+        }
+        if dst.len() < stride as usize * height as usize {
+            return Err(RdpError::Failed("Destination buffer too small".into()));
+        }
+
+        let mut region = Region16::new();
+        let res = unsafe {
+            sys::progressive_decompress(
+                self.inner.as_ptr(),
+                src.as_ptr(),
+                src.len().try_into()?,
+                dst.as_mut_ptr(),
+                format.into(),
+                stride,
+                0,
+                0,
+                &mut region.0,
+                surface_id,
+                frame_id,
+            )
+        };
+        if res < 0 {
+            Err(RdpError::Failed(format!(
+                "progressive_decompress() failed: {}",
+                res
+            )))
+        } else {
+            Ok(region.rects())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PIXEL_FORMAT_BGRA32;
+
+    #[test]
+    fn decode_checks_surface() {
+        let mut progressive = ProgressiveContext::new().unwrap();
+        let mut dst = vec![0x55; 64 * 64 * 4];
+        // an unknown block type
+        let src = [0xff, 0xff, 6, 0, 0, 0];
+
+        assert!(progressive
+            .decode(&src, 1, 0, &mut dst, PIXEL_FORMAT_BGRA32, 64 * 4)
+            .is_err());
+
+        progressive.create_surface(1, 64, 64).unwrap();
+        assert!(progressive
+            .decode(&src, 1, 0, &mut dst, PIXEL_FORMAT_BGRA32, 63 * 4)
+            .is_err());
+        assert!(progressive
+            .decode(&src, 1, 0, &mut dst[4..], PIXEL_FORMAT_BGRA32, 64 * 4)
+            .is_err());
+        assert!(progressive
+            .decode(&src, 1, 0, &mut dst, PIXEL_FORMAT_BGRA32, 64 * 4)
+            .is_err());
+        assert!(dst.iter().all(|b| *b == 0x55));
+
+        progressive.delete_surface(1).unwrap();
+        assert!(progressive
+            .decode(&src, 1, 0, &mut dst, PIXEL_FORMAT_BGRA32, 64 * 4)
+            .is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use std::ptr;
+
+use super::Region16;
+use crate::{sys, PixelFormat, RdpError, Rect, Result};
+
+/// A RemoteFX decoder.
+#[derive(Debug)]
+pub struct RfxContext {
+    inner: ptr::NonNull<sys::RFX_CONTEXT>,
+}
+
+unsafe impl Send for RfxContext {}
+
+impl Drop for RfxContext {
+    fn drop(&mut self) {
+        unsafe { sys::rfx_context_free(self.inner.as_ptr()) }
+    }
+}
+
+impl RfxContext {
+    /// A decoder for a `width`x`height` surface.
+    pub fn new(width: u32, height: u32) -> Result<Self> {
+        let inner = unsafe { sys::rfx_context_new(0) };
+        let mut rfx = Self {
+            inner: ptr::NonNull::new(inner).ok_or(RdpError::Unsupported)?,
+        };
+
+        rfx.reset(width, height)?;
+        Ok(rfx)
+    }
+
+    pub fn reset(&mut self, width: u32, height: u32) -> Result<()> {
+        if unsafe { sys::rfx_context_reset(self.inner.as_ptr(), width, height) } == 0 {
+            Err(RdpError::Failed("rfx_context_reset() failed".into()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Decode a message to (`left`, `top`) of `dst`, a buffer of `height`
+    /// rows. Returns the updated rectangles.
+    #[allow(clippy::too_many_arguments)]
+    pub fn decode(
+        &mut self,
+        src: &[u8],
+        left: u32,
+        top: u32,
+        dst: &mut [u8],
+        format: &PixelFormat,
+        stride: u32,
+        height: u32,
+    ) -> Result<Vec<Rect>> {
+        if dst.len() < stride as usize * height as usize {
+            return Err(RdpError::Failed("Destination buffer too small".into()));
+        }
+
+        let mut region = Region16::new();
+        let res = unsafe {
+            sys::rfx_process_message(
+                self.inner.as_ptr(),
+                src.as_ptr(),
+                src.len().try_into()?,
+                left,
+                top,
+                dst.as_mut_ptr(),
+                format.into(),
+                stride,
+                height,
+                &mut region.0,
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("rfx_process_message() failed".into()))
+        } else {
+            Ok(region.rects())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PIXEL_FORMAT_BGRA32;
+
+    #[test]
+    fn new() {
+        let mut rfx = RfxContext::new(64, 64).unwrap();
+        rfx.reset(128, 64).unwrap();
+    }
+
+    #[test]
+    fn decode_malformed() {
+        let mut rfx = RfxContext::new(64, 64).unwrap();
+        let mut dst = vec![0x55; 64 * 64 * 4];
+
+        assert!(rfx
+            .decode(&[0xff; 8], 0, 0, &mut dst, PIXEL_FORMAT_BGRA32, 64 * 4, 64)
+            .is_err());
+        assert!(dst.iter().all(|b| *b == 0x55));
+    }
+}
@@ -0,0 +1,224 @@
+use std::{collections::HashMap, ptr, sync::Arc};
+
+use crate::{gdi::GdiPalette, sys, RdpError, Result, PIXEL_FORMAT_RGBA32};
+
+// Xcursor file format, see Xcursor(3)
+const XCURSOR_MAGIC: &[u8; 4] = b"Xcur";
+const XCURSOR_FILE_HEADER_LEN: u32 = 16;
+const XCURSOR_FILE_VERSION: u32 = 0x0001_0000;
+const XCURSOR_TOC_LEN: u32 = 12;
+const XCURSOR_IMAGE_TYPE: u32 = 0xfffd_0002;
+const XCURSOR_IMAGE_HEADER_LEN: u32 = 36;
+const XCURSOR_IMAGE_VERSION: u32 = 1;
+
+/// A decoded pointer shape.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub width: u32,
+    pub height: u32,
+    pub hotspot: (u32, u32),
+    /// Non-premultiplied RGBA pixels, top-down, `width * 4` bytes per row.
+    pub rgba: Vec<u8>,
+}
+
+impl std::fmt::Debug for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cursor")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("hotspot", &self.hotspot)
+            .finish()
+    }
+}
+
+impl Cursor {
+    /// Decode the XOR and AND masks of a pointer update. A palette is
+    /// required for `xor_bpp` of 8 or less, except monochrome pointers.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn decode(
+        width: u32,
+        height: u32,
+        hotspot: (u32, u32),
+        xor_bpp: u32,
+        xor_mask: *const u8,
+        xor_len: u32,
+        and_mask: *const u8,
+        and_len: u32,
+        palette: Option<&GdiPalette>,
+    ) -> Result<Self> {
+        let len = (width * height * 4) as usize;
+        let mut rgba = Vec::with_capacity(len);
+        let palette = palette.map_or(ptr::null(), |p| p.inner.as_ptr() as *const _);
+        let res = unsafe {
+            sys::freerdp_image_copy_from_pointer_data(
+                rgba.as_mut_ptr(),
+                PIXEL_FORMAT_RGBA32.into(),
+                width * 4,
+                0,
+                0,
+                width,
+                height,
+                xor_mask,
+                xor_len,
+                and_mask,
+                and_len,
+                xor_bpp,
+                palette,
+            )
+        };
+        if res == 0 {
+            return Err(RdpError::Failed(
+                "freerdp_image_copy_from_pointer_data() failed".into(),
+            ));
+        }
+        unsafe { rgba.set_len(len) };
+
+        Ok(Self {
+            width,
+            height,
+            hotspot,
+            rgba,
+        })
+    }
+
+    /// The pixels as premultiplied ARGB, as X11 and most toolkits expect.
+    pub fn to_argb32(&self) -> Vec<u32> {
+        self.rgba
+            .chunks_exact(4)
+            .map(|p| {
+                let a = p[3] as u32;
+                let premul = |c: u8| (c as u32 * a + 127) / 255;
+                a << 24 | premul(p[0]) << 16 | premul(p[1]) << 8 | premul(p[2])
+            })
+            .collect()
+    }
+
+    /// Encode the cursor as an Xcursor file, with a single image.
+    pub fn to_xcursor(&self) -> Vec<u8> {
+        let size = self.width.max(self.height);
+        let position = XCURSOR_FILE_HEADER_LEN + XCURSOR_TOC_LEN;
+        let mut out =
+            Vec::with_capacity((position + XCURSOR_IMAGE_HEADER_LEN) as usize + self.rgba.len());
+
+        out.extend_from_slice(XCURSOR_MAGIC);
+        for v in [XCURSOR_FILE_HEADER_LEN, XCURSOR_FILE_VERSION, 1] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in [XCURSOR_IMAGE_TYPE, size, position] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in [
+            XCURSOR_IMAGE_HEADER_LEN,
+            XCURSOR_IMAGE_TYPE,
+            size,
+            XCURSOR_IMAGE_VERSION,
+            self.width,
+            self.height,
+            self.hotspot.0,
+            self.hotspot.1,
+            0,
+        ] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        for p in self.to_argb32() {
+            out.extend_from_slice(&p.to_le_bytes());
+        }
+
+        out
+    }
+
+    /// The 32 bpp XOR mask, bottom-up BGRA, and an empty AND mask of a
+    /// pointer update.
+    #[cfg(feature = "server")]
+    pub(crate) fn to_pointer_masks(&self) -> (Vec<u8>, Vec<u8>) {
+        let stride = (self.width * 4) as usize;
+        let xor_mask = self
+            .rgba
+            .chunks_exact(stride.max(1))
+            .rev()
+            .flat_map(|row| row.chunks_exact(4).flat_map(|p| [p[2], p[1], p[0], p[3]]))
+            .collect();
+        // rows of the AND mask are padded to 2 bytes
+        let and_mask = vec![0; (self.width.div_ceil(16) * 2 * self.height) as usize];
+
+        (xor_mask, and_mask)
+    }
+
+    /// Encode the cursor as a PNG image. The hotspot isn't part of it.
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        crate::image::encode_png(self.width, self.height, &self.rgba)
+    }
+}
+
+/// The pointers sent by the server, by cache index.
+#[derive(Debug, Default)]
+pub struct CursorCache {
+    cursors: HashMap<u32, Arc<Cursor>>,
+}
+
+impl CursorCache {
+    pub fn get(&self, index: u32) -> Option<Arc<Cursor>> {
+        self.cursors.get(&index).cloned()
+    }
+
+    pub fn insert(&mut self, index: u32, cursor: Cursor) -> Arc<Cursor> {
+        let cursor = Arc::new(cursor);
+        self.cursors.insert(index, cursor.clone());
+        cursor
+    }
+
+    pub fn remove(&mut self, index: u32) -> Option<Arc<Cursor>> {
+        self.cursors.remove(&index)
+    }
+
+    pub fn clear(&mut self) {
+        self.cursors.clear()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cursors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cursors.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xcursor() {
+        let cursor = Cursor {
+            width: 2,
+            height: 1,
+            hotspot: (1, 0),
+            rgba: vec![255, 0, 0, 255, 255, 255, 255, 128],
+        };
+
+        assert_eq!(cursor.to_argb32(), vec![0xffff0000, 0x80808080]);
+
+        let xcur = cursor.to_xcursor();
+        assert_eq!(&xcur[..4], b"Xcur");
+        assert_eq!(xcur.len(), 16 + 12 + 36 + 2 * 4);
+        // xhot
+        assert_eq!(&xcur[28 + 24..28 + 28], &1u32.to_le_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn pointer_masks() {
+        let cursor = Cursor {
+            width: 1,
+            height: 2,
+            hotspot: (0, 0),
+            rgba: vec![1, 2, 3, 4, 5, 6, 7, 8],
+        };
+
+        let (xor_mask, and_mask) = cursor.to_pointer_masks();
+        assert_eq!(xor_mask, vec![7, 6, 5, 8, 3, 2, 1, 4]);
+        assert_eq!(and_mask, vec![0; 4]);
+    }
+}
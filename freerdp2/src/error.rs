@@ -23,7 +23,7 @@ impl TryFrom<u32> for RdpCodeClass {
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RdpErrBase {
     Success = sys::ERRBASE_SUCCESS,
     None = sys::ERRBASE_NONE,
@@ -399,7 +399,7 @@ impl TryFrom<u32> for RdpErrConnect {
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RdpErrInfo {
     RpcInitiatedDisconnected = sys::ERRINFO_RPC_INITIATED_DISCONNECT,
     RpcInitiatedLogoff = sys::ERRINFO_RPC_INITIATED_LOGOFF,
@@ -524,7 +524,7 @@ pub enum RdpErrInfo {
 }
 
 #[repr(u32)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RdpErrConnect {
     PreConnectFailed = sys::ERRCONNECT_PRE_CONNECT_FAILED,
     ConnectUndefined = sys::ERRCONNECT_CONNECT_UNDEFINED,
@@ -557,42 +557,137 @@ pub enum RdpErrConnect {
     None = sys::ERRCONNECT_NONE,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RdpErr {
     RdpErrBase(RdpErrBase),
     RdpErrInfo(RdpErrInfo),
     RdpErrConnect(RdpErrConnect),
 }
 
+fn static_str(ptr: *const std::os::raw::c_char) -> &'static str {
+    if ptr.is_null() {
+        return "";
+    }
+    unsafe { CStr::from_ptr(ptr) }.to_str().unwrap_or_default()
+}
+
+impl RdpErr {
+    /// The full FreeRDP error code, including its class.
+    pub fn code(&self) -> RdpCode {
+        let (class, type_) = match *self {
+            RdpErr::RdpErrBase(e) => (RdpCodeClass::Base, e as u32),
+            RdpErr::RdpErrInfo(e) => (RdpCodeClass::Info, e as u32),
+            RdpErr::RdpErrConnect(e) => (RdpCodeClass::Connect, e as u32),
+        };
+        RdpCode((class as u32) << 16 | type_)
+    }
+
+    /// The symbolic name, such as `ERRCONNECT_DNS_NAME_NOT_FOUND`.
+    pub fn name(&self) -> &'static str {
+        static_str(unsafe {
+            match *self {
+                RdpErr::RdpErrBase(e) => sys::freerdp_get_error_base_name(e as _),
+                RdpErr::RdpErrInfo(e) => sys::freerdp_get_error_info_name(e as _),
+                RdpErr::RdpErrConnect(e) => sys::freerdp_get_error_connect_name(e as _),
+            }
+        })
+    }
+
+    /// The human-readable description, as shown by xfreerdp.
+    pub fn description(&self) -> &'static str {
+        static_str(unsafe {
+            match *self {
+                RdpErr::RdpErrBase(e) => sys::freerdp_get_error_base_string(e as _),
+                RdpErr::RdpErrInfo(e) => sys::freerdp_get_error_info_string(e as _),
+                RdpErr::RdpErrConnect(e) => sys::freerdp_get_error_connect_string(e as _),
+            }
+        })
+    }
+
+    /// The error category, such as `NETWORK` or `SECURITY`.
+    pub fn category(&self) -> &'static str {
+        static_str(unsafe {
+            match *self {
+                RdpErr::RdpErrBase(e) => sys::freerdp_get_error_base_category(e as _),
+                RdpErr::RdpErrInfo(e) => sys::freerdp_get_error_info_category(e as _),
+                RdpErr::RdpErrConnect(e) => sys::freerdp_get_error_connect_category(e as _),
+            }
+        })
+    }
+
+    /// Whether the failure is likely transient, and connecting again may succeed.
+    ///
+    /// Errors caused by the user, the credentials, the licensing, a protocol
+    /// violation or the server running out of memory are not retryable.
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            RdpErr::RdpErrBase(_) => false,
+            RdpErr::RdpErrInfo(e) => matches!(
+                e,
+                RdpErrInfo::CloseStackOnDriverNotReady
+                    | RdpErrInfo::ServerDwmCrash
+                    | RdpErrInfo::ServerWinlogonCrash
+                    | RdpErrInfo::ServerCsrssCrash
+                    | RdpErrInfo::CbLoadingDestination
+                    | RdpErrInfo::CbRedirectingToDestination
+                    | RdpErrInfo::CbSessionOnlineVmWake
+                    | RdpErrInfo::CbSessionOnlineVmBoot
+                    | RdpErrInfo::CbDestinationPoolNotFree
+                    | RdpErrInfo::CbSessionOnlineVmBootTimeout
+                    | RdpErrInfo::GraphicsSubsystemResetFailed
+                    | RdpErrInfo::GraphicsSubsystemFailed
+                    | RdpErrInfo::PeerDisconnected
+            ),
+            RdpErr::RdpErrConnect(e) => matches!(
+                e,
+                RdpErrConnect::DnsError
+                    | RdpErrConnect::ConnectFailed
+                    | RdpErrConnect::McsConnectInitialError
+                    | RdpErrConnect::TlsConnectFailed
+                    | RdpErrConnect::ConnectTransportFailed
+                    | RdpErrConnect::KdcUnreachable
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for RdpErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name(), self.description())
+    }
+}
+
+impl std::error::Error for RdpErr {}
+
 #[repr(transparent)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RdpCode(pub u32);
 
 impl std::fmt::Display for RdpCode {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{} ({:#x}): {}",
-            unsafe {
-                CStr::from_ptr(sys::freerdp_get_last_error_name(self.0))
-                    .to_str()
-                    .unwrap()
-            },
-            self.0,
-            unsafe {
-                CStr::from_ptr(sys::freerdp_get_last_error_string(self.0))
-                    .to_str()
-                    .unwrap()
-            },
-        )
+        write!(f, "{} ({:#x}): {}", self.name(), self.0, self.description())
     }
 }
 
+impl std::error::Error for RdpCode {}
+
 impl RdpCode {
     pub fn class(&self) -> Option<RdpCodeClass> {
         (self.0 >> 16).try_into().ok()
     }
 
+    pub fn name(&self) -> &'static str {
+        static_str(unsafe { sys::freerdp_get_last_error_name(self.0) })
+    }
+
+    pub fn description(&self) -> &'static str {
+        static_str(unsafe { sys::freerdp_get_last_error_string(self.0) })
+    }
+
+    pub fn category(&self) -> &'static str {
+        static_str(unsafe { sys::freerdp_get_last_error_category(self.0) })
+    }
+
     pub fn as_err(&self) -> Option<RdpErr> {
         let type_ = self.0 & 0xffff;
         self.class().and_then(|c| match c {
@@ -674,3 +769,35 @@ impl From<TryFromIntError> for RdpError {
 }
 
 pub type Result<T> = std::result::Result<T, RdpError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn err_code() {
+        let code = RdpCode(0x20005);
+        let err = code.as_err().unwrap();
+        assert_eq!(err, RdpErr::RdpErrConnect(RdpErrConnect::DnsNameNotFound));
+        assert_eq!(err.code(), code);
+        assert_eq!(err.name(), "ERRCONNECT_DNS_NAME_NOT_FOUND");
+        assert_eq!(err.name(), code.name());
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn retryable() {
+        assert!(!RdpErr::RdpErrInfo(RdpErrInfo::OutOfMemory).is_retryable());
+        assert!(RdpErr::RdpErrInfo(RdpErrInfo::ServerDwmCrash).is_retryable());
+        assert!(RdpErr::RdpErrConnect(RdpErrConnect::ConnectFailed).is_retryable());
+    }
+
+    #[test]
+    fn code_has_no_source() {
+        use std::error::Error;
+
+        // the code is already part of the message
+        let err = RdpError::Code(RdpCode(0x20005));
+        assert!(err.source().is_none());
+    }
+}
@@ -55,6 +55,10 @@ impl FreeRdp {
         }
     }
 
+    pub fn error_info(&self) -> u32 {
+        unsafe { sys::freerdp_error_info(self.inner.as_ptr()) }
+    }
+
     pub fn gdi_init(&mut self, format: &PixelFormat) -> Result<()> {
         if unsafe { sys::gdi_init(self.inner.as_ptr(), format.into()) > 0 } {
             Ok(())
@@ -1,7 +1,10 @@
 use core::slice;
 use std::{marker::PhantomData, ptr};
 
-use crate::{sys, RdpError, Result};
+use crate::{
+    pixel_format::split_color, sys, update::PaletteEntry, Image, PixelFormat, RdpError, Rect,
+    Result, PIXEL_FORMAT_RGB8,
+};
 
 pub mod gfx;
 
@@ -19,6 +22,21 @@ impl GdiPalette {
             inner: ptr::NonNull::new(palette).unwrap(),
         }
     }
+
+    /// The colors, as last set by the server.
+    pub fn entries(&self) -> Result<Vec<PaletteEntry>> {
+        let palette = unsafe { self.inner.as_ref() };
+        let channels = PixelFormat::try_from(palette.format)?.channels()?;
+
+        Ok(palette
+            .palette
+            .iter()
+            .map(|c| {
+                let [red, green, blue, _] = split_color(&channels, *c);
+                PaletteEntry { red, green, blue }
+            })
+            .collect())
+    }
 }
 
 #[derive(Debug)]
@@ -179,4 +197,187 @@ impl<'a> Gdi<'a> {
     pub fn palette(&self) -> &GdiPalette {
         &self.palette
     }
+
+    /// A copy of the primary surface.
+    pub fn snapshot(&self) -> Option<Image> {
+        let fb = self.frame_buffer()?;
+        let format = fb.format().ok()?;
+        let palette = if format == *PIXEL_FORMAT_RGB8 {
+            Some(self.palette.entries().ok()?)
+        } else {
+            None
+        };
+
+        Some(Image {
+            width: fb.width(),
+            height: fb.height(),
+            stride: fb.stride(),
+            format,
+            data: fb.data().to_vec(),
+            palette,
+        })
+    }
+
+    /// The primary surface, once the GDI is initialized.
+    pub fn frame_buffer(&self) -> Option<FrameBuffer<'a>> {
+        let gdi = unsafe { self.inner.as_ref() };
+        if gdi.primary_buffer.is_null() {
+            None
+        } else {
+            Some(FrameBuffer {
+                inner: self.inner,
+                _lifetime: PhantomData,
+            })
+        }
+    }
+}
+
+/// A view of the GDI primary surface, and of the regions drawn since the
+/// last call to `dirty_rects()`.
+#[derive(Debug)]
+pub struct FrameBuffer<'a> {
+    inner: ptr::NonNull<sys::rdpGdi>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> FrameBuffer<'a> {
+    pub fn width(&self) -> u32 {
+        unsafe { self.inner.as_ref() }.width.max(0) as _
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { self.inner.as_ref() }.height.max(0) as _
+    }
+
+    pub fn stride(&self) -> u32 {
+        unsafe { self.inner.as_ref() }.stride
+    }
+
+    pub fn format(&self) -> Result<PixelFormat> {
+        unsafe { self.inner.as_ref() }.dstFormat.try_into()
+    }
+
+    pub fn data(&self) -> &[u8] {
+        let gdi = unsafe { self.inner.as_ref() };
+        let len = self.stride() as usize * self.height() as usize;
+        unsafe { slice::from_raw_parts(gdi.primary_buffer, len) }
+    }
+
+    fn bounds(&self, x: i32, y: i32, w: i32, h: i32) -> Option<Rect> {
+        let clamp = |v: i32, max: u32| v.clamp(0, max.min(u16::MAX.into()) as i32) as u16;
+        let rect = Rect {
+            left: clamp(x, self.width()),
+            top: clamp(y, self.height()),
+            right: clamp(x.saturating_add(w), self.width()),
+            bottom: clamp(y.saturating_add(h), self.height()),
+        };
+
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// The regions invalidated since the last call, clipped to the surface,
+    /// with the overlapping ones merged. The GDI invalid region is reset.
+    pub fn dirty_rects(&mut self) -> impl Iterator<Item = Rect> {
+        let gdi = unsafe { self.inner.as_ref() };
+        let mut rects = Vec::new();
+
+        let hwnd = unsafe { gdi.primary.as_ref() }
+            .and_then(|primary| unsafe { primary.hdc.as_ref() })
+            .and_then(|hdc| unsafe { hdc.hwnd.as_mut() });
+        if let Some(hwnd) = hwnd {
+            if let Some(invalid) = unsafe { hwnd.invalid.as_mut() } {
+                if invalid.null == 0 {
+                    if hwnd.ninvalid > 0 && !hwnd.cinvalid.is_null() {
+                        let cinvalid =
+                            unsafe { slice::from_raw_parts(hwnd.cinvalid, hwnd.ninvalid as _) };
+                        rects.extend(
+                            cinvalid
+                                .iter()
+                                .filter_map(|r| self.bounds(r.x, r.y, r.w, r.h)),
+                        );
+                    } else {
+                        rects.extend(self.bounds(invalid.x, invalid.y, invalid.w, invalid.h));
+                    }
+                }
+                invalid.null = 1;
+            }
+            hwnd.ninvalid = 0;
+        }
+
+        merge_rects(rects).into_iter()
+    }
+
+    /// Copy a region of the surface to `dst`, packed with `format`.
+    pub fn copy_rect_to(&self, rect: &Rect, dst: &mut [u8], format: &PixelFormat) -> Result<()> {
+        if rect.is_empty()
+            || u32::from(rect.right) > self.width()
+            || u32::from(rect.bottom) > self.height()
+        {
+            return Err(RdpError::Failed("Rectangle out of the frame buffer".into()));
+        }
+
+        let step = u32::from(rect.width()) * format.bytes_per_pixel();
+        if dst.len() < step as usize * rect.height() as usize {
+            return Err(RdpError::Failed("Destination buffer too small".into()));
+        }
+
+        let gdi = unsafe { self.inner.as_ref() };
+        let res = unsafe {
+            sys::freerdp_image_copy(
+                dst.as_mut_ptr(),
+                format.into(),
+                step,
+                0,
+                0,
+                rect.width().into(),
+                rect.height().into(),
+                gdi.primary_buffer,
+                gdi.dstFormat,
+                gdi.stride,
+                rect.left.into(),
+                rect.top.into(),
+                &gdi.palette,
+                sys::FREERDP_FLIP_NONE,
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("freerdp_image_copy() failed".into()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn merge_rects(rects: Vec<Rect>) -> Vec<Rect> {
+    let mut merged: Vec<Rect> = Vec::with_capacity(rects.len());
+
+    for mut rect in rects {
+        // a union may now overlap with rectangles already merged
+        while let Some(i) = merged.iter().position(|r| r.intersects(&rect)) {
+            rect = rect.union(&merged.swap_remove(i));
+        }
+        merged.push(rect);
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge() {
+        let rects = vec![
+            Rect::new(0, 0, 10, 10),
+            Rect::new(20, 0, 10, 10),
+            Rect::new(50, 50, 5, 5),
+            Rect::new(5, 5, 20, 2),
+        ];
+
+        let merged = merge_rects(rects);
+        assert_eq!(merged.len(), 2);
+        assert!(merged.contains(&Rect::new(50, 50, 5, 5)));
+        assert!(merged.contains(&Rect::new(0, 0, 30, 10)));
+    }
 }
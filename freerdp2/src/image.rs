@@ -0,0 +1,501 @@
+use std::ptr;
+
+use crate::{
+    gdi::GdiPalette, pixel_format::split_color, sys, update::PaletteEntry, PixelFormat, RdpError,
+    Rect, Result, PIXEL_FORMAT_XRGB32,
+};
+
+/// A copy of pixels, in the layout of `format`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// The length of a row in bytes, including padding.
+    pub stride: u32,
+    pub format: PixelFormat,
+    pub data: Vec<u8>,
+    /// The colors of an 8 bpp image, indexed by the pixel values.
+    pub palette: Option<Vec<PaletteEntry>>,
+}
+
+impl std::fmt::Debug for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Image")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("stride", &self.stride)
+            .field("format", &self.format)
+            .field("palette", &self.palette.as_ref().map(Vec::len))
+            .finish()
+    }
+}
+
+pub(crate) fn palette_ptr(palette: Option<&GdiPalette>) -> *const sys::gdiPalette {
+    palette.map_or(ptr::null(), |p| p.inner.as_ptr() as *const _)
+}
+
+// The length of the pixels of a row in bytes, sub-byte formats packed.
+pub(crate) fn row_len(width: u32, format: &PixelFormat) -> u32 {
+    if format.bpp() < 8 {
+        (width * format.bpp() as u32).div_ceil(8)
+    } else {
+        width * format.bytes_per_pixel()
+    }
+}
+
+impl Image {
+    /// A zeroed image, with rows packed, padded to a byte for 1 and 4 bpp.
+    pub fn new(width: u32, height: u32, format: &PixelFormat) -> Self {
+        let stride = row_len(width, format);
+
+        Self {
+            width,
+            height,
+            stride,
+            format: *format,
+            data: vec![0; stride as usize * height as usize],
+            palette: None,
+        }
+    }
+
+    fn check_rect(&self, x: u32, y: u32, width: u32, height: u32) -> Result<()> {
+        let fits = x.checked_add(width).is_some_and(|r| r <= self.width)
+            && y.checked_add(height).is_some_and(|b| b <= self.height);
+        let len = self.stride as usize * self.height as usize;
+        if !fits || self.data.len() < len || self.stride < row_len(self.width, &self.format) {
+            return Err(RdpError::Failed("Rectangle out of the image".into()));
+        }
+        Ok(())
+    }
+
+    /// Copy `rect` of `src` at (`x`, `y`), converting the pixels. 8 bpp
+    /// sources use `palette`, or else their own.
+    pub fn copy_from(
+        &mut self,
+        x: u32,
+        y: u32,
+        src: &Image,
+        rect: &Rect,
+        palette: Option<&GdiPalette>,
+    ) -> Result<()> {
+        let (w, h) = (rect.width().into(), rect.height().into());
+        self.check_rect(x, y, w, h)?;
+        src.check_rect(rect.left.into(), rect.top.into(), w, h)?;
+        let own_palette = match (palette, &src.palette) {
+            (None, Some(entries)) => Some(gdi_palette(entries)),
+            _ => None,
+        };
+        let palette = match &own_palette {
+            Some(p) => p as *const _,
+            None => palette_ptr(palette),
+        };
+
+        let res = unsafe {
+            sys::freerdp_image_copy(
+                self.data.as_mut_ptr(),
+                (&self.format).into(),
+                self.stride,
+                x,
+                y,
+                w,
+                h,
+                src.data.as_ptr(),
+                (&src.format).into(),
+                src.stride,
+                rect.left.into(),
+                rect.top.into(),
+                palette,
+                sys::FREERDP_FLIP_NONE,
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("freerdp_image_copy() failed".into()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// A copy of the image in another format.
+    pub fn convert(&self, format: &PixelFormat, palette: Option<&GdiPalette>) -> Result<Image> {
+        let mut dst = Image::new(self.width, self.height, format);
+        let rect = Rect {
+            left: 0,
+            top: 0,
+            right: u16::try_from(self.width)?,
+            bottom: u16::try_from(self.height)?,
+        };
+
+        dst.copy_from(0, 0, self, &rect, palette)?;
+        Ok(dst)
+    }
+
+    /// Fill `rect` with `color`, in the image format, see `PixelFormat::color()`.
+    pub fn fill(&mut self, rect: &Rect, color: u32) -> Result<()> {
+        let (w, h) = (rect.width().into(), rect.height().into());
+        self.check_rect(rect.left.into(), rect.top.into(), w, h)?;
+
+        let res = unsafe {
+            sys::freerdp_image_fill(
+                self.data.as_mut_ptr(),
+                (&self.format).into(),
+                self.stride,
+                rect.left.into(),
+                rect.top.into(),
+                w,
+                h,
+                color,
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("freerdp_image_fill() failed".into()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// A resized copy of the image, in the same format.
+    pub fn scale(&self, width: u32, height: u32) -> Result<Image> {
+        self.check_rect(0, 0, self.width, self.height)?;
+        let mut dst = Image::new(width, height, &self.format);
+
+        let res = unsafe {
+            sys::freerdp_image_scale(
+                dst.data.as_mut_ptr(),
+                (&dst.format).into(),
+                dst.stride,
+                0,
+                0,
+                width,
+                height,
+                self.data.as_ptr(),
+                (&self.format).into(),
+                self.stride,
+                0,
+                0,
+                self.width,
+                self.height,
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed("freerdp_image_scale() failed".into()))
+        } else {
+            Ok(dst)
+        }
+    }
+
+    /// Expand a 1 bpp bitmap, with rows padded to a byte, using
+    /// `back_color` for set bits, as in the glyph and pointer masks. The
+    /// colors are in `format`.
+    pub fn from_monochrome(
+        width: u32,
+        height: u32,
+        bits: &[u8],
+        back_color: u32,
+        fore_color: u32,
+        format: &PixelFormat,
+    ) -> Result<Image> {
+        if bits.len() < width.div_ceil(8) as usize * height as usize {
+            return Err(RdpError::Failed("Monochrome data too short".into()));
+        }
+        let mut dst = Image::new(width, height, format);
+
+        let res = unsafe {
+            sys::freerdp_image_copy_from_monochrome(
+                dst.data.as_mut_ptr(),
+                format.into(),
+                dst.stride,
+                0,
+                0,
+                width,
+                height,
+                bits.as_ptr(),
+                back_color,
+                fore_color,
+                ptr::null(),
+            )
+        };
+        if res == 0 {
+            Err(RdpError::Failed(
+                "freerdp_image_copy_from_monochrome() failed".into(),
+            ))
+        } else {
+            Ok(dst)
+        }
+    }
+
+    /// Convert to non-premultiplied RGBA, 4 bytes per pixel with no padding.
+    /// 8 bpp images need a palette. 1 and 4 bpp images are expanded to gray
+    /// levels with a matching alpha, as FreeRDP does for monochrome.
+    pub fn to_rgba(&self) -> Result<Vec<u8>> {
+        let bpp = self.format.bpp();
+        let row_len = row_len(self.width, &self.format) as usize;
+        if self.height > 0
+            && self.data.len() < (self.height as usize - 1) * self.stride as usize + row_len
+        {
+            return Err(RdpError::Failed("Image data too short".into()));
+        }
+        let rows =
+            (0..self.height as usize).map(|y| &self.data[y * self.stride as usize..][..row_len]);
+        let mut rgba = Vec::with_capacity(self.width as usize * self.height as usize * 4);
+
+        match bpp {
+            1 | 4 => {
+                let max = (1u32 << bpp) - 1;
+                let per_byte = 8 / bpp as usize;
+                for row in rows {
+                    for x in 0..self.width as usize {
+                        // the first pixel in the high bits
+                        let shift = 8 - bpp as usize * (x % per_byte + 1);
+                        let v = (row[x / per_byte] as u32 >> shift) & max;
+                        let v = (v * 255 / max) as u8;
+                        rgba.extend_from_slice(&[v, v, v, v]);
+                    }
+                }
+            }
+            8 => {
+                let palette = self
+                    .palette
+                    .as_ref()
+                    .ok_or_else(|| RdpError::Failed("8 bpp image without palette".into()))?;
+                for row in rows {
+                    for px in row {
+                        let e = palette.get(*px as usize).ok_or_else(|| {
+                            RdpError::Failed(format!("No palette entry for {}", px))
+                        })?;
+                        rgba.extend_from_slice(&[e.red, e.green, e.blue, 0xff]);
+                    }
+                }
+            }
+            9..=32 => {
+                let bytes = self.format.bytes_per_pixel() as usize;
+                let channels = self.format.channels()?;
+                for row in rows {
+                    for px in row.chunks_exact(bytes) {
+                        // FreeRDP reads 24 and 32 bpp big-endian, 15 and 16 bpp little-endian
+                        let color = if bytes == 2 {
+                            u16::from_le_bytes([px[0], px[1]]) as u32
+                        } else {
+                            px.iter().fold(0u32, |c, b| c << 8 | *b as u32)
+                        };
+                        rgba.extend_from_slice(&split_color(&channels, color));
+                    }
+                }
+            }
+            _ => return Err(RdpError::Unsupported),
+        }
+
+        Ok(rgba)
+    }
+
+    /// Encode as a PNG image.
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        encode_png(self.width, self.height, &self.to_rgba()?)
+    }
+
+    /// Encode as a top-down 32 bpp BMP file.
+    #[cfg(feature = "bmp")]
+    pub fn to_bmp(&self) -> Result<Vec<u8>> {
+        const FILE_HEADER_LEN: u32 = 14;
+        const INFO_HEADER_LEN: u32 = 40;
+
+        let rgba = self.to_rgba()?;
+        let offset = FILE_HEADER_LEN + INFO_HEADER_LEN;
+        let size = u32::try_from(rgba.len())?;
+        let mut out = Vec::with_capacity((offset + size) as usize);
+
+        out.extend_from_slice(b"BM");
+        out.extend_from_slice(&(offset + size).to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&offset.to_le_bytes());
+
+        out.extend_from_slice(&INFO_HEADER_LEN.to_le_bytes());
+        out.extend_from_slice(&i32::try_from(self.width)?.to_le_bytes());
+        // negative for top-down rows
+        out.extend_from_slice(&(-i32::try_from(self.height)?).to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&32u16.to_le_bytes());
+        // BI_RGB
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes());
+        // 96 DPI
+        out.extend_from_slice(&3780i32.to_le_bytes());
+        out.extend_from_slice(&3780i32.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+
+        for px in rgba.chunks_exact(4) {
+            out.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+        }
+
+        Ok(out)
+    }
+}
+
+// A FreeRDP palette of `entries`, for freerdp_image_copy().
+fn gdi_palette(entries: &[PaletteEntry]) -> sys::gdi_palette {
+    let mut palette: sys::gdi_palette = unsafe { std::mem::zeroed() };
+    palette.format = PIXEL_FORMAT_XRGB32.into();
+    for (c, e) in palette.palette.iter_mut().zip(entries) {
+        *c = (e.red as u32) << 16 | (e.green as u32) << 8 | e.blue as u32;
+    }
+    palette
+}
+
+#[cfg(feature = "png")]
+pub(crate) fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| RdpError::Failed(e.to_string()))?;
+    writer
+        .write_image_data(rgba)
+        .map_err(|e| RdpError::Failed(e.to_string()))?;
+    writer
+        .finish()
+        .map_err(|e| RdpError::Failed(e.to_string()))?;
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        PIXEL_FORMAT_A4, PIXEL_FORMAT_ARGB32, PIXEL_FORMAT_BGRA32, PIXEL_FORMAT_BGRX32,
+        PIXEL_FORMAT_MONO, PIXEL_FORMAT_RGB8, PIXEL_FORMAT_RGBA32,
+    };
+
+    fn image(format: &PixelFormat, data: Vec<u8>) -> Image {
+        Image {
+            width: 1,
+            height: 1,
+            stride: data.len() as _,
+            format: *format,
+            data,
+            palette: None,
+        }
+    }
+
+    #[test]
+    fn to_rgba() {
+        let rgba = image(PIXEL_FORMAT_BGRX32, vec![1, 2, 3, 0])
+            .to_rgba()
+            .unwrap();
+        assert_eq!(rgba, [3, 2, 1, 0xff]);
+
+        let rgba = image(PIXEL_FORMAT_ARGB32, vec![4, 1, 2, 3])
+            .to_rgba()
+            .unwrap();
+        assert_eq!(rgba, [1, 2, 3, 4]);
+
+        let rgba = image(PIXEL_FORMAT_RGBA32, vec![1, 2, 3, 4])
+            .to_rgba()
+            .unwrap();
+        assert_eq!(rgba, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn to_rgba_palette() {
+        let mut img = image(PIXEL_FORMAT_RGB8, vec![1]);
+        assert!(img.to_rgba().is_err());
+
+        let entry = |v| PaletteEntry {
+            red: v,
+            green: v + 1,
+            blue: v + 2,
+        };
+        img.palette = Some(vec![entry(0), entry(10)]);
+        assert_eq!(img.to_rgba().unwrap(), [10, 11, 12, 0xff]);
+
+        img.data[0] = 2;
+        assert!(img.to_rgba().is_err());
+    }
+
+    #[test]
+    fn to_rgba_sub_byte() {
+        let mut img = image(PIXEL_FORMAT_MONO, vec![0b1010_0000]);
+        img.width = 3;
+        assert_eq!(
+            img.to_rgba().unwrap(),
+            [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]
+        );
+
+        let mut img = image(PIXEL_FORMAT_A4, vec![0xf0, 0x50]);
+        img.width = 3;
+        assert_eq!(
+            img.to_rgba().unwrap(),
+            [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0x55, 0x55, 0x55, 0x55]
+        );
+    }
+
+    #[test]
+    fn new_stride() {
+        assert_eq!(Image::new(9, 2, PIXEL_FORMAT_MONO).stride, 2);
+        assert_eq!(Image::new(3, 2, PIXEL_FORMAT_A4).stride, 2);
+        assert_eq!(Image::new(3, 2, PIXEL_FORMAT_RGB8).stride, 3);
+        let img = Image::new(3, 2, PIXEL_FORMAT_BGRX32);
+        assert_eq!((img.stride, img.data.len()), (12, 24));
+    }
+
+    #[test]
+    fn copy_from() {
+        let src = image(PIXEL_FORMAT_BGRX32, vec![1, 2, 3, 0]);
+        let mut dst = Image::new(2, 1, PIXEL_FORMAT_ARGB32);
+        dst.copy_from(1, 0, &src, &Rect::new(0, 0, 1, 1), None)
+            .unwrap();
+        assert_eq!(dst.data, [0, 0, 0, 0, 0xff, 3, 2, 1]);
+
+        assert!(dst
+            .copy_from(2, 0, &src, &Rect::new(0, 0, 1, 1), None)
+            .is_err());
+        assert!(dst
+            .copy_from(0, 0, &src, &Rect::new(0, 0, 2, 1), None)
+            .is_err());
+    }
+
+    #[test]
+    fn copy_from_palette() {
+        let mut src = image(PIXEL_FORMAT_RGB8, vec![1]);
+        let entry = |red, green, blue| PaletteEntry { red, green, blue };
+        src.palette = Some(vec![entry(0, 0, 0), entry(1, 2, 3)]);
+        let dst = src.convert(PIXEL_FORMAT_BGRA32, None).unwrap();
+        assert_eq!(dst.data, [3, 2, 1, 0xff]);
+    }
+
+    #[test]
+    fn fill() {
+        let mut img = Image::new(3, 2, PIXEL_FORMAT_BGRX32);
+        let color = PIXEL_FORMAT_BGRX32.color(1, 2, 3, 0).unwrap();
+        img.fill(&Rect::new(1, 1, 2, 1), color).unwrap();
+        assert_eq!(&img.data[..16], &[0; 16]);
+        assert_eq!(&img.data[16..], &[3, 2, 1, 0, 3, 2, 1, 0]);
+
+        assert!(img.fill(&Rect::new(2, 0, 2, 1), color).is_err());
+    }
+
+    #[test]
+    fn scale() {
+        let img = image(PIXEL_FORMAT_BGRX32, vec![1, 2, 3, 0]);
+        let scaled = img.scale(2, 2).unwrap();
+        assert_eq!((scaled.width, scaled.height, scaled.stride), (2, 2, 8));
+        assert_eq!(scaled.data, [1, 2, 3, 0].repeat(4));
+    }
+
+    #[test]
+    fn from_monochrome() {
+        let (back, fore) = (0x01020300, 0x04050600);
+        let img =
+            Image::from_monochrome(9, 1, &[0x80, 0x80], back, fore, PIXEL_FORMAT_BGRX32).unwrap();
+        let px: Vec<_> = img.data.chunks_exact(4).collect();
+        assert_eq!(px[0], &[1, 2, 3, 0]);
+        assert_eq!(px[1], &[4, 5, 6, 0]);
+        assert_eq!(px[8], &[1, 2, 3, 0]);
+
+        assert!(Image::from_monochrome(9, 1, &[0x80], back, fore, PIXEL_FORMAT_BGRX32).is_err());
+    }
+}
@@ -6,14 +6,26 @@ pub use connection_type::*;
 mod pixel_format;
 pub use pixel_format::*;
 
+mod rect;
+pub use rect::*;
+
+mod cursor;
+pub use cursor::*;
+
+mod image;
+pub use image::*;
+
 mod error;
 pub use error::*;
 
 mod freerdp;
 pub use freerdp::*;
 
-mod settings;
-pub use settings::*;
+pub mod settings;
+pub use settings::Settings;
+
+mod assistance;
+pub use assistance::*;
 
 pub mod gdi;
 
@@ -23,10 +35,22 @@ pub mod input;
 
 pub mod locale;
 
+pub mod primary;
+
 pub mod update;
 
+pub mod window;
+
 pub mod channels;
 
+pub mod codec;
+
 pub mod client;
 
+#[cfg(feature = "server")]
+pub mod server;
+
+#[cfg(all(unix, feature = "testing"))]
+pub mod testing;
+
 pub mod winpr;
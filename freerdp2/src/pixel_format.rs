@@ -1,3 +1,6 @@
+use crate::{sys, RdpError, Result};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum PixelFormatType {
     A,
     ARGB,
@@ -18,6 +21,22 @@ impl From<&PixelFormatType> for u32 {
     }
 }
 
+impl TryFrom<u32> for PixelFormatType {
+    type Error = RdpError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(PixelFormatType::A),
+            1 => Ok(PixelFormatType::ARGB),
+            2 => Ok(PixelFormatType::ABGR),
+            3 => Ok(PixelFormatType::RGBA),
+            4 => Ok(PixelFormatType::BGRA),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct PixelFormat {
     bpp: u8,
     type_: PixelFormatType,
@@ -38,6 +57,103 @@ impl From<&PixelFormat> for u32 {
     }
 }
 
+impl TryFrom<u32> for PixelFormat {
+    type Error = RdpError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        let fmt = Self {
+            bpp: (value >> 24) as u8,
+            type_: ((value >> 16) & 0x07).try_into()?,
+            a: ((value >> 12) & 0x0F) as u8,
+            r: ((value >> 8) & 0x0F) as u8,
+            g: ((value >> 4) & 0x0F) as u8,
+            b: (value & 0x0F) as u8,
+        };
+        if fmt.bpp == 0 || fmt.a + fmt.r + fmt.g + fmt.b > fmt.bpp {
+            return Err(RdpError::Unsupported);
+        }
+
+        Ok(fmt)
+    }
+}
+
+impl PixelFormat {
+    pub fn bpp(&self) -> u8 {
+        self.bpp
+    }
+
+    pub fn type_(&self) -> PixelFormatType {
+        self.type_
+    }
+
+    /// The number of bits of the alpha, red, green and blue components.
+    pub fn channel_bits(&self) -> (u32, u32, u32, u32) {
+        (self.a as _, self.r as _, self.g as _, self.b as _)
+    }
+
+    pub fn bytes_per_pixel(&self) -> u32 {
+        (self.bpp as u32).div_ceil(8)
+    }
+
+    pub fn has_alpha(&self) -> bool {
+        self.a > 0
+    }
+
+    /// The format GDI uses for a color depth, as given by the server.
+    pub fn from_depth(bpp: u32) -> Result<Self> {
+        unsafe { sys::gdi_get_pixel_format(bpp) }.try_into()
+    }
+
+    // The bit shift and width of each component, alpha first, in the
+    // value FreeRDP reads and writes for a pixel. Without alpha, padding
+    // bits take its place and its width is 0.
+    pub(crate) fn channels(&self) -> Result<[(u32, u32); 4]> {
+        let (a, r, g, b) = self.channel_bits();
+        let bpp = self.bpp as u32;
+        let x = if a == 0 { bpp - r - g - b } else { a };
+
+        let order: [(usize, u32); 4] = match self.type_ {
+            PixelFormatType::ARGB => [(0, x), (1, r), (2, g), (3, b)],
+            PixelFormatType::ABGR => [(0, x), (3, b), (2, g), (1, r)],
+            PixelFormatType::RGBA => [(1, r), (2, g), (3, b), (0, x)],
+            PixelFormatType::BGRA => [(3, b), (2, g), (1, r), (0, x)],
+            PixelFormatType::A => return Err(RdpError::Unsupported),
+        };
+
+        let mut shift = bpp;
+        let mut res = [(0, 0); 4];
+        for (i, bits) in order {
+            shift -= bits;
+            res[i] = (shift, bits);
+        }
+        if a == 0 {
+            res[0].1 = 0;
+        }
+        Ok(res)
+    }
+
+    /// The value of a color in this format, as used by `Image::fill()`.
+    /// Components are scaled down from 8 bits.
+    pub fn color(&self, r: u8, g: u8, b: u8, a: u8) -> Result<u32> {
+        let [ca, cr, cg, cb] = self.channels()?;
+        let component = |v: u8, (shift, bits): (u32, u32)| (v as u32 >> (8 - bits)) << shift;
+
+        Ok(component(a, ca) | component(r, cr) | component(g, cg) | component(b, cb))
+    }
+}
+
+// Split a pixel value with the channels of its format, scaling the
+// components up to 8 bits. Without alpha, the color is opaque.
+pub(crate) fn split_color(channels: &[(u32, u32); 4], color: u32) -> [u8; 4] {
+    let component = |(shift, bits): (u32, u32)| -> u8 {
+        let max = (1u32 << bits) - 1;
+        ((color >> shift & max) * 255 / max) as u8
+    };
+    let [a, r, g, b] = *channels;
+    let alpha = if a.1 == 0 { 0xff } else { component(a) };
+    [component(r), component(g), component(b), alpha]
+}
+
 pub const PIXEL_FORMAT_ARGB32: &PixelFormat = &PixelFormat {
     bpp: 32,
     type_: PixelFormatType::ARGB,
@@ -109,3 +225,136 @@ pub const PIXEL_FORMAT_RGBX32: &PixelFormat = &PixelFormat {
     g: 8,
     b: 8,
 };
+
+pub const PIXEL_FORMAT_RGB24: &PixelFormat = &PixelFormat {
+    bpp: 24,
+    type_: PixelFormatType::ARGB,
+    a: 0,
+    r: 8,
+    g: 8,
+    b: 8,
+};
+
+pub const PIXEL_FORMAT_BGR24: &PixelFormat = &PixelFormat {
+    bpp: 24,
+    type_: PixelFormatType::ABGR,
+    a: 0,
+    r: 8,
+    g: 8,
+    b: 8,
+};
+
+pub const PIXEL_FORMAT_RGB16: &PixelFormat = &PixelFormat {
+    bpp: 16,
+    type_: PixelFormatType::ARGB,
+    a: 0,
+    r: 5,
+    g: 6,
+    b: 5,
+};
+
+pub const PIXEL_FORMAT_BGR16: &PixelFormat = &PixelFormat {
+    bpp: 16,
+    type_: PixelFormatType::ABGR,
+    a: 0,
+    r: 5,
+    g: 6,
+    b: 5,
+};
+
+pub const PIXEL_FORMAT_ARGB15: &PixelFormat = &PixelFormat {
+    bpp: 16,
+    type_: PixelFormatType::ARGB,
+    a: 1,
+    r: 5,
+    g: 5,
+    b: 5,
+};
+
+pub const PIXEL_FORMAT_RGB15: &PixelFormat = &PixelFormat {
+    bpp: 15,
+    type_: PixelFormatType::ARGB,
+    a: 0,
+    r: 5,
+    g: 5,
+    b: 5,
+};
+
+pub const PIXEL_FORMAT_ABGR15: &PixelFormat = &PixelFormat {
+    bpp: 16,
+    type_: PixelFormatType::ABGR,
+    a: 1,
+    r: 5,
+    g: 5,
+    b: 5,
+};
+
+pub const PIXEL_FORMAT_BGR15: &PixelFormat = &PixelFormat {
+    bpp: 15,
+    type_: PixelFormatType::ABGR,
+    a: 0,
+    r: 5,
+    g: 5,
+    b: 5,
+};
+
+pub const PIXEL_FORMAT_RGB8: &PixelFormat = &PixelFormat {
+    bpp: 8,
+    type_: PixelFormatType::A,
+    a: 0,
+    r: 0,
+    g: 0,
+    b: 8,
+};
+
+pub const PIXEL_FORMAT_A4: &PixelFormat = &PixelFormat {
+    bpp: 4,
+    type_: PixelFormatType::A,
+    a: 4,
+    r: 0,
+    g: 0,
+    b: 0,
+};
+
+pub const PIXEL_FORMAT_MONO: &PixelFormat = &PixelFormat {
+    bpp: 1,
+    type_: PixelFormatType::A,
+    a: 1,
+    r: 0,
+    g: 0,
+    b: 0,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_u32() {
+        for fmt in [
+            PIXEL_FORMAT_ARGB32,
+            PIXEL_FORMAT_BGRX32,
+            PIXEL_FORMAT_RGB24,
+            PIXEL_FORMAT_BGR16,
+            PIXEL_FORMAT_RGB15,
+            PIXEL_FORMAT_RGB8,
+            PIXEL_FORMAT_MONO,
+        ] {
+            assert_eq!(PixelFormat::try_from(u32::from(fmt)).unwrap(), *fmt);
+        }
+        // FreeRDP's PIXEL_FORMAT_BGRA32
+        assert_eq!(
+            PixelFormat::try_from(0x20048888).unwrap(),
+            *PIXEL_FORMAT_BGRA32
+        );
+        assert!(PixelFormat::try_from(0).is_err());
+    }
+
+    #[test]
+    fn color() {
+        assert_eq!(PIXEL_FORMAT_ARGB32.color(1, 2, 3, 4).unwrap(), 0x04010203);
+        assert_eq!(PIXEL_FORMAT_RGBX32.color(1, 2, 3, 4).unwrap(), 0x01020300);
+        assert_eq!(PIXEL_FORMAT_RGB16.color(0xff, 0, 0xff, 0).unwrap(), 0xf81f);
+        assert!(PIXEL_FORMAT_RGB8.color(0, 0, 0, 0).is_err());
+    }
+}
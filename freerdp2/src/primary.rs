@@ -0,0 +1,849 @@
+use std::{fmt::Debug, marker::PhantomData, ptr, slice};
+
+use crate::{
+    client::{Context, Handler, RdpContext},
+    sys,
+    update::Bounds,
+    Result,
+};
+
+// The previous callbacks, see the chaining section of the update module docs.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct Chain {
+    dst_blt: sys::pDstBlt,
+    pat_blt: sys::pPatBlt,
+    scr_blt: sys::pScrBlt,
+    opaque_rect: sys::pOpaqueRect,
+    multi_opaque_rect: sys::pMultiOpaqueRect,
+    mem_blt: sys::pMemBlt,
+    mem3_blt: sys::pMem3Blt,
+    line_to: sys::pLineTo,
+    polyline: sys::pPolyline,
+    polygon_sc: sys::pPolygonSC,
+    polygon_cb: sys::pPolygonCB,
+    ellipse_sc: sys::pEllipseSC,
+    ellipse_cb: sys::pEllipseCB,
+    glyph_index: sys::pGlyphIndex,
+    fast_index: sys::pFastIndex,
+    fast_glyph: sys::pFastGlyph,
+}
+
+pub struct PrimaryUpdate<'a> {
+    inner: ptr::NonNull<sys::rdpPrimaryUpdate>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> Debug for PrimaryUpdate<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PrimaryUpdate").finish()
+    }
+}
+
+impl<'a> PrimaryUpdate<'a> {
+    pub(crate) fn new(primary: *mut sys::rdpPrimaryUpdate) -> Self {
+        Self {
+            inner: ptr::NonNull::new(primary).unwrap(),
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// Observe the drawing orders, chained to the previous callbacks, see
+    /// [chaining](crate::update#chaining).
+    pub fn register<H: PrimaryUpdateHandler>(&mut self) {
+        let inner = unsafe { self.inner.as_mut() };
+
+        let chain = &mut RdpContext::<H::ContextHandler>::from_ptr(inner.context).primary_chain;
+        chain.dst_blt = inner.DstBlt;
+        chain.pat_blt = inner.PatBlt;
+        chain.scr_blt = inner.ScrBlt;
+        chain.opaque_rect = inner.OpaqueRect;
+        chain.multi_opaque_rect = inner.MultiOpaqueRect;
+        chain.mem_blt = inner.MemBlt;
+        chain.mem3_blt = inner.Mem3Blt;
+        chain.line_to = inner.LineTo;
+        chain.polyline = inner.Polyline;
+        chain.polygon_sc = inner.PolygonSC;
+        chain.polygon_cb = inner.PolygonCB;
+        chain.ellipse_sc = inner.EllipseSC;
+        chain.ellipse_cb = inner.EllipseCB;
+        chain.glyph_index = inner.GlyphIndex;
+        chain.fast_index = inner.FastIndex;
+        chain.fast_glyph = inner.FastGlyph;
+
+        inner.DstBlt = Some(rdp_primary_dst_blt::<H>);
+        inner.PatBlt = Some(rdp_primary_pat_blt::<H>);
+        inner.ScrBlt = Some(rdp_primary_scr_blt::<H>);
+        inner.OpaqueRect = Some(rdp_primary_opaque_rect::<H>);
+        inner.MultiOpaqueRect = Some(rdp_primary_multi_opaque_rect::<H>);
+        inner.MemBlt = Some(rdp_primary_mem_blt::<H>);
+        inner.Mem3Blt = Some(rdp_primary_mem3_blt::<H>);
+        inner.LineTo = Some(rdp_primary_line_to::<H>);
+        inner.Polyline = Some(rdp_primary_polyline::<H>);
+        inner.PolygonSC = Some(rdp_primary_polygon_sc::<H>);
+        inner.PolygonCB = Some(rdp_primary_polygon_cb::<H>);
+        inner.EllipseSC = Some(rdp_primary_ellipse_sc::<H>);
+        inner.EllipseCB = Some(rdp_primary_ellipse_cb::<H>);
+        inner.GlyphIndex = Some(rdp_primary_glyph_index::<H>);
+        inner.FastIndex = Some(rdp_primary_fast_index::<H>);
+        inner.FastGlyph = Some(rdp_primary_fast_glyph::<H>);
+    }
+}
+
+pub trait PrimaryUpdateHandler {
+    type ContextHandler: Handler;
+
+    fn dst_blt(_context: &mut Context<Self::ContextHandler>, _order: &DstBlt) -> Result<()> {
+        Ok(())
+    }
+
+    fn pat_blt(_context: &mut Context<Self::ContextHandler>, _order: &PatBlt) -> Result<()> {
+        Ok(())
+    }
+
+    fn scr_blt(_context: &mut Context<Self::ContextHandler>, _order: &ScrBlt) -> Result<()> {
+        Ok(())
+    }
+
+    fn opaque_rect(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &OpaqueRect,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn multi_opaque_rect(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &MultiOpaqueRect,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn mem_blt(_context: &mut Context<Self::ContextHandler>, _order: &MemBlt) -> Result<()> {
+        Ok(())
+    }
+
+    fn mem3_blt(_context: &mut Context<Self::ContextHandler>, _order: &Mem3Blt) -> Result<()> {
+        Ok(())
+    }
+
+    fn line_to(_context: &mut Context<Self::ContextHandler>, _order: &LineTo) -> Result<()> {
+        Ok(())
+    }
+
+    fn polyline(_context: &mut Context<Self::ContextHandler>, _order: &Polyline) -> Result<()> {
+        Ok(())
+    }
+
+    fn polygon_sc(_context: &mut Context<Self::ContextHandler>, _order: &PolygonSc) -> Result<()> {
+        Ok(())
+    }
+
+    fn polygon_cb(_context: &mut Context<Self::ContextHandler>, _order: &PolygonCb) -> Result<()> {
+        Ok(())
+    }
+
+    fn ellipse_sc(_context: &mut Context<Self::ContextHandler>, _order: &EllipseSc) -> Result<()> {
+        Ok(())
+    }
+
+    fn ellipse_cb(_context: &mut Context<Self::ContextHandler>, _order: &EllipseCb) -> Result<()> {
+        Ok(())
+    }
+
+    fn glyph_index(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &GlyphIndex,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn fast_index(_context: &mut Context<Self::ContextHandler>, _order: &FastIndex) -> Result<()> {
+        Ok(())
+    }
+
+    fn fast_glyph(_context: &mut Context<Self::ContextHandler>, _order: &FastGlyph) -> Result<()> {
+        Ok(())
+    }
+}
+
+macro_rules! callback {
+    ($name:ident, $method:ident, $sys:ty, $order:ty) => {
+        extern "C" fn $name<H: PrimaryUpdateHandler>(
+            context: *mut sys::rdpContext,
+            order: $sys,
+        ) -> sys::BOOL {
+            let ctxt = Context::from_ptr(context);
+            let o = <$order>::from(unsafe { order.as_ref() }.unwrap());
+
+            if H::$method(ctxt, &o).is_err() {
+                return 0;
+            }
+            match RdpContext::<H::ContextHandler>::from_ptr(context)
+                .primary_chain
+                .$method
+            {
+                Some(f) => unsafe { f(context, order) },
+                None => 1,
+            }
+        }
+    };
+}
+
+callback!(
+    rdp_primary_dst_blt,
+    dst_blt,
+    *const sys::DSTBLT_ORDER,
+    DstBlt
+);
+callback!(rdp_primary_pat_blt, pat_blt, *mut sys::PATBLT_ORDER, PatBlt);
+callback!(
+    rdp_primary_scr_blt,
+    scr_blt,
+    *const sys::SCRBLT_ORDER,
+    ScrBlt
+);
+callback!(
+    rdp_primary_opaque_rect,
+    opaque_rect,
+    *const sys::OPAQUE_RECT_ORDER,
+    OpaqueRect
+);
+callback!(
+    rdp_primary_multi_opaque_rect,
+    multi_opaque_rect,
+    *const sys::MULTI_OPAQUE_RECT_ORDER,
+    MultiOpaqueRect
+);
+callback!(rdp_primary_mem_blt, mem_blt, *mut sys::MEMBLT_ORDER, MemBlt);
+callback!(
+    rdp_primary_mem3_blt,
+    mem3_blt,
+    *mut sys::MEM3BLT_ORDER,
+    Mem3Blt
+);
+callback!(
+    rdp_primary_line_to,
+    line_to,
+    *const sys::LINE_TO_ORDER,
+    LineTo
+);
+callback!(
+    rdp_primary_polyline,
+    polyline,
+    *const sys::POLYLINE_ORDER,
+    Polyline
+);
+callback!(
+    rdp_primary_polygon_sc,
+    polygon_sc,
+    *const sys::POLYGON_SC_ORDER,
+    PolygonSc
+);
+callback!(
+    rdp_primary_polygon_cb,
+    polygon_cb,
+    *mut sys::POLYGON_CB_ORDER,
+    PolygonCb
+);
+callback!(
+    rdp_primary_ellipse_sc,
+    ellipse_sc,
+    *const sys::ELLIPSE_SC_ORDER,
+    EllipseSc
+);
+callback!(
+    rdp_primary_ellipse_cb,
+    ellipse_cb,
+    *const sys::ELLIPSE_CB_ORDER,
+    EllipseCb
+);
+callback!(
+    rdp_primary_glyph_index,
+    glyph_index,
+    *mut sys::GLYPH_INDEX_ORDER,
+    GlyphIndex
+);
+callback!(
+    rdp_primary_fast_index,
+    fast_index,
+    *const sys::FAST_INDEX_ORDER,
+    FastIndex
+);
+callback!(
+    rdp_primary_fast_glyph,
+    fast_glyph,
+    *const sys::FAST_GLYPH_ORDER,
+    FastGlyph
+);
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Brush {
+    pub x: u32,
+    pub y: u32,
+    pub bpp: u32,
+    pub style: u32,
+    pub hatch: u32,
+    pub index: u32,
+    /// The 8x8 monochrome pattern.
+    pub pattern: [u8; 8],
+}
+
+impl From<&sys::rdpBrush> for Brush {
+    fn from(b: &sys::rdpBrush) -> Self {
+        Self {
+            x: b.x,
+            y: b.y,
+            bpp: b.bpp,
+            style: b.style,
+            hatch: b.hatch,
+            index: b.index,
+            pattern: b.p8x8,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct DeltaPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct DeltaRect {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+fn points(points: *const sys::DELTA_POINT, len: u32) -> Vec<DeltaPoint> {
+    if points.is_null() {
+        return Vec::new();
+    }
+    unsafe { slice::from_raw_parts(points, len as _) }
+        .iter()
+        .map(|p| DeltaPoint { x: p.x, y: p.y })
+        .collect()
+}
+
+fn bytes(data: *const u8, len: u32) -> Vec<u8> {
+    if data.is_null() {
+        return Vec::new();
+    }
+    unsafe { slice::from_raw_parts(data, len as _) }.to_vec()
+}
+
+fn glyph_data(data: &[u8; 256], len: u32) -> Vec<u8> {
+    data[..data.len().min(len as _)].to_vec()
+}
+
+fn bounds(left: i32, top: i32, right: i32, bottom: i32) -> Bounds {
+    Bounds {
+        left,
+        top,
+        right,
+        bottom,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DstBlt {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+    pub rop: u32,
+}
+
+impl From<&sys::DSTBLT_ORDER> for DstBlt {
+    fn from(o: &sys::DSTBLT_ORDER) -> Self {
+        Self {
+            left: o.nLeftRect,
+            top: o.nTopRect,
+            width: o.nWidth,
+            height: o.nHeight,
+            rop: o.bRop,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatBlt {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+    pub rop: u32,
+    pub back_color: u32,
+    pub fore_color: u32,
+    pub brush: Brush,
+}
+
+impl From<&sys::PATBLT_ORDER> for PatBlt {
+    fn from(o: &sys::PATBLT_ORDER) -> Self {
+        Self {
+            left: o.nLeftRect,
+            top: o.nTopRect,
+            width: o.nWidth,
+            height: o.nHeight,
+            rop: o.bRop,
+            back_color: o.backColor,
+            fore_color: o.foreColor,
+            brush: (&o.brush).into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrBlt {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+    pub rop: u32,
+    pub src_x: i32,
+    pub src_y: i32,
+}
+
+impl From<&sys::SCRBLT_ORDER> for ScrBlt {
+    fn from(o: &sys::SCRBLT_ORDER) -> Self {
+        Self {
+            left: o.nLeftRect,
+            top: o.nTopRect,
+            width: o.nWidth,
+            height: o.nHeight,
+            rop: o.bRop,
+            src_x: o.nXSrc,
+            src_y: o.nYSrc,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpaqueRect {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+    pub color: u32,
+}
+
+impl From<&sys::OPAQUE_RECT_ORDER> for OpaqueRect {
+    fn from(o: &sys::OPAQUE_RECT_ORDER) -> Self {
+        Self {
+            left: o.nLeftRect,
+            top: o.nTopRect,
+            width: o.nWidth,
+            height: o.nHeight,
+            color: o.color,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiOpaqueRect {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+    pub color: u32,
+    /// The rectangles to fill, already decoded to absolute coordinates.
+    pub rects: Vec<DeltaRect>,
+}
+
+impl From<&sys::MULTI_OPAQUE_RECT_ORDER> for MultiOpaqueRect {
+    fn from(o: &sys::MULTI_OPAQUE_RECT_ORDER) -> Self {
+        let len = o.rectangles.len().min(o.numRectangles as _);
+
+        Self {
+            left: o.nLeftRect,
+            top: o.nTopRect,
+            width: o.nWidth,
+            height: o.nHeight,
+            color: o.color,
+            rects: o.rectangles[..len]
+                .iter()
+                .map(|r| DeltaRect {
+                    left: r.left,
+                    top: r.top,
+                    width: r.width,
+                    height: r.height,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemBlt {
+    pub cache_id: u32,
+    pub color_index: u32,
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+    pub rop: u32,
+    pub src_x: i32,
+    pub src_y: i32,
+    pub cache_index: u32,
+}
+
+impl From<&sys::MEMBLT_ORDER> for MemBlt {
+    fn from(o: &sys::MEMBLT_ORDER) -> Self {
+        Self {
+            cache_id: o.cacheId,
+            color_index: o.colorIndex,
+            left: o.nLeftRect,
+            top: o.nTopRect,
+            width: o.nWidth,
+            height: o.nHeight,
+            rop: o.bRop,
+            src_x: o.nXSrc,
+            src_y: o.nYSrc,
+            cache_index: o.cacheIndex,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mem3Blt {
+    pub cache_id: u32,
+    pub color_index: u32,
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+    pub rop: u32,
+    pub src_x: i32,
+    pub src_y: i32,
+    pub back_color: u32,
+    pub fore_color: u32,
+    pub brush: Brush,
+    pub cache_index: u32,
+}
+
+impl From<&sys::MEM3BLT_ORDER> for Mem3Blt {
+    fn from(o: &sys::MEM3BLT_ORDER) -> Self {
+        Self {
+            cache_id: o.cacheId,
+            color_index: o.colorIndex,
+            left: o.nLeftRect,
+            top: o.nTopRect,
+            width: o.nWidth,
+            height: o.nHeight,
+            rop: o.bRop,
+            src_x: o.nXSrc,
+            src_y: o.nYSrc,
+            back_color: o.backColor,
+            fore_color: o.foreColor,
+            brush: (&o.brush).into(),
+            cache_index: o.cacheIndex,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineTo {
+    pub back_mode: u32,
+    pub start: (i32, i32),
+    pub end: (i32, i32),
+    pub back_color: u32,
+    pub rop2: u32,
+    pub pen_style: u32,
+    pub pen_width: u32,
+    pub pen_color: u32,
+}
+
+impl From<&sys::LINE_TO_ORDER> for LineTo {
+    fn from(o: &sys::LINE_TO_ORDER) -> Self {
+        Self {
+            back_mode: o.backMode,
+            start: (o.nXStart, o.nYStart),
+            end: (o.nXEnd, o.nYEnd),
+            back_color: o.backColor,
+            rop2: o.bRop2,
+            pen_style: o.penStyle,
+            pen_width: o.penWidth,
+            pen_color: o.penColor,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polyline {
+    pub start: (i32, i32),
+    pub rop2: u32,
+    pub pen_color: u32,
+    /// Each point is relative to the previous one.
+    pub points: Vec<DeltaPoint>,
+}
+
+impl From<&sys::POLYLINE_ORDER> for Polyline {
+    fn from(o: &sys::POLYLINE_ORDER) -> Self {
+        Self {
+            start: (o.xStart, o.yStart),
+            rop2: o.bRop2,
+            pen_color: o.penColor,
+            points: points(o.points, o.numDeltaEntries),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolygonSc {
+    pub start: (i32, i32),
+    pub rop2: u32,
+    pub fill_mode: u32,
+    pub brush_color: u32,
+    /// Each point is relative to the previous one.
+    pub points: Vec<DeltaPoint>,
+}
+
+impl From<&sys::POLYGON_SC_ORDER> for PolygonSc {
+    fn from(o: &sys::POLYGON_SC_ORDER) -> Self {
+        Self {
+            start: (o.xStart, o.yStart),
+            rop2: o.bRop2,
+            fill_mode: o.fillMode,
+            brush_color: o.brushColor,
+            points: points(o.points, o.numPoints),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolygonCb {
+    pub start: (i32, i32),
+    pub rop2: u32,
+    pub back_mode: u32,
+    pub fill_mode: u32,
+    pub back_color: u32,
+    pub fore_color: u32,
+    pub brush: Brush,
+    /// Each point is relative to the previous one.
+    pub points: Vec<DeltaPoint>,
+}
+
+impl From<&sys::POLYGON_CB_ORDER> for PolygonCb {
+    fn from(o: &sys::POLYGON_CB_ORDER) -> Self {
+        Self {
+            start: (o.xStart, o.yStart),
+            rop2: o.bRop2,
+            back_mode: o.backMode,
+            fill_mode: o.fillMode,
+            back_color: o.backColor,
+            fore_color: o.foreColor,
+            brush: (&o.brush).into(),
+            points: points(o.points, o.numPoints),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EllipseSc {
+    pub rect: Bounds,
+    pub rop2: u32,
+    pub fill_mode: u32,
+    pub color: u32,
+}
+
+impl From<&sys::ELLIPSE_SC_ORDER> for EllipseSc {
+    fn from(o: &sys::ELLIPSE_SC_ORDER) -> Self {
+        Self {
+            rect: bounds(o.leftRect, o.topRect, o.rightRect, o.bottomRect),
+            rop2: o.bRop2,
+            fill_mode: o.fillMode,
+            color: o.color,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EllipseCb {
+    pub rect: Bounds,
+    pub rop2: u32,
+    pub fill_mode: u32,
+    pub back_color: u32,
+    pub fore_color: u32,
+    pub brush: Brush,
+}
+
+impl From<&sys::ELLIPSE_CB_ORDER> for EllipseCb {
+    fn from(o: &sys::ELLIPSE_CB_ORDER) -> Self {
+        Self {
+            rect: bounds(o.leftRect, o.topRect, o.rightRect, o.bottomRect),
+            rop2: o.bRop2,
+            fill_mode: o.fillMode,
+            back_color: o.backColor,
+            fore_color: o.foreColor,
+            brush: (&o.brush).into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlyphIndex {
+    pub cache_id: u32,
+    pub fl_accel: u32,
+    pub char_inc: u32,
+    pub op_redundant: bool,
+    pub back_color: u32,
+    pub fore_color: u32,
+    pub bk: Bounds,
+    pub op: Bounds,
+    pub brush: Brush,
+    pub x: i32,
+    pub y: i32,
+    /// The glyph fragments, indices into the glyph cache.
+    pub data: Vec<u8>,
+}
+
+impl From<&sys::GLYPH_INDEX_ORDER> for GlyphIndex {
+    fn from(o: &sys::GLYPH_INDEX_ORDER) -> Self {
+        Self {
+            cache_id: o.cacheId,
+            fl_accel: o.flAccel,
+            char_inc: o.ulCharInc,
+            op_redundant: o.fOpRedundant != 0,
+            back_color: o.backColor,
+            fore_color: o.foreColor,
+            bk: bounds(o.bkLeft, o.bkTop, o.bkRight, o.bkBottom),
+            op: bounds(o.opLeft, o.opTop, o.opRight, o.opBottom),
+            brush: (&o.brush).into(),
+            x: o.x,
+            y: o.y,
+            data: glyph_data(&o.data, o.cbData),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastIndex {
+    pub cache_id: u32,
+    pub fl_accel: u32,
+    pub char_inc: u32,
+    pub back_color: u32,
+    pub fore_color: u32,
+    pub bk: Bounds,
+    pub op: Bounds,
+    pub opaque_rect: bool,
+    pub x: i32,
+    pub y: i32,
+    /// The glyph fragments, indices into the glyph cache.
+    pub data: Vec<u8>,
+}
+
+impl From<&sys::FAST_INDEX_ORDER> for FastIndex {
+    fn from(o: &sys::FAST_INDEX_ORDER) -> Self {
+        Self {
+            cache_id: o.cacheId,
+            fl_accel: o.flAccel,
+            char_inc: o.ulCharInc,
+            back_color: o.backColor,
+            fore_color: o.foreColor,
+            bk: bounds(o.bkLeft, o.bkTop, o.bkRight, o.bkBottom),
+            op: bounds(o.opLeft, o.opTop, o.opRight, o.opBottom),
+            opaque_rect: o.opaqueRect != 0,
+            x: o.x,
+            y: o.y,
+            data: glyph_data(&o.data, o.cbData),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlyphData {
+    pub cache_index: u32,
+    pub x: i32,
+    pub y: i32,
+    pub cx: u32,
+    pub cy: u32,
+    /// The 1bpp glyph bitmap.
+    pub aj: Vec<u8>,
+}
+
+impl From<&sys::GLYPH_DATA_V2> for GlyphData {
+    fn from(g: &sys::GLYPH_DATA_V2) -> Self {
+        Self {
+            cache_index: g.cacheIndex,
+            x: g.x,
+            y: g.y,
+            cx: g.cx,
+            cy: g.cy,
+            aj: bytes(g.aj, g.cb),
+        }
+    }
+}
+
+impl From<&sys::GLYPH_DATA> for GlyphData {
+    fn from(g: &sys::GLYPH_DATA) -> Self {
+        Self {
+            cache_index: g.cacheIndex,
+            x: g.x.into(),
+            y: g.y.into(),
+            cx: g.cx,
+            cy: g.cy,
+            aj: bytes(g.aj, g.cb),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastGlyph {
+    pub cache_id: u32,
+    pub fl_accel: u32,
+    pub char_inc: u32,
+    pub back_color: u32,
+    pub fore_color: u32,
+    pub bk: Bounds,
+    pub op: Bounds,
+    pub x: i32,
+    pub y: i32,
+    pub data: Vec<u8>,
+    /// The glyph, when it isn't only a cache reference.
+    pub glyph: Option<GlyphData>,
+}
+
+impl From<&sys::FAST_GLYPH_ORDER> for FastGlyph {
+    fn from(o: &sys::FAST_GLYPH_ORDER) -> Self {
+        let glyph = (!o.glyphData.aj.is_null()).then(|| (&o.glyphData).into());
+
+        Self {
+            cache_id: o.cacheId,
+            fl_accel: o.flAccel,
+            char_inc: o.ulCharInc,
+            back_color: o.backColor,
+            fore_color: o.foreColor,
+            bk: bounds(o.bkLeft, o.bkTop, o.bkRight, o.bkBottom),
+            op: bounds(o.opLeft, o.opTop, o.opRight, o.opBottom),
+            x: o.x,
+            y: o.y,
+            data: glyph_data(&o.data, o.cbData),
+            glyph,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_opaque_rect() {
+        let mut order: sys::MULTI_OPAQUE_RECT_ORDER = unsafe { std::mem::zeroed() };
+        order.color = 0xff0000;
+        order.numRectangles = 2;
+        order.rectangles[1].left = 10;
+        order.rectangles[1].width = 5;
+
+        let o = MultiOpaqueRect::from(&order);
+        assert_eq!(o.color, 0xff0000);
+        assert_eq!(o.rects.len(), 2);
+        assert_eq!(o.rects[1].left, 10);
+        assert_eq!(o.rects[1].width, 5);
+
+        // numRectangles is not trusted to be within bounds
+        order.numRectangles = 1000;
+        assert_eq!(MultiOpaqueRect::from(&order).rects.len(), 45);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::sys;
+
+/// A rectangle, in the exclusive bounds convention of `RECTANGLE_16`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Rect {
+    pub left: u16,
+    pub top: u16,
+    pub right: u16,
+    pub bottom: u16,
+}
+
+impl Rect {
+    pub fn new(left: u16, top: u16, width: u16, height: u16) -> Self {
+        Self {
+            left,
+            top,
+            right: left.saturating_add(width),
+            bottom: top.saturating_add(height),
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.right.saturating_sub(self.left)
+    }
+
+    pub fn height(&self) -> u16 {
+        self.bottom.saturating_sub(self.top)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.left < other.right
+            && other.left < self.right
+            && self.top < other.bottom
+            && other.top < self.bottom
+    }
+
+    /// The bounding rectangle of both.
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            left: self.left.min(other.left),
+            top: self.top.min(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
+
+    /// The overlap of both, empty if they don't intersect.
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let left = self.left.max(other.left);
+        let top = self.top.max(other.top);
+
+        Rect {
+            left,
+            top,
+            right: self.right.min(other.right).max(left),
+            bottom: self.bottom.min(other.bottom).max(top),
+        }
+    }
+}
+
+impl From<sys::RECTANGLE_16> for Rect {
+    fn from(r: sys::RECTANGLE_16) -> Self {
+        Self {
+            left: r.left,
+            top: r.top,
+            right: r.right,
+            bottom: r.bottom,
+        }
+    }
+}
+
+impl From<Rect> for sys::RECTANGLE_16 {
+    fn from(r: Rect) -> Self {
+        Self {
+            left: r.left,
+            top: r.top,
+            right: r.right,
+            bottom: r.bottom,
+        }
+    }
+}
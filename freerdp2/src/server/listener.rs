@@ -0,0 +1,218 @@
+use std::{
+    collections::VecDeque,
+    ffi::{CStr, CString},
+    mem::MaybeUninit,
+    ptr,
+};
+
+#[cfg(unix)]
+use std::{
+    net::{TcpListener, TcpStream},
+    os::unix::prelude::{FromRawFd, IntoRawFd},
+};
+
+use crate::{
+    sys,
+    winpr::{self, Handle},
+    RdpError, Result,
+};
+
+/// Accepts the RDP connections, which are then handled by a
+/// [`Peer`](super::Peer).
+///
+/// ```no_run
+/// # use freerdp2::{server::*, Result};
+/// # struct MyPeer;
+/// # impl PeerHandler for MyPeer {}
+/// # fn main() -> Result<()> {
+/// let mut listener = Listener::new()?;
+/// listener.open(Some("127.0.0.1"), 3389)?;
+/// loop {
+///     listener.wait()?;
+///     while let Some(incoming) = listener.accept() {
+///         let mut peer = Peer::new(incoming, MyPeer)?;
+///         std::thread::spawn(move || peer.run());
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Listener {
+    inner: ptr::NonNull<sys::freerdp_listener>,
+    // pointed to by the listener info, filled by PeerAccepted
+    #[allow(clippy::box_collection)]
+    accepted: Box<VecDeque<Incoming>>,
+}
+
+unsafe impl Send for Listener {}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.close();
+        unsafe { sys::freerdp_listener_free(self.inner.as_ptr()) }
+    }
+}
+
+impl Listener {
+    pub fn new() -> Result<Self> {
+        let inner = ptr::NonNull::new(unsafe { sys::freerdp_listener_new() })
+            .ok_or_else(|| RdpError::Failed("freerdp_listener_new() failed".into()))?;
+        let mut accepted = Box::<VecDeque<Incoming>>::default();
+
+        unsafe {
+            let listener = &mut *inner.as_ptr();
+            listener.info = &mut *accepted as *mut _ as _;
+            listener.PeerAccepted = Some(rdp_listener_peer_accepted);
+        }
+
+        Ok(Self { inner, accepted })
+    }
+
+    /// Listen on `port`, on all the interfaces if `bind_address` is `None`.
+    pub fn open(&mut self, bind_address: Option<&str>, port: u16) -> Result<()> {
+        let bind_address = bind_address.map(CString::new).transpose()?;
+        let f = unsafe { self.inner.as_ref() }
+            .Open
+            .ok_or(RdpError::Unsupported)?;
+
+        if unsafe {
+            f(
+                self.inner.as_ptr(),
+                bind_address.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+                port,
+            )
+        } != 0
+        {
+            Ok(())
+        } else {
+            Err(RdpError::Failed(format!(
+                "Failed to listen on port {}",
+                port
+            )))
+        }
+    }
+
+    /// Listen on an already bound socket, to pick an ephemeral port for
+    /// example.
+    #[cfg(unix)]
+    pub fn open_from_socket(&mut self, socket: TcpListener) -> Result<()> {
+        let f = unsafe { self.inner.as_ref() }
+            .OpenFromSocket
+            .ok_or(RdpError::Unsupported)?;
+        let fd = socket.into_raw_fd();
+
+        if unsafe { f(self.inner.as_ptr(), fd) } != 0 {
+            Ok(())
+        } else {
+            drop(unsafe { TcpListener::from_raw_fd(fd) });
+            Err(RdpError::Failed("Failed to listen on socket".into()))
+        }
+    }
+
+    pub fn close(&mut self) {
+        if let Some(f) = unsafe { self.inner.as_ref() }.Close {
+            unsafe { f(self.inner.as_ptr()) }
+        }
+    }
+
+    pub fn event_handles(&self) -> Result<Vec<Handle>> {
+        let mut handles: [MaybeUninit<sys::HANDLE>; winpr::MAX_WAIT_OBJECTS] =
+            [MaybeUninit::uninit(); winpr::MAX_WAIT_OBJECTS];
+        let f = unsafe { self.inner.as_ref() }
+            .GetEventHandles
+            .ok_or(RdpError::Unsupported)?;
+        let res = unsafe {
+            f(
+                self.inner.as_ptr(),
+                handles.as_mut_ptr() as _,
+                handles.len() as _,
+            )
+        };
+        match res {
+            0 => Err(RdpError::Failed("Listener is not open".into())),
+            _ => Ok(handles[0..(res as _)]
+                .iter()
+                .map(|h| Handle::new(unsafe { h.assume_init() }, false))
+                .collect()),
+        }
+    }
+
+    /// Accept the pending connections, which are then returned by
+    /// [`Listener::accept`].
+    pub fn check_event_handles(&mut self) -> bool {
+        match unsafe { self.inner.as_ref() }.CheckFileDescriptor {
+            Some(f) => unsafe { f(self.inner.as_ptr()) != 0 },
+            None => false,
+        }
+    }
+
+    /// Block until a connection is accepted, or fails.
+    pub fn wait(&mut self) -> Result<()> {
+        while self.accepted.is_empty() {
+            let handles = self.event_handles()?;
+            let handles: Vec<_> = handles.iter().collect();
+            winpr::wait_for_multiple_objects(&handles, false, None)?;
+
+            if !self.check_event_handles() {
+                return Err(RdpError::Failed("Failed to accept connection".into()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn accept(&mut self) -> Option<Incoming> {
+        self.accepted.pop_front()
+    }
+}
+
+extern "C" fn rdp_listener_peer_accepted(
+    listener: *mut sys::freerdp_listener,
+    client: *mut sys::freerdp_peer,
+) -> sys::BOOL {
+    let accepted = unsafe { ((*listener).info as *mut VecDeque<Incoming>).as_mut() }.unwrap();
+
+    match ptr::NonNull::new(client) {
+        Some(inner) => {
+            accepted.push_back(Incoming { inner });
+            1
+        }
+        None => 0,
+    }
+}
+
+/// A connection accepted by a [`Listener`], before the RDP handshake.
+#[derive(Debug)]
+pub struct Incoming {
+    inner: ptr::NonNull<sys::freerdp_peer>,
+}
+
+unsafe impl Send for Incoming {}
+
+impl Drop for Incoming {
+    fn drop(&mut self) {
+        // the socket is only owned by the transport once the context is created
+        #[cfg(unix)]
+        drop(unsafe { TcpStream::from_raw_fd(self.inner.as_ref().sockfd) });
+        unsafe { sys::freerdp_peer_free(self.inner.as_ptr()) }
+    }
+}
+
+impl Incoming {
+    /// The address of the client.
+    pub fn hostname(&self) -> String {
+        let hostname = unsafe { &self.inner.as_ref().hostname };
+        unsafe { CStr::from_ptr(hostname.as_ptr()) }
+            .to_string_lossy()
+            .into()
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut sys::freerdp_peer {
+        self.inner.as_ptr()
+    }
+
+    pub(crate) fn into_raw(self) -> *mut sys::freerdp_peer {
+        let ptr = self.inner.as_ptr();
+        std::mem::forget(self);
+        ptr
+    }
+}
@@ -0,0 +1,7 @@
+//! Accept RDP connections and send the desktop updates to the clients.
+
+mod listener;
+pub use listener::*;
+
+mod peer;
+pub use peer::*;
@@ -0,0 +1,594 @@
+use std::{
+    ffi::CStr,
+    mem::{self, size_of, MaybeUninit},
+    ptr, slice,
+};
+
+use crate::{
+    input::{KbdFlags, PtrFlags, PtrXFlags, SyncFlags},
+    server::Incoming,
+    sys,
+    update::{BitmapData, FrameMarker, SurfaceBits, SystemPointer},
+    winpr::{self, Handle},
+    Cursor, RdpError, Result, Settings,
+};
+
+// this struct is allocated from C/freerdp, like the client RdpContext
+#[repr(C)]
+#[derive(Debug)]
+pub(crate) struct RdpPeerContext<H: PeerHandler> {
+    pub(crate) rdp_context: sys::rdpContext,
+    peer: ptr::NonNull<Peer<H>>,
+}
+
+impl<H: PeerHandler> RdpPeerContext<H> {
+    pub(crate) fn from_ptr<'a>(ptr: *mut sys::rdpContext) -> &'a mut Self {
+        let ptr = ptr as *mut Self;
+        unsafe { ptr::NonNull::new(ptr).unwrap().as_mut() }
+    }
+
+    fn peer(&mut self) -> &mut Peer<H> {
+        unsafe { self.peer.as_mut() }
+    }
+}
+
+/// The server side of an RDP connection, created from a connection accepted
+/// by a [`Listener`](super::Listener).
+#[derive(Debug)]
+pub struct Peer<H: PeerHandler> {
+    pub handler: H,
+    pub settings: Settings,
+
+    inner: ptr::NonNull<sys::freerdp_peer>,
+}
+
+unsafe impl<H> Send for Peer<H> where H: PeerHandler + Send {}
+unsafe impl<H> Sync for Peer<H> where H: PeerHandler + Sync {}
+
+impl<H: PeerHandler> Drop for Peer<H> {
+    fn drop(&mut self) {
+        self.disconnect();
+        unsafe {
+            sys::freerdp_peer_context_free(self.inner.as_ptr());
+            sys::freerdp_peer_free(self.inner.as_ptr());
+        }
+    }
+}
+
+pub trait PeerHandler {
+    /// Called before the server capabilities are sent, `peer.settings` can
+    /// still be adjusted.
+    fn capabilities(&mut self, _peer: &mut Peer<Self>) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    fn post_connect(&mut self, _peer: &mut Peer<Self>) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    /// Called once the client is ready to receive updates, again after a
+    /// reactivation.
+    fn activate(&mut self, _peer: &mut Peer<Self>) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    /// Called after NLA, with the credentials of the client.
+    fn logon(
+        &mut self,
+        _peer: &mut Peer<Self>,
+        _identity: &Identity,
+        _automatic: bool,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    fn synchronize_event(&mut self, _peer: &mut Peer<Self>, _flags: SyncFlags) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    fn keyboard_event(&mut self, _peer: &mut Peer<Self>, _flags: KbdFlags, _code: u16) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    fn unicode_keyboard_event(
+        &mut self,
+        _peer: &mut Peer<Self>,
+        _flags: KbdFlags,
+        _code: u16,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    fn mouse_event(
+        &mut self,
+        _peer: &mut Peer<Self>,
+        _flags: PtrFlags,
+        _x: u16,
+        _y: u16,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    fn extended_mouse_event(
+        &mut self,
+        _peer: &mut Peer<Self>,
+        _flags: PtrXFlags,
+        _x: u16,
+        _y: u16,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+}
+
+/// The credentials presented by the client.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub user: String,
+    pub domain: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Identity")
+            .field("user", &self.user)
+            .field("domain", &self.domain)
+            .finish()
+    }
+}
+
+impl From<&sys::SEC_WINNT_AUTH_IDENTITY> for Identity {
+    fn from(identity: &sys::SEC_WINNT_AUTH_IDENTITY) -> Self {
+        let unicode = identity.Flags & sys::SEC_WINNT_AUTH_IDENTITY_UNICODE != 0;
+        let string = |s: *const u16, len: u32| {
+            if s.is_null() {
+                String::new()
+            } else if unicode {
+                String::from_utf16_lossy(unsafe { slice::from_raw_parts(s, len as _) })
+            } else {
+                let s = unsafe { slice::from_raw_parts(s as *const u8, len as _) };
+                String::from_utf8_lossy(s).into()
+            }
+        };
+
+        Self {
+            user: string(identity.User, identity.UserLength),
+            domain: string(identity.Domain, identity.DomainLength),
+            password: string(identity.Password, identity.PasswordLength),
+        }
+    }
+}
+
+fn cvt(res: sys::BOOL, what: &str) -> Result<()> {
+    if res != 0 {
+        Ok(())
+    } else {
+        Err(RdpError::Failed(format!("{}() failed", what)))
+    }
+}
+
+impl<H: PeerHandler> Peer<H> {
+    pub(crate) fn from_ptr<'a>(ptr: *mut sys::rdpContext) -> &'a mut Self {
+        RdpPeerContext::<H>::from_ptr(ptr).peer()
+    }
+
+    pub fn new(incoming: Incoming, handler: H) -> Result<Box<Self>> {
+        let client = incoming.as_ptr();
+
+        unsafe {
+            (*client).ContextSize = size_of::<RdpPeerContext<H>>();
+            if sys::freerdp_peer_context_new(client) == 0 {
+                // incoming still owns the socket, and closes it
+                return Err(RdpError::Failed("freerdp_peer_context_new() failed".into()));
+            }
+        }
+        let client = incoming.into_raw();
+
+        let peer = unsafe { &mut *client };
+        peer.Capabilities = Some(rdp_peer_capabilities::<H>);
+        peer.PostConnect = Some(rdp_peer_post_connect::<H>);
+        peer.Activate = Some(rdp_peer_activate::<H>);
+        peer.Logon = Some(rdp_peer_logon::<H>);
+
+        let input = unsafe { &mut *peer.input };
+        input.SynchronizeEvent = Some(rdp_input_synchronize_event::<H>);
+        input.KeyboardEvent = Some(rdp_input_keyboard_event::<H>);
+        input.UnicodeKeyboardEvent = Some(rdp_input_unicode_keyboard_event::<H>);
+        input.MouseEvent = Some(rdp_input_mouse_event::<H>);
+        input.ExtendedMouseEvent = Some(rdp_input_extended_mouse_event::<H>);
+
+        let res = Box::new(Self {
+            handler,
+            settings: Settings::new(false, peer.settings),
+            inner: ptr::NonNull::new(client).unwrap(),
+        });
+        RdpPeerContext::<H>::from_ptr(peer.context).peer =
+            ptr::NonNull::new(&*res as *const _ as *mut _).unwrap();
+        Ok(res)
+    }
+
+    fn rdp_context(&self) -> *mut sys::rdpContext {
+        unsafe { self.inner.as_ref() }.context
+    }
+
+    fn rdp_update(&self) -> &sys::rdpUpdate {
+        unsafe { &*self.inner.as_ref().update }
+    }
+
+    /// Start the RDP handshake, once `settings` has the server certificate
+    /// and key.
+    pub fn initialize(&mut self) -> Result<()> {
+        let f = unsafe { self.inner.as_ref() }
+            .Initialize
+            .ok_or(RdpError::Unsupported)?;
+        cvt(unsafe { f(self.inner.as_ptr()) }, "Initialize")
+    }
+
+    pub fn event_handles(&self) -> Result<Vec<Handle>> {
+        let mut handles: [MaybeUninit<sys::HANDLE>; winpr::MAX_WAIT_OBJECTS] =
+            [MaybeUninit::uninit(); winpr::MAX_WAIT_OBJECTS];
+        let f = unsafe { self.inner.as_ref() }
+            .GetEventHandles
+            .ok_or(RdpError::Unsupported)?;
+        let res = unsafe {
+            f(
+                self.inner.as_ptr(),
+                handles.as_mut_ptr() as _,
+                handles.len() as _,
+            )
+        };
+        match res {
+            0 => Err(RdpError::Failed("GetEventHandles() failed".into())),
+            _ => Ok(handles[0..(res as _)]
+                .iter()
+                .map(|h| Handle::new(unsafe { h.assume_init() }, false))
+                .collect()),
+        }
+    }
+
+    /// Process the client messages, returns `false` once disconnected.
+    pub fn check_event_handles(&mut self) -> bool {
+        match unsafe { self.inner.as_ref() }.CheckFileDescriptor {
+            Some(f) => unsafe { f(self.inner.as_ptr()) != 0 },
+            None => false,
+        }
+    }
+
+    /// Initialize and process the client messages, until it disconnects.
+    pub fn run(&mut self) -> Result<()> {
+        self.initialize()?;
+
+        loop {
+            let handles = self.event_handles()?;
+            let handles: Vec<_> = handles.iter().collect();
+            winpr::wait_for_multiple_objects(&handles, false, None)?;
+
+            if !self.check_event_handles() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn disconnect(&mut self) {
+        if let Some(f) = unsafe { self.inner.as_ref() }.Disconnect {
+            unsafe { f(self.inner.as_ptr()) }
+        }
+    }
+
+    /// The address of the client.
+    pub fn hostname(&self) -> String {
+        let hostname = unsafe { &self.inner.as_ref().hostname };
+        unsafe { CStr::from_ptr(hostname.as_ptr()) }
+            .to_string_lossy()
+            .into()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        unsafe { self.inner.as_ref() }.connected != 0
+    }
+
+    pub fn is_activated(&self) -> bool {
+        unsafe { self.inner.as_ref() }.activated != 0
+    }
+
+    pub fn begin_paint(&mut self) -> Result<()> {
+        let f = self.rdp_update().BeginPaint.ok_or(RdpError::Unsupported)?;
+        cvt(unsafe { f(self.rdp_context()) }, "BeginPaint")
+    }
+
+    pub fn end_paint(&mut self) -> Result<()> {
+        let f = self.rdp_update().EndPaint.ok_or(RdpError::Unsupported)?;
+        cvt(unsafe { f(self.rdp_context()) }, "EndPaint")
+    }
+
+    /// Send bitmap rectangles, `data` is compressed by FreeRDP unless
+    /// `compressed` is already set.
+    pub fn send_bitmap_update(&mut self, bitmaps: &[BitmapData]) -> Result<()> {
+        let f = self
+            .rdp_update()
+            .BitmapUpdate
+            .ok_or(RdpError::Unsupported)?;
+        let mut rects: Vec<_> = bitmaps
+            .iter()
+            .map(|b| {
+                let mut data: sys::BITMAP_DATA = unsafe { mem::zeroed() };
+                // destRight/destBottom are inclusive
+                data.destLeft = b.dest.left.into();
+                data.destTop = b.dest.top.into();
+                data.destRight = u32::from(b.dest.right).saturating_sub(1);
+                data.destBottom = u32::from(b.dest.bottom).saturating_sub(1);
+                data.width = b.width;
+                data.height = b.height;
+                data.bitsPerPixel = b.bpp;
+                data.flags = b.flags;
+                data.bitmapLength = b.data.len() as _;
+                data.bitmapDataStream = b.data.as_ptr() as _;
+                data.compressed = b.compressed as _;
+                data
+            })
+            .collect();
+        let update = sys::BITMAP_UPDATE {
+            count: rects.len() as _,
+            number: rects.len() as _,
+            rectangles: rects.as_mut_ptr(),
+            skipCompression: 0,
+        };
+
+        cvt(unsafe { f(self.rdp_context(), &update) }, "BitmapUpdate")
+    }
+
+    /// Send a surface bits command, `data` is already encoded with `codec`.
+    pub fn send_surface_bits(&mut self, bits: &SurfaceBits) -> Result<()> {
+        let f = self.rdp_update().SurfaceBits.ok_or(RdpError::Unsupported)?;
+        let mut cmd: sys::SURFACE_BITS_COMMAND = unsafe { mem::zeroed() };
+        cmd.cmdType = bits.cmd_type;
+        cmd.destLeft = bits.dest.left.into();
+        cmd.destTop = bits.dest.top.into();
+        cmd.destRight = bits.dest.right.into();
+        cmd.destBottom = bits.dest.bottom.into();
+        cmd.bmp.bpp = bits.bpp;
+        cmd.bmp.flags = bits.flags;
+        cmd.bmp.codecID = bits.codec.map_or_else(|id| id, |c| c as _);
+        cmd.bmp.width = bits.width;
+        cmd.bmp.height = bits.height;
+        cmd.bmp.bitmapDataLength = bits.data.len() as _;
+        cmd.bmp.bitmapData = bits.data.as_ptr() as _;
+        cmd.skipCompression = 1;
+
+        cvt(unsafe { f(self.rdp_context(), &cmd) }, "SurfaceBits")
+    }
+
+    pub fn send_frame_marker(&mut self, marker: &FrameMarker) -> Result<()> {
+        let f = self
+            .rdp_update()
+            .SurfaceFrameMarker
+            .ok_or(RdpError::Unsupported)?;
+        let marker = sys::SURFACE_FRAME_MARKER {
+            frameAction: marker.action as _,
+            frameId: marker.frame_id,
+        };
+
+        cvt(
+            unsafe { f(self.rdp_context(), &marker) },
+            "SurfaceFrameMarker",
+        )
+    }
+
+    fn rdp_pointer(&self) -> Result<&sys::rdpPointerUpdate> {
+        unsafe { self.rdp_update().pointer.as_ref() }.ok_or(RdpError::Unsupported)
+    }
+
+    pub fn send_pointer_system(&mut self, pointer: SystemPointer) -> Result<()> {
+        let f = self
+            .rdp_pointer()?
+            .PointerSystem
+            .ok_or(RdpError::Unsupported)?;
+        let update = sys::POINTER_SYSTEM_UPDATE {
+            type_: pointer as _,
+        };
+
+        cvt(unsafe { f(self.rdp_context(), &update) }, "PointerSystem")
+    }
+
+    pub fn send_pointer_position(&mut self, x: u32, y: u32) -> Result<()> {
+        let f = self
+            .rdp_pointer()?
+            .PointerPosition
+            .ok_or(RdpError::Unsupported)?;
+        let update = sys::POINTER_POSITION_UPDATE { xPos: x, yPos: y };
+
+        cvt(unsafe { f(self.rdp_context(), &update) }, "PointerPosition")
+    }
+
+    /// Send a new pointer shape, kept by the client at `cache_index`.
+    pub fn send_pointer(&mut self, cache_index: u32, cursor: &Cursor) -> Result<()> {
+        let f = self
+            .rdp_pointer()?
+            .PointerNew
+            .ok_or(RdpError::Unsupported)?;
+        let (mut xor_mask, mut and_mask) = cursor.to_pointer_masks();
+        let update = sys::POINTER_NEW_UPDATE {
+            xorBpp: 32,
+            colorPtrAttr: sys::POINTER_COLOR_UPDATE {
+                cacheIndex: cache_index,
+                xPos: cursor.hotspot.0,
+                yPos: cursor.hotspot.1,
+                width: cursor.width,
+                height: cursor.height,
+                lengthAndMask: and_mask.len() as _,
+                lengthXorMask: xor_mask.len() as _,
+                xorMaskData: xor_mask.as_mut_ptr(),
+                andMaskData: and_mask.as_mut_ptr(),
+            },
+        };
+
+        cvt(unsafe { f(self.rdp_context(), &update) }, "PointerNew")
+    }
+
+    /// Show the pointer previously sent at `cache_index`.
+    pub fn send_pointer_cached(&mut self, cache_index: u32) -> Result<()> {
+        let f = self
+            .rdp_pointer()?
+            .PointerCached
+            .ok_or(RdpError::Unsupported)?;
+        let update = sys::POINTER_CACHED_UPDATE {
+            cacheIndex: cache_index,
+        };
+
+        cvt(unsafe { f(self.rdp_context(), &update) }, "PointerCached")
+    }
+}
+
+extern "C" fn rdp_peer_capabilities<H: PeerHandler>(client: *mut sys::freerdp_peer) -> sys::BOOL {
+    let ptr = unsafe { (*client).context };
+    // XXX: alias peer, like the client context callbacks
+    let peer = Peer::<H>::from_ptr(ptr);
+
+    peer.handler.capabilities(Peer::<H>::from_ptr(ptr)).is_ok() as _
+}
+
+extern "C" fn rdp_peer_post_connect<H: PeerHandler>(client: *mut sys::freerdp_peer) -> sys::BOOL {
+    let ptr = unsafe { (*client).context };
+    let peer = Peer::<H>::from_ptr(ptr);
+
+    peer.handler.post_connect(Peer::<H>::from_ptr(ptr)).is_ok() as _
+}
+
+extern "C" fn rdp_peer_activate<H: PeerHandler>(client: *mut sys::freerdp_peer) -> sys::BOOL {
+    let ptr = unsafe { (*client).context };
+    let peer = Peer::<H>::from_ptr(ptr);
+
+    peer.handler.activate(Peer::<H>::from_ptr(ptr)).is_ok() as _
+}
+
+extern "C" fn rdp_peer_logon<H: PeerHandler>(
+    client: *mut sys::freerdp_peer,
+    identity: *mut sys::SEC_WINNT_AUTH_IDENTITY,
+    automatic: sys::BOOL,
+) -> sys::BOOL {
+    let ptr = unsafe { (*client).context };
+    let peer = Peer::<H>::from_ptr(ptr);
+    let identity = match unsafe { identity.as_ref() } {
+        Some(identity) => identity.into(),
+        None => return 0,
+    };
+
+    peer.handler
+        .logon(Peer::<H>::from_ptr(ptr), &identity, automatic != 0)
+        .is_ok() as _
+}
+
+extern "C" fn rdp_input_synchronize_event<H: PeerHandler>(
+    input: *mut sys::rdpInput,
+    flags: sys::UINT32,
+) -> sys::BOOL {
+    let ptr = unsafe { (*input).context };
+    let peer = Peer::<H>::from_ptr(ptr);
+
+    peer.handler
+        .synchronize_event(
+            Peer::<H>::from_ptr(ptr),
+            SyncFlags::from_bits_truncate(flags),
+        )
+        .is_ok() as _
+}
+
+extern "C" fn rdp_input_keyboard_event<H: PeerHandler>(
+    input: *mut sys::rdpInput,
+    flags: sys::UINT16,
+    code: sys::UINT16,
+) -> sys::BOOL {
+    let ptr = unsafe { (*input).context };
+    let peer = Peer::<H>::from_ptr(ptr);
+
+    peer.handler
+        .keyboard_event(
+            Peer::<H>::from_ptr(ptr),
+            KbdFlags::from_bits_truncate(flags),
+            code,
+        )
+        .is_ok() as _
+}
+
+extern "C" fn rdp_input_unicode_keyboard_event<H: PeerHandler>(
+    input: *mut sys::rdpInput,
+    flags: sys::UINT16,
+    code: sys::UINT16,
+) -> sys::BOOL {
+    let ptr = unsafe { (*input).context };
+    let peer = Peer::<H>::from_ptr(ptr);
+
+    peer.handler
+        .unicode_keyboard_event(
+            Peer::<H>::from_ptr(ptr),
+            KbdFlags::from_bits_truncate(flags),
+            code,
+        )
+        .is_ok() as _
+}
+
+extern "C" fn rdp_input_mouse_event<H: PeerHandler>(
+    input: *mut sys::rdpInput,
+    flags: sys::UINT16,
+    x: sys::UINT16,
+    y: sys::UINT16,
+) -> sys::BOOL {
+    let ptr = unsafe { (*input).context };
+    let peer = Peer::<H>::from_ptr(ptr);
+
+    peer.handler
+        .mouse_event(
+            Peer::<H>::from_ptr(ptr),
+            PtrFlags::from_bits_retain(flags),
+            x,
+            y,
+        )
+        .is_ok() as _
+}
+
+extern "C" fn rdp_input_extended_mouse_event<H: PeerHandler>(
+    input: *mut sys::rdpInput,
+    flags: sys::UINT16,
+    x: sys::UINT16,
+    y: sys::UINT16,
+) -> sys::BOOL {
+    let ptr = unsafe { (*input).context };
+    let peer = Peer::<H>::from_ptr(ptr);
+
+    peer.handler
+        .extended_mouse_event(
+            Peer::<H>::from_ptr(ptr),
+            PtrXFlags::from_bits_truncate(flags),
+            x,
+            y,
+        )
+        .is_ok() as _
+}
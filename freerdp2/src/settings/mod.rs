@@ -1,10 +1,13 @@
 use std::{
     ffi::{CStr, CString},
+    path::Path,
     ptr,
 };
 
 use crate::{locale::KeyboardLayout, sys, ConnectionType, RdpError, Result};
 
+pub mod typed;
+
 pub struct Settings {
     pub(crate) inner: ptr::NonNull<sys::rdpSettings>,
     owned: bool,
@@ -78,10 +81,78 @@ impl Settings {
         }
     }
 
+    fn alloc() -> Result<Self> {
+        let settings = unsafe { sys::freerdp_settings_new(0) };
+        if settings.is_null() {
+            return Err(RdpError::Failed("Failed to allocate settings".into()));
+        }
+        Ok(Self::new(true, settings))
+    }
+
     pub fn as_ptr(&self) -> *mut sys::rdpSettings {
         self.inner.as_ptr()
     }
 
+    /// Create settings from a `.rdp` connection file.
+    pub fn from_rdp_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut settings = Self::alloc()?;
+        settings.parse_rdp_file(path)?;
+        Ok(settings)
+    }
+
+    /// Create settings from the content of a `.rdp` connection file.
+    pub fn from_rdp_buffer(buffer: &str) -> Result<Self> {
+        let mut settings = Self::alloc()?;
+        settings.parse_rdp_buffer(buffer)?;
+        Ok(settings)
+    }
+
+    /// Apply the entries of a `.rdp` connection file on top of the current settings.
+    pub fn parse_rdp_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path_to_cstring(path.as_ref())?;
+        let res = unsafe {
+            sys::freerdp_client_settings_parse_connection_file(self.inner.as_ptr(), path.as_ptr())
+        };
+        if res == 0 {
+            Ok(())
+        } else {
+            Err(RdpError::Failed("Failed to parse connection file".into()))
+        }
+    }
+
+    /// Apply the entries of a `.rdp` connection buffer on top of the current settings.
+    pub fn parse_rdp_buffer(&mut self, buffer: &str) -> Result<()> {
+        let res = unsafe {
+            sys::freerdp_client_settings_parse_connection_file_buffer(
+                self.inner.as_ptr(),
+                buffer.as_ptr(),
+                buffer.len(),
+            )
+        };
+        if res == 0 {
+            Ok(())
+        } else {
+            Err(RdpError::Failed("Failed to parse connection buffer".into()))
+        }
+    }
+
+    /// Save the settings as a `.rdp` connection file.
+    pub fn write_rdp_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path_to_cstring(path.as_ref())?;
+        let res = unsafe {
+            sys::freerdp_client_settings_write_connection_file(
+                self.inner.as_ptr(),
+                path.as_ptr(),
+                false as _,
+            )
+        };
+        if res == 0 {
+            Ok(())
+        } else {
+            Err(RdpError::Failed("Failed to write connection file".into()))
+        }
+    }
+
     pub fn parse_command_line(&mut self, args: &[&str], allow_unknown: bool) -> Result<()> {
         let cargs: Vec<_> = args.iter().map(|a| CString::new(*a).unwrap()).collect();
         let argv: Vec<_> = cargs.iter().map(|a| a.as_ptr()).collect();
@@ -248,10 +319,76 @@ impl Settings {
         }
     }
 
+    /// The desktop scale factor in percent, 0 if unset.
+    pub fn desktop_scale_factor(&self) -> u32 {
+        unsafe { self.inner.as_ref().DesktopScaleFactor }
+    }
+
+    /// The device scale factor in percent, 0 if unset.
+    pub fn device_scale_factor(&self) -> u32 {
+        unsafe { self.inner.as_ref().DeviceScaleFactor }
+    }
+
     pub fn gfx_h264(&self) -> bool {
         unsafe { self.inner.as_ref().GfxH264 != 0 }
     }
 
+    pub fn set_gfx_h264(&mut self, enabled: bool) {
+        unsafe {
+            self.inner.as_mut().GfxH264 = enabled as _;
+        }
+    }
+
+    pub fn gfx_avc444(&self) -> bool {
+        unsafe { self.inner.as_ref().GfxAVC444 != 0 }
+    }
+
+    pub fn set_gfx_avc444(&mut self, enabled: bool) {
+        unsafe {
+            self.inner.as_mut().GfxAVC444 = enabled as _;
+        }
+    }
+
+    pub fn gfx_avc444v2(&self) -> bool {
+        unsafe { self.inner.as_ref().GfxAVC444v2 != 0 }
+    }
+
+    pub fn set_gfx_avc444v2(&mut self, enabled: bool) {
+        unsafe {
+            self.inner.as_mut().GfxAVC444v2 = enabled as _;
+        }
+    }
+
+    pub fn gfx_thin_client(&self) -> bool {
+        unsafe { self.inner.as_ref().GfxThinClient != 0 }
+    }
+
+    pub fn set_gfx_thin_client(&mut self, enabled: bool) {
+        unsafe {
+            self.inner.as_mut().GfxThinClient = enabled as _;
+        }
+    }
+
+    pub fn gfx_small_cache(&self) -> bool {
+        unsafe { self.inner.as_ref().GfxSmallCache != 0 }
+    }
+
+    pub fn set_gfx_small_cache(&mut self, enabled: bool) {
+        unsafe {
+            self.inner.as_mut().GfxSmallCache = enabled as _;
+        }
+    }
+
+    pub fn gfx_send_qoe_ack(&self) -> bool {
+        unsafe { self.inner.as_ref().GfxSendQoeAck != 0 }
+    }
+
+    pub fn set_gfx_send_qoe_ack(&mut self, enabled: bool) {
+        unsafe {
+            self.inner.as_mut().GfxSendQoeAck = enabled as _;
+        }
+    }
+
     pub fn set_support_display_control(&mut self, enabled: bool) {
         unsafe {
             self.inner.as_mut().SupportDisplayControl = enabled as _;
@@ -263,6 +400,21 @@ impl Settings {
     }
 }
 
+#[cfg(unix)]
+pub(crate) fn path_to_cstring(path: &Path) -> Result<CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(CString::new(path.as_os_str().as_bytes())?)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn path_to_cstring(path: &Path) -> Result<CString> {
+    let path = path
+        .to_str()
+        .ok_or_else(|| RdpError::Failed("Invalid path".into()))?;
+    Ok(CString::new(path)?)
+}
+
 impl std::fmt::Debug for Settings {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Settings")
@@ -273,3 +425,50 @@ impl std::fmt::Debug for Settings {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(name: &str) -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data")
+            .join(name)
+    }
+
+    #[test]
+    fn rdp_file_round_trip() {
+        for name in ["mstsc.rdp", "rdweb.rdp"] {
+            let settings = Settings::from_rdp_file(data(name)).unwrap();
+            assert_eq!(
+                settings.server_hostname().as_deref(),
+                Some("rdp.example.com")
+            );
+            assert_eq!(settings.server_port(), 3390);
+            assert_eq!(settings.username().as_deref(), Some("jdoe"));
+            assert_eq!(settings.domain().as_deref(), Some("EXAMPLE"));
+
+            let out =
+                std::env::temp_dir().join(format!("freerdp-rs-{}-{}", std::process::id(), name));
+            settings.write_rdp_file(&out).unwrap();
+            let copy = Settings::from_rdp_file(&out).unwrap();
+            std::fs::remove_file(&out).unwrap();
+
+            assert_eq!(copy.server_hostname(), settings.server_hostname());
+            assert_eq!(copy.server_port(), settings.server_port());
+            assert_eq!(copy.username(), settings.username());
+            assert_eq!(copy.domain(), settings.domain());
+        }
+    }
+
+    #[test]
+    fn rdp_buffer() {
+        let content = std::fs::read_to_string(data("rdweb.rdp")).unwrap();
+        let settings = Settings::from_rdp_buffer(&content).unwrap();
+        assert_eq!(
+            settings.server_hostname().as_deref(),
+            Some("rdp.example.com")
+        );
+        assert_eq!(settings.server_port(), 3390);
+    }
+}
@@ -0,0 +1,779 @@
+//! Typed access to the whole `rdpSettings` surface.
+//!
+//! ```no_run
+//! # fn f(settings: &mut freerdp2::Settings) -> freerdp2::Result<()> {
+//! use freerdp2::settings::typed::{Bool, Key, SettingValue};
+//!
+//! settings.set::<Bool>(Key::NlaSecurity, false)?;
+//! assert!(!settings.get::<Bool>(Key::NlaSecurity)?);
+//! assert!(matches!(
+//!     settings.get_by_name("DesktopWidth")?,
+//!     SettingValue::UInt32(_)
+//! ));
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+    str::FromStr,
+};
+
+use crate::{sys, RdpError, Result, Settings};
+
+macro_rules! keys {
+    ($($name:ident = $sys:ident,)*) => {
+        /// A FreeRDP settings key.
+        #[non_exhaustive]
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+        pub enum Key {
+            $($name = sys::$sys as _,)*
+        }
+
+        impl TryFrom<usize> for Key {
+            type Error = RdpError;
+
+            fn try_from(value: usize) -> std::result::Result<Self, Self::Error> {
+                match value {
+                    $(v if v == Key::$name as usize => Ok(Key::$name),)*
+                    _ => Err(RdpError::Unsupported),
+                }
+            }
+        }
+    };
+}
+
+keys! {
+    ServerMode = FreeRDP_ServerMode,
+    ShareId = FreeRDP_ShareId,
+    PduSource = FreeRDP_PduSource,
+    ServerPort = FreeRDP_ServerPort,
+    ServerHostname = FreeRDP_ServerHostname,
+    Username = FreeRDP_Username,
+    Password = FreeRDP_Password,
+    Domain = FreeRDP_Domain,
+    PasswordHash = FreeRDP_PasswordHash,
+    WaitForOutputBufferFlush = FreeRDP_WaitForOutputBufferFlush,
+    MaxTimeInCheckLoop = FreeRDP_MaxTimeInCheckLoop,
+    AcceptedCert = FreeRDP_AcceptedCert,
+    AcceptedCertLength = FreeRDP_AcceptedCertLength,
+    RdpVersion = FreeRDP_RdpVersion,
+    DesktopWidth = FreeRDP_DesktopWidth,
+    DesktopHeight = FreeRDP_DesktopHeight,
+    ColorDepth = FreeRDP_ColorDepth,
+    ConnectionType = FreeRDP_ConnectionType,
+    ClientBuild = FreeRDP_ClientBuild,
+    ClientHostname = FreeRDP_ClientHostname,
+    ClientProductId = FreeRDP_ClientProductId,
+    EarlyCapabilityFlags = FreeRDP_EarlyCapabilityFlags,
+    NetworkAutoDetect = FreeRDP_NetworkAutoDetect,
+    SupportAsymetricKeys = FreeRDP_SupportAsymetricKeys,
+    SupportErrorInfoPdu = FreeRDP_SupportErrorInfoPdu,
+    SupportStatusInfoPdu = FreeRDP_SupportStatusInfoPdu,
+    SupportMonitorLayoutPdu = FreeRDP_SupportMonitorLayoutPdu,
+    SupportGraphicsPipeline = FreeRDP_SupportGraphicsPipeline,
+    SupportDynamicTimeZone = FreeRDP_SupportDynamicTimeZone,
+    SupportHeartbeatPdu = FreeRDP_SupportHeartbeatPdu,
+    DesktopPhysicalWidth = FreeRDP_DesktopPhysicalWidth,
+    DesktopPhysicalHeight = FreeRDP_DesktopPhysicalHeight,
+    DesktopOrientation = FreeRDP_DesktopOrientation,
+    DesktopScaleFactor = FreeRDP_DesktopScaleFactor,
+    DeviceScaleFactor = FreeRDP_DeviceScaleFactor,
+    UseRdpSecurityLayer = FreeRDP_UseRdpSecurityLayer,
+    EncryptionMethods = FreeRDP_EncryptionMethods,
+    ExtEncryptionMethods = FreeRDP_ExtEncryptionMethods,
+    EncryptionLevel = FreeRDP_EncryptionLevel,
+    ServerRandom = FreeRDP_ServerRandom,
+    ServerRandomLength = FreeRDP_ServerRandomLength,
+    ServerCertificate = FreeRDP_ServerCertificate,
+    ServerCertificateLength = FreeRDP_ServerCertificateLength,
+    ClientRandom = FreeRDP_ClientRandom,
+    ClientRandomLength = FreeRDP_ClientRandomLength,
+    ChannelCount = FreeRDP_ChannelCount,
+    ChannelDefArraySize = FreeRDP_ChannelDefArraySize,
+    ChannelDefArray = FreeRDP_ChannelDefArray,
+    ClusterInfoFlags = FreeRDP_ClusterInfoFlags,
+    RedirectedSessionId = FreeRDP_RedirectedSessionId,
+    ConsoleSession = FreeRDP_ConsoleSession,
+    MonitorCount = FreeRDP_MonitorCount,
+    MonitorDefArraySize = FreeRDP_MonitorDefArraySize,
+    MonitorDefArray = FreeRDP_MonitorDefArray,
+    SpanMonitors = FreeRDP_SpanMonitors,
+    UseMultimon = FreeRDP_UseMultimon,
+    ForceMultimon = FreeRDP_ForceMultimon,
+    DesktopPosX = FreeRDP_DesktopPosX,
+    DesktopPosY = FreeRDP_DesktopPosY,
+    ListMonitors = FreeRDP_ListMonitors,
+    MonitorIds = FreeRDP_MonitorIds,
+    NumMonitorIds = FreeRDP_NumMonitorIds,
+    MonitorLocalShiftX = FreeRDP_MonitorLocalShiftX,
+    MonitorLocalShiftY = FreeRDP_MonitorLocalShiftY,
+    HasMonitorAttributes = FreeRDP_HasMonitorAttributes,
+    MultitransportFlags = FreeRDP_MultitransportFlags,
+    SupportMultitransport = FreeRDP_SupportMultitransport,
+    AlternateShell = FreeRDP_AlternateShell,
+    ShellWorkingDirectory = FreeRDP_ShellWorkingDirectory,
+    AutoLogonEnabled = FreeRDP_AutoLogonEnabled,
+    CompressionEnabled = FreeRDP_CompressionEnabled,
+    DisableCtrlAltDel = FreeRDP_DisableCtrlAltDel,
+    EnableWindowsKey = FreeRDP_EnableWindowsKey,
+    MaximizeShell = FreeRDP_MaximizeShell,
+    LogonNotify = FreeRDP_LogonNotify,
+    LogonErrors = FreeRDP_LogonErrors,
+    MouseAttached = FreeRDP_MouseAttached,
+    MouseHasWheel = FreeRDP_MouseHasWheel,
+    RemoteConsoleAudio = FreeRDP_RemoteConsoleAudio,
+    AudioPlayback = FreeRDP_AudioPlayback,
+    AudioCapture = FreeRDP_AudioCapture,
+    VideoDisable = FreeRDP_VideoDisable,
+    PasswordIsSmartcardPin = FreeRDP_PasswordIsSmartcardPin,
+    UsingSavedCredentials = FreeRDP_UsingSavedCredentials,
+    ForceEncryptedCsPdu = FreeRDP_ForceEncryptedCsPdu,
+    HiDefRemoteApp = FreeRDP_HiDefRemoteApp,
+    CompressionLevel = FreeRDP_CompressionLevel,
+    IPv6Enabled = FreeRDP_IPv6Enabled,
+    ClientAddress = FreeRDP_ClientAddress,
+    ClientDir = FreeRDP_ClientDir,
+    AutoReconnectionEnabled = FreeRDP_AutoReconnectionEnabled,
+    AutoReconnectMaxRetries = FreeRDP_AutoReconnectMaxRetries,
+    ClientAutoReconnectCookie = FreeRDP_ClientAutoReconnectCookie,
+    ServerAutoReconnectCookie = FreeRDP_ServerAutoReconnectCookie,
+    PrintReconnectCookie = FreeRDP_PrintReconnectCookie,
+    ClientTimeZone = FreeRDP_ClientTimeZone,
+    DynamicDSTTimeZoneKeyName = FreeRDP_DynamicDSTTimeZoneKeyName,
+    DynamicDaylightTimeDisabled = FreeRDP_DynamicDaylightTimeDisabled,
+    PerformanceFlags = FreeRDP_PerformanceFlags,
+    AllowFontSmoothing = FreeRDP_AllowFontSmoothing,
+    DisableWallpaper = FreeRDP_DisableWallpaper,
+    DisableFullWindowDrag = FreeRDP_DisableFullWindowDrag,
+    DisableMenuAnims = FreeRDP_DisableMenuAnims,
+    DisableThemes = FreeRDP_DisableThemes,
+    DisableCursorShadow = FreeRDP_DisableCursorShadow,
+    DisableCursorBlinking = FreeRDP_DisableCursorBlinking,
+    AllowDesktopComposition = FreeRDP_AllowDesktopComposition,
+    RemoteAssistanceMode = FreeRDP_RemoteAssistanceMode,
+    RemoteAssistanceSessionId = FreeRDP_RemoteAssistanceSessionId,
+    RemoteAssistancePassStub = FreeRDP_RemoteAssistancePassStub,
+    RemoteAssistancePassword = FreeRDP_RemoteAssistancePassword,
+    RemoteAssistanceRCTicket = FreeRDP_RemoteAssistanceRCTicket,
+    EncomspVirtualChannel = FreeRDP_EncomspVirtualChannel,
+    RemdeskVirtualChannel = FreeRDP_RemdeskVirtualChannel,
+    LyncRdpMode = FreeRDP_LyncRdpMode,
+    RemoteAssistanceRequestControl = FreeRDP_RemoteAssistanceRequestControl,
+    TlsSecurity = FreeRDP_TlsSecurity,
+    NlaSecurity = FreeRDP_NlaSecurity,
+    RdpSecurity = FreeRDP_RdpSecurity,
+    ExtSecurity = FreeRDP_ExtSecurity,
+    Authentication = FreeRDP_Authentication,
+    RequestedProtocols = FreeRDP_RequestedProtocols,
+    SelectedProtocol = FreeRDP_SelectedProtocol,
+    NegotiationFlags = FreeRDP_NegotiationFlags,
+    NegotiateSecurityLayer = FreeRDP_NegotiateSecurityLayer,
+    RestrictedAdminModeRequired = FreeRDP_RestrictedAdminModeRequired,
+    AuthenticationServiceClass = FreeRDP_AuthenticationServiceClass,
+    DisableCredentialsDelegation = FreeRDP_DisableCredentialsDelegation,
+    AuthenticationLevel = FreeRDP_AuthenticationLevel,
+    AllowedTlsCiphers = FreeRDP_AllowedTlsCiphers,
+    VmConnectMode = FreeRDP_VmConnectMode,
+    NtlmSamFile = FreeRDP_NtlmSamFile,
+    FIPSMode = FreeRDP_FIPSMode,
+    TlsSecLevel = FreeRDP_TlsSecLevel,
+    TLSMinVersion = FreeRDP_TLSMinVersion,
+    TLSMaxVersion = FreeRDP_TLSMaxVersion,
+    MstscCookieMode = FreeRDP_MstscCookieMode,
+    CookieMaxLength = FreeRDP_CookieMaxLength,
+    PreconnectionId = FreeRDP_PreconnectionId,
+    PreconnectionBlob = FreeRDP_PreconnectionBlob,
+    SendPreconnectionPdu = FreeRDP_SendPreconnectionPdu,
+    RedirectionFlags = FreeRDP_RedirectionFlags,
+    TargetNetAddress = FreeRDP_TargetNetAddress,
+    LoadBalanceInfo = FreeRDP_LoadBalanceInfo,
+    LoadBalanceInfoLength = FreeRDP_LoadBalanceInfoLength,
+    RedirectionUsername = FreeRDP_RedirectionUsername,
+    RedirectionDomain = FreeRDP_RedirectionDomain,
+    RedirectionPassword = FreeRDP_RedirectionPassword,
+    RedirectionPasswordLength = FreeRDP_RedirectionPasswordLength,
+    RedirectionTargetFQDN = FreeRDP_RedirectionTargetFQDN,
+    RedirectionTargetNetBiosName = FreeRDP_RedirectionTargetNetBiosName,
+    RedirectionTsvUrl = FreeRDP_RedirectionTsvUrl,
+    RedirectionTsvUrlLength = FreeRDP_RedirectionTsvUrlLength,
+    TargetNetAddressCount = FreeRDP_TargetNetAddressCount,
+    TargetNetAddresses = FreeRDP_TargetNetAddresses,
+    TargetNetPorts = FreeRDP_TargetNetPorts,
+    RedirectionAcceptedCert = FreeRDP_RedirectionAcceptedCert,
+    RedirectionAcceptedCertLength = FreeRDP_RedirectionAcceptedCertLength,
+    RedirectionPreferType = FreeRDP_RedirectionPreferType,
+    RedirectionGuid = FreeRDP_RedirectionGuid,
+    RedirectionGuidLength = FreeRDP_RedirectionGuidLength,
+    RedirectionTargetCertificate = FreeRDP_RedirectionTargetCertificate,
+    RedirectionTargetCertificateLength = FreeRDP_RedirectionTargetCertificateLength,
+    Password51 = FreeRDP_Password51,
+    Password51Length = FreeRDP_Password51Length,
+    SmartcardLogon = FreeRDP_SmartcardLogon,
+    PromptForCredentials = FreeRDP_PromptForCredentials,
+    KerberosKdc = FreeRDP_KerberosKdc,
+    KerberosRealm = FreeRDP_KerberosRealm,
+    IgnoreCertificate = FreeRDP_IgnoreCertificate,
+    CertificateName = FreeRDP_CertificateName,
+    CertificateFile = FreeRDP_CertificateFile,
+    PrivateKeyFile = FreeRDP_PrivateKeyFile,
+    RdpKeyFile = FreeRDP_RdpKeyFile,
+    RdpServerRsaKey = FreeRDP_RdpServerRsaKey,
+    RdpServerCertificate = FreeRDP_RdpServerCertificate,
+    ExternalCertificateManagement = FreeRDP_ExternalCertificateManagement,
+    CertificateContent = FreeRDP_CertificateContent,
+    PrivateKeyContent = FreeRDP_PrivateKeyContent,
+    RdpKeyContent = FreeRDP_RdpKeyContent,
+    AutoAcceptCertificate = FreeRDP_AutoAcceptCertificate,
+    AutoDenyCertificate = FreeRDP_AutoDenyCertificate,
+    CertificateAcceptedFingerprints = FreeRDP_CertificateAcceptedFingerprints,
+    Workarea = FreeRDP_Workarea,
+    Fullscreen = FreeRDP_Fullscreen,
+    PercentScreen = FreeRDP_PercentScreen,
+    GrabKeyboard = FreeRDP_GrabKeyboard,
+    Decorations = FreeRDP_Decorations,
+    MouseMotion = FreeRDP_MouseMotion,
+    WindowTitle = FreeRDP_WindowTitle,
+    ParentWindowId = FreeRDP_ParentWindowId,
+    AsyncInput = FreeRDP_AsyncInput,
+    AsyncUpdate = FreeRDP_AsyncUpdate,
+    AsyncChannels = FreeRDP_AsyncChannels,
+    ToggleFullscreen = FreeRDP_ToggleFullscreen,
+    WmClass = FreeRDP_WmClass,
+    EmbeddedWindow = FreeRDP_EmbeddedWindow,
+    SmartSizing = FreeRDP_SmartSizing,
+    XPan = FreeRDP_XPan,
+    YPan = FreeRDP_YPan,
+    SmartSizingWidth = FreeRDP_SmartSizingWidth,
+    SmartSizingHeight = FreeRDP_SmartSizingHeight,
+    PercentScreenUseWidth = FreeRDP_PercentScreenUseWidth,
+    PercentScreenUseHeight = FreeRDP_PercentScreenUseHeight,
+    DynamicResolutionUpdate = FreeRDP_DynamicResolutionUpdate,
+    SoftwareGdi = FreeRDP_SoftwareGdi,
+    LocalConnection = FreeRDP_LocalConnection,
+    AuthenticationOnly = FreeRDP_AuthenticationOnly,
+    CredentialsFromStdin = FreeRDP_CredentialsFromStdin,
+    UnmapButtons = FreeRDP_UnmapButtons,
+    OldLicenseBehaviour = FreeRDP_OldLicenseBehaviour,
+    ComputerName = FreeRDP_ComputerName,
+    ConnectionFile = FreeRDP_ConnectionFile,
+    AssistanceFile = FreeRDP_AssistanceFile,
+    HomePath = FreeRDP_HomePath,
+    ConfigPath = FreeRDP_ConfigPath,
+    CurrentPath = FreeRDP_CurrentPath,
+    DumpRemoteFx = FreeRDP_DumpRemoteFx,
+    PlayRemoteFx = FreeRDP_PlayRemoteFx,
+    DumpRemoteFxFile = FreeRDP_DumpRemoteFxFile,
+    PlayRemoteFxFile = FreeRDP_PlayRemoteFxFile,
+    GatewayUsageMethod = FreeRDP_GatewayUsageMethod,
+    GatewayPort = FreeRDP_GatewayPort,
+    GatewayHostname = FreeRDP_GatewayHostname,
+    GatewayUsername = FreeRDP_GatewayUsername,
+    GatewayPassword = FreeRDP_GatewayPassword,
+    GatewayDomain = FreeRDP_GatewayDomain,
+    GatewayCredentialsSource = FreeRDP_GatewayCredentialsSource,
+    GatewayUseSameCredentials = FreeRDP_GatewayUseSameCredentials,
+    GatewayEnabled = FreeRDP_GatewayEnabled,
+    GatewayBypassLocal = FreeRDP_GatewayBypassLocal,
+    GatewayRpcTransport = FreeRDP_GatewayRpcTransport,
+    GatewayHttpTransport = FreeRDP_GatewayHttpTransport,
+    GatewayUdpTransport = FreeRDP_GatewayUdpTransport,
+    GatewayAccessToken = FreeRDP_GatewayAccessToken,
+    GatewayAcceptedCert = FreeRDP_GatewayAcceptedCert,
+    GatewayAcceptedCertLength = FreeRDP_GatewayAcceptedCertLength,
+    GatewayHttpUseWebsockets = FreeRDP_GatewayHttpUseWebsockets,
+    ProxyType = FreeRDP_ProxyType,
+    ProxyHostname = FreeRDP_ProxyHostname,
+    ProxyPort = FreeRDP_ProxyPort,
+    ProxyUsername = FreeRDP_ProxyUsername,
+    ProxyPassword = FreeRDP_ProxyPassword,
+    RemoteApplicationMode = FreeRDP_RemoteApplicationMode,
+    RemoteApplicationName = FreeRDP_RemoteApplicationName,
+    RemoteApplicationIcon = FreeRDP_RemoteApplicationIcon,
+    RemoteApplicationProgram = FreeRDP_RemoteApplicationProgram,
+    RemoteApplicationFile = FreeRDP_RemoteApplicationFile,
+    RemoteApplicationGuid = FreeRDP_RemoteApplicationGuid,
+    RemoteApplicationCmdLine = FreeRDP_RemoteApplicationCmdLine,
+    RemoteApplicationExpandCmdLine = FreeRDP_RemoteApplicationExpandCmdLine,
+    RemoteApplicationExpandWorkingDir = FreeRDP_RemoteApplicationExpandWorkingDir,
+    DisableRemoteAppCapsCheck = FreeRDP_DisableRemoteAppCapsCheck,
+    RemoteAppNumIconCaches = FreeRDP_RemoteAppNumIconCaches,
+    RemoteAppNumIconCacheEntries = FreeRDP_RemoteAppNumIconCacheEntries,
+    RemoteAppLanguageBarSupported = FreeRDP_RemoteAppLanguageBarSupported,
+    RemoteWndSupportLevel = FreeRDP_RemoteWndSupportLevel,
+    RemoteApplicationSupportLevel = FreeRDP_RemoteApplicationSupportLevel,
+    RemoteApplicationSupportMask = FreeRDP_RemoteApplicationSupportMask,
+    RemoteApplicationWorkingDir = FreeRDP_RemoteApplicationWorkingDir,
+    ReceivedCapabilities = FreeRDP_ReceivedCapabilities,
+    ReceivedCapabilitiesSize = FreeRDP_ReceivedCapabilitiesSize,
+    OsMajorType = FreeRDP_OsMajorType,
+    OsMinorType = FreeRDP_OsMinorType,
+    RefreshRect = FreeRDP_RefreshRect,
+    SuppressOutput = FreeRDP_SuppressOutput,
+    FastPathOutput = FreeRDP_FastPathOutput,
+    SaltedChecksum = FreeRDP_SaltedChecksum,
+    LongCredentialsSupported = FreeRDP_LongCredentialsSupported,
+    NoBitmapCompressionHeader = FreeRDP_NoBitmapCompressionHeader,
+    BitmapCompressionDisabled = FreeRDP_BitmapCompressionDisabled,
+    DesktopResize = FreeRDP_DesktopResize,
+    DrawAllowDynamicColorFidelity = FreeRDP_DrawAllowDynamicColorFidelity,
+    DrawAllowColorSubsampling = FreeRDP_DrawAllowColorSubsampling,
+    DrawAllowSkipAlpha = FreeRDP_DrawAllowSkipAlpha,
+    OrderSupport = FreeRDP_OrderSupport,
+    BitmapCacheV3Enabled = FreeRDP_BitmapCacheV3Enabled,
+    AltSecFrameMarkerSupport = FreeRDP_AltSecFrameMarkerSupport,
+    AllowUnanouncedOrdersFromServer = FreeRDP_AllowUnanouncedOrdersFromServer,
+    BitmapCacheEnabled = FreeRDP_BitmapCacheEnabled,
+    BitmapCacheVersion = FreeRDP_BitmapCacheVersion,
+    AllowCacheWaitingList = FreeRDP_AllowCacheWaitingList,
+    BitmapCachePersistEnabled = FreeRDP_BitmapCachePersistEnabled,
+    BitmapCacheV2NumCells = FreeRDP_BitmapCacheV2NumCells,
+    BitmapCacheV2CellInfo = FreeRDP_BitmapCacheV2CellInfo,
+    ColorPointerFlag = FreeRDP_ColorPointerFlag,
+    PointerCacheSize = FreeRDP_PointerCacheSize,
+    KeyboardRemappingList = FreeRDP_KeyboardRemappingList,
+    KeyboardCodePage = FreeRDP_KeyboardCodePage,
+    KeyboardLayout = FreeRDP_KeyboardLayout,
+    KeyboardType = FreeRDP_KeyboardType,
+    KeyboardSubType = FreeRDP_KeyboardSubType,
+    KeyboardFunctionKey = FreeRDP_KeyboardFunctionKey,
+    ImeFileName = FreeRDP_ImeFileName,
+    UnicodeInput = FreeRDP_UnicodeInput,
+    FastPathInput = FreeRDP_FastPathInput,
+    MultiTouchInput = FreeRDP_MultiTouchInput,
+    MultiTouchGestures = FreeRDP_MultiTouchGestures,
+    KeyboardHook = FreeRDP_KeyboardHook,
+    HasHorizontalWheel = FreeRDP_HasHorizontalWheel,
+    HasExtendedMouseEvent = FreeRDP_HasExtendedMouseEvent,
+    BrushSupportLevel = FreeRDP_BrushSupportLevel,
+    GlyphSupportLevel = FreeRDP_GlyphSupportLevel,
+    GlyphCache = FreeRDP_GlyphCache,
+    FragCache = FreeRDP_FragCache,
+    OffscreenSupportLevel = FreeRDP_OffscreenSupportLevel,
+    OffscreenCacheSize = FreeRDP_OffscreenCacheSize,
+    OffscreenCacheEntries = FreeRDP_OffscreenCacheEntries,
+    VirtualChannelCompressionFlags = FreeRDP_VirtualChannelCompressionFlags,
+    VirtualChannelChunkSize = FreeRDP_VirtualChannelChunkSize,
+    SoundBeepsEnabled = FreeRDP_SoundBeepsEnabled,
+    MultifragMaxRequestSize = FreeRDP_MultifragMaxRequestSize,
+    LargePointerFlag = FreeRDP_LargePointerFlag,
+    CompDeskSupportLevel = FreeRDP_CompDeskSupportLevel,
+    SurfaceCommandsEnabled = FreeRDP_SurfaceCommandsEnabled,
+    FrameMarkerCommandEnabled = FreeRDP_FrameMarkerCommandEnabled,
+    SurfaceFrameMarkerEnabled = FreeRDP_SurfaceFrameMarkerEnabled,
+    RemoteFxOnly = FreeRDP_RemoteFxOnly,
+    RemoteFxCodec = FreeRDP_RemoteFxCodec,
+    RemoteFxCodecId = FreeRDP_RemoteFxCodecId,
+    RemoteFxCodecMode = FreeRDP_RemoteFxCodecMode,
+    RemoteFxImageCodec = FreeRDP_RemoteFxImageCodec,
+    RemoteFxCaptureFlags = FreeRDP_RemoteFxCaptureFlags,
+    NSCodec = FreeRDP_NSCodec,
+    NSCodecId = FreeRDP_NSCodecId,
+    FrameAcknowledge = FreeRDP_FrameAcknowledge,
+    NSCodecColorLossLevel = FreeRDP_NSCodecColorLossLevel,
+    NSCodecAllowSubsampling = FreeRDP_NSCodecAllowSubsampling,
+    NSCodecAllowDynamicColorFidelity = FreeRDP_NSCodecAllowDynamicColorFidelity,
+    JpegCodec = FreeRDP_JpegCodec,
+    JpegCodecId = FreeRDP_JpegCodecId,
+    JpegQuality = FreeRDP_JpegQuality,
+    GfxThinClient = FreeRDP_GfxThinClient,
+    GfxSmallCache = FreeRDP_GfxSmallCache,
+    GfxProgressive = FreeRDP_GfxProgressive,
+    GfxProgressiveV2 = FreeRDP_GfxProgressiveV2,
+    GfxH264 = FreeRDP_GfxH264,
+    GfxAVC444 = FreeRDP_GfxAVC444,
+    GfxSendQoeAck = FreeRDP_GfxSendQoeAck,
+    GfxAVC444v2 = FreeRDP_GfxAVC444v2,
+    GfxCapsFilter = FreeRDP_GfxCapsFilter,
+    BitmapCacheV3CodecId = FreeRDP_BitmapCacheV3CodecId,
+    DrawNineGridEnabled = FreeRDP_DrawNineGridEnabled,
+    DrawNineGridCacheSize = FreeRDP_DrawNineGridCacheSize,
+    DrawNineGridCacheEntries = FreeRDP_DrawNineGridCacheEntries,
+    DrawGdiPlusEnabled = FreeRDP_DrawGdiPlusEnabled,
+    DrawGdiPlusCacheEnabled = FreeRDP_DrawGdiPlusCacheEnabled,
+    DeviceRedirection = FreeRDP_DeviceRedirection,
+    DeviceCount = FreeRDP_DeviceCount,
+    DeviceArraySize = FreeRDP_DeviceArraySize,
+    DeviceArray = FreeRDP_DeviceArray,
+    RedirectDrives = FreeRDP_RedirectDrives,
+    RedirectHomeDrive = FreeRDP_RedirectHomeDrive,
+    DrivesToRedirect = FreeRDP_DrivesToRedirect,
+    RedirectSmartCards = FreeRDP_RedirectSmartCards,
+    RedirectPrinters = FreeRDP_RedirectPrinters,
+    RedirectSerialPorts = FreeRDP_RedirectSerialPorts,
+    RedirectParallelPorts = FreeRDP_RedirectParallelPorts,
+    PreferIPv6OverIPv4 = FreeRDP_PreferIPv6OverIPv4,
+    RedirectClipboard = FreeRDP_RedirectClipboard,
+    StaticChannelCount = FreeRDP_StaticChannelCount,
+    StaticChannelArraySize = FreeRDP_StaticChannelArraySize,
+    StaticChannelArray = FreeRDP_StaticChannelArray,
+    DynamicChannelCount = FreeRDP_DynamicChannelCount,
+    DynamicChannelArraySize = FreeRDP_DynamicChannelArraySize,
+    DynamicChannelArray = FreeRDP_DynamicChannelArray,
+    SupportDynamicChannels = FreeRDP_SupportDynamicChannels,
+    SupportEchoChannel = FreeRDP_SupportEchoChannel,
+    SupportDisplayControl = FreeRDP_SupportDisplayControl,
+    SupportGeometryTracking = FreeRDP_SupportGeometryTracking,
+    SupportSSHAgentChannel = FreeRDP_SupportSSHAgentChannel,
+    SupportVideoOptimized = FreeRDP_SupportVideoOptimized,
+    RDP2TCPArgs = FreeRDP_RDP2TCPArgs,
+    TcpKeepAlive = FreeRDP_TcpKeepAlive,
+    TcpKeepAliveRetries = FreeRDP_TcpKeepAliveRetries,
+    TcpKeepAliveDelay = FreeRDP_TcpKeepAliveDelay,
+    TcpKeepAliveInterval = FreeRDP_TcpKeepAliveInterval,
+    TcpAckTimeout = FreeRDP_TcpAckTimeout,
+    TcpConnectTimeout = FreeRDP_TcpConnectTimeout,
+}
+
+impl Key {
+    /// Look up a key by its FreeRDP name, such as `"DesktopWidth"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = CString::new(name).ok()?;
+        let key = unsafe { sys::freerdp_settings_get_key_for_name(name.as_ptr()) };
+        usize::try_from(key).ok()?.try_into().ok()
+    }
+
+    pub fn name(&self) -> &'static str {
+        let name = unsafe { sys::freerdp_settings_get_name_for_key(*self as _) };
+        if name.is_null() {
+            return "";
+        }
+        unsafe { CStr::from_ptr(name) }.to_str().unwrap_or_default()
+    }
+
+    pub fn type_(&self) -> Option<Type> {
+        let type_ = unsafe { sys::freerdp_settings_get_type_for_key(*self as _) };
+        (type_ as isize).try_into().ok()
+    }
+}
+
+impl FromStr for Key {
+    type Err = RdpError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::from_name(s).ok_or(RdpError::Unsupported)
+    }
+}
+
+/// The storage type of a settings key.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Type {
+    Bool = 0,
+    UInt16 = 1,
+    Int16 = 2,
+    UInt32 = 3,
+    Int32 = 4,
+    UInt64 = 5,
+    Int64 = 6,
+    String = 7,
+    Pointer = 8,
+}
+
+impl TryFrom<isize> for Type {
+    type Error = RdpError;
+
+    fn try_from(value: isize) -> std::result::Result<Self, Self::Error> {
+        match value {
+            v if v == Type::Bool as isize => Ok(Type::Bool),
+            v if v == Type::UInt16 as isize => Ok(Type::UInt16),
+            v if v == Type::Int16 as isize => Ok(Type::Int16),
+            v if v == Type::UInt32 as isize => Ok(Type::UInt32),
+            v if v == Type::Int32 as isize => Ok(Type::Int32),
+            v if v == Type::UInt64 as isize => Ok(Type::UInt64),
+            v if v == Type::Int64 as isize => Ok(Type::Int64),
+            v if v == Type::String as isize => Ok(Type::String),
+            v if v == Type::Pointer as isize => Ok(Type::Pointer),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+/// A dynamically typed settings value.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SettingValue {
+    Bool(bool),
+    UInt16(u16),
+    Int16(i16),
+    UInt32(u32),
+    Int32(i32),
+    UInt64(u64),
+    Int64(i64),
+    String(Option<String>),
+}
+
+/// A settings storage type, used to select the accessor with
+/// [`Settings::get`] and [`Settings::set`].
+pub trait Kind {
+    const TYPE: Type;
+
+    type Value;
+
+    #[doc(hidden)]
+    fn get(settings: &Settings, key: Key) -> Self::Value;
+
+    #[doc(hidden)]
+    fn set(settings: &mut Settings, key: Key, value: Self::Value) -> bool;
+}
+
+macro_rules! kind {
+    ($kind:ident, $value:ty, $get:ident, $set:ident) => {
+        #[derive(Debug)]
+        pub enum $kind {}
+
+        impl Kind for $kind {
+            const TYPE: Type = Type::$kind;
+
+            type Value = $value;
+
+            fn get(settings: &Settings, key: Key) -> Self::Value {
+                unsafe { sys::$get(settings.inner.as_ptr(), key as _) as _ }
+            }
+
+            fn set(settings: &mut Settings, key: Key, value: Self::Value) -> bool {
+                unsafe { sys::$set(settings.inner.as_ptr(), key as _, value as _) != 0 }
+            }
+        }
+    };
+}
+
+#[derive(Debug)]
+pub enum Bool {}
+
+impl Kind for Bool {
+    const TYPE: Type = Type::Bool;
+
+    type Value = bool;
+
+    fn get(settings: &Settings, key: Key) -> Self::Value {
+        unsafe { sys::freerdp_settings_get_bool(settings.inner.as_ptr(), key as _) != 0 }
+    }
+
+    fn set(settings: &mut Settings, key: Key, value: Self::Value) -> bool {
+        unsafe {
+            sys::freerdp_settings_set_bool(settings.inner.as_ptr(), key as _, value as _) != 0
+        }
+    }
+}
+
+kind!(
+    UInt16,
+    u16,
+    freerdp_settings_get_uint16,
+    freerdp_settings_set_uint16
+);
+kind!(
+    Int16,
+    i16,
+    freerdp_settings_get_int16,
+    freerdp_settings_set_int16
+);
+kind!(
+    UInt32,
+    u32,
+    freerdp_settings_get_uint32,
+    freerdp_settings_set_uint32
+);
+kind!(
+    Int32,
+    i32,
+    freerdp_settings_get_int32,
+    freerdp_settings_set_int32
+);
+kind!(
+    UInt64,
+    u64,
+    freerdp_settings_get_uint64,
+    freerdp_settings_set_uint64
+);
+kind!(
+    Int64,
+    i64,
+    freerdp_settings_get_int64,
+    freerdp_settings_set_int64
+);
+
+#[derive(Debug)]
+pub enum Str {}
+
+impl Kind for Str {
+    const TYPE: Type = Type::String;
+
+    type Value = Option<String>;
+
+    fn get(settings: &Settings, key: Key) -> Self::Value {
+        let ptr = unsafe { sys::freerdp_settings_get_string(settings.inner.as_ptr(), key as _) };
+        if ptr.is_null() {
+            None
+        } else {
+            Some(
+                unsafe { CStr::from_ptr(ptr) }
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        }
+    }
+
+    fn set(settings: &mut Settings, key: Key, value: Self::Value) -> bool {
+        let value = match value.map(CString::new) {
+            Some(Ok(s)) => Some(s),
+            Some(Err(_)) => return false,
+            None => None,
+        };
+        unsafe {
+            sys::freerdp_settings_set_string(
+                settings.inner.as_ptr(),
+                key as _,
+                value.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            ) != 0
+        }
+    }
+}
+
+fn check_type(key: Key, type_: Type) -> Result<()> {
+    match key.type_() {
+        Some(t) if t == type_ => Ok(()),
+        Some(t) => Err(RdpError::Failed(format!(
+            "{:?} is a {:?} setting, not {:?}",
+            key, t, type_
+        ))),
+        None => Err(RdpError::Failed(format!("Unknown setting {:?}", key))),
+    }
+}
+
+impl Settings {
+    /// Get the value of `key`, which must be of type `K`.
+    pub fn get<K: Kind>(&self, key: Key) -> Result<K::Value> {
+        check_type(key, K::TYPE)?;
+        Ok(K::get(self, key))
+    }
+
+    /// Set the value of `key`, which must be of type `K`.
+    pub fn set<K: Kind>(&mut self, key: Key, value: K::Value) -> Result<()> {
+        check_type(key, K::TYPE)?;
+        if K::set(self, key, value) {
+            Ok(())
+        } else {
+            Err(RdpError::Failed(format!("Failed to set {:?}", key)))
+        }
+    }
+
+    /// Get the value of `key`, whatever its type.
+    ///
+    /// Pointer settings are not supported.
+    pub fn get_value(&self, key: Key) -> Result<SettingValue> {
+        let value = match key.type_() {
+            Some(Type::Bool) => SettingValue::Bool(Bool::get(self, key)),
+            Some(Type::UInt16) => SettingValue::UInt16(UInt16::get(self, key)),
+            Some(Type::Int16) => SettingValue::Int16(Int16::get(self, key)),
+            Some(Type::UInt32) => SettingValue::UInt32(UInt32::get(self, key)),
+            Some(Type::Int32) => SettingValue::Int32(Int32::get(self, key)),
+            Some(Type::UInt64) => SettingValue::UInt64(UInt64::get(self, key)),
+            Some(Type::Int64) => SettingValue::Int64(Int64::get(self, key)),
+            Some(Type::String) => SettingValue::String(Str::get(self, key)),
+            Some(Type::Pointer) | None => return Err(RdpError::Unsupported),
+        };
+        Ok(value)
+    }
+
+    /// Set the value of `key`, the variant must match the key type.
+    pub fn set_value(&mut self, key: Key, value: SettingValue) -> Result<()> {
+        match value {
+            SettingValue::Bool(v) => self.set::<Bool>(key, v),
+            SettingValue::UInt16(v) => self.set::<UInt16>(key, v),
+            SettingValue::Int16(v) => self.set::<Int16>(key, v),
+            SettingValue::UInt32(v) => self.set::<UInt32>(key, v),
+            SettingValue::Int32(v) => self.set::<Int32>(key, v),
+            SettingValue::UInt64(v) => self.set::<UInt64>(key, v),
+            SettingValue::Int64(v) => self.set::<Int64>(key, v),
+            SettingValue::String(v) => self.set::<Str>(key, v),
+        }
+    }
+
+    /// Get a setting by its FreeRDP name, such as `"DesktopWidth"`.
+    pub fn get_by_name(&self, name: &str) -> Result<SettingValue> {
+        self.get_value(name.parse()?)
+    }
+
+    /// Set a setting by its FreeRDP name, parsing `value` as FreeRDP does.
+    pub fn set_by_name(&mut self, name: &str, value: &str) -> Result<()> {
+        let cname = CString::new(name)?;
+        let cvalue = CString::new(value)?;
+        if unsafe {
+            sys::freerdp_settings_set_value_for_name(
+                self.inner.as_ptr(),
+                cname.as_ptr(),
+                cvalue.as_ptr(),
+            )
+        } != 0
+        {
+            Ok(())
+        } else {
+            Err(RdpError::Failed(format!("Failed to set {}", name)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_set() {
+        let mut settings = Settings::alloc().unwrap();
+
+        settings.set::<Bool>(Key::NlaSecurity, false).unwrap();
+        assert!(!settings.get::<Bool>(Key::NlaSecurity).unwrap());
+        settings.set::<Bool>(Key::NlaSecurity, true).unwrap();
+        assert!(settings.get::<Bool>(Key::NlaSecurity).unwrap());
+
+        settings.set::<UInt32>(Key::DesktopWidth, 1280).unwrap();
+        assert_eq!(settings.get::<UInt32>(Key::DesktopWidth).unwrap(), 1280);
+
+        settings
+            .set::<Str>(Key::ServerHostname, Some("example.com".into()))
+            .unwrap();
+        assert_eq!(
+            settings.get::<Str>(Key::ServerHostname).unwrap().as_deref(),
+            Some("example.com")
+        );
+        settings.set::<Str>(Key::ServerHostname, None).unwrap();
+        assert_eq!(settings.get::<Str>(Key::ServerHostname).unwrap(), None);
+
+        // the kind must match the key type
+        assert!(settings.get::<Bool>(Key::DesktopWidth).is_err());
+        assert!(settings.set::<UInt16>(Key::DesktopWidth, 1).is_err());
+    }
+
+    #[test]
+    fn by_name() {
+        let mut settings = Settings::alloc().unwrap();
+
+        assert_eq!(Key::from_name("DesktopHeight"), Some(Key::DesktopHeight));
+        assert_eq!(Key::DesktopHeight.name(), "DesktopHeight");
+        assert_eq!(Key::DesktopHeight.type_(), Some(Type::UInt32));
+        assert_eq!(Key::from_name("NoSuchSetting"), None);
+
+        settings.set_by_name("DesktopHeight", "720").unwrap();
+        assert_eq!(
+            settings.get_by_name("DesktopHeight").unwrap(),
+            SettingValue::UInt32(720)
+        );
+        settings.set_by_name("Username", "jdoe").unwrap();
+        assert_eq!(
+            settings.get_by_name("Username").unwrap(),
+            SettingValue::String(Some("jdoe".into()))
+        );
+
+        settings
+            .set_value(Key::GatewayEnabled, SettingValue::Bool(true))
+            .unwrap();
+        assert_eq!(
+            settings.get_value(Key::GatewayEnabled).unwrap(),
+            SettingValue::Bool(true)
+        );
+
+        assert!(settings.get_by_name("NoSuchSetting").is_err());
+        assert!(settings.set_by_name("NoSuchSetting", "1").is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! A loopback RDP server, to test the client handlers without a Windows
+//! host.
+//!
+//! The server accepts TLS connections on 127.0.0.1, with a self-signed
+//! certificate, and runs a [`PeerHandler`] for each of them, [`TestPeer`]
+//! by default:
+//!
+//! ```no_run
+//! # use freerdp2::{client::*, testing::*, Result};
+//! # struct MyHandler;
+//! # impl Handler for MyHandler {}
+//! # fn main() -> Result<()> {
+//! let server = TestServer::start()?;
+//! let mut context = Context::new(MyHandler);
+//! context.client_start()?;
+//! server.configure_client(&mut context.settings)?;
+//! context.instance.connect()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    server::{Listener, Peer, PeerHandler},
+    settings::typed::{Bool, Key, Str, UInt32},
+    update::{CodecId, FrameAction, FrameMarker, SurfaceBits, SystemPointer},
+    winpr::wait_for_multiple_objects,
+    Rect, Result, Settings,
+};
+
+const CERTIFICATE: &str = include_str!("cert.pem");
+const PRIVATE_KEY: &str = include_str!("key.pem");
+
+const CMDTYPE_STREAM_SURFACE_BITS: u32 = 0x0006;
+// keeps each surface bits command under the default multifragment size
+const TILE_SIZE: u16 = 64;
+// how often the connections check for drop_connections()
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A server listening on an ephemeral port of 127.0.0.1, stopped on drop.
+#[derive(Debug)]
+pub struct TestServer {
+    port: u16,
+    stop: Arc<AtomicBool>,
+    // incremented to close the current connections
+    epoch: Arc<AtomicUsize>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl TestServer {
+    /// Start a server handling the connections with [`TestPeer`].
+    pub fn start() -> Result<Self> {
+        Self::start_with(TestPeer::default)
+    }
+
+    /// Start a server handling each connection with a new handler.
+    pub fn start_with<H, F>(new_handler: F) -> Result<Self>
+    where
+        H: PeerHandler + Send + 'static,
+        F: Fn() -> H + Send + 'static,
+    {
+        let socket = TcpListener::bind("127.0.0.1:0")?;
+        let port = socket.local_addr()?.port();
+        let mut listener = Listener::new()?;
+        listener.open_from_socket(socket)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let epoch = Arc::new(AtomicUsize::new(0));
+        let thread = thread::spawn({
+            let stop = stop.clone();
+            let epoch = epoch.clone();
+            move || {
+                let mut peers: Vec<thread::JoinHandle<_>> = Vec::new();
+                while listener.wait().is_ok() && !stop.load(Ordering::SeqCst) {
+                    peers.retain(|peer| !peer.is_finished());
+                    while let Some(incoming) = listener.accept() {
+                        let mut peer = match Peer::new(incoming, new_handler()) {
+                            Ok(peer) => peer,
+                            Err(_) => continue,
+                        };
+                        if configure_server(&mut peer.settings).is_err() {
+                            continue;
+                        }
+                        let epoch = epoch.clone();
+                        peers.push(thread::spawn(move || serve(&mut peer, epoch)));
+                    }
+                }
+                for peer in peers {
+                    let _ = peer.join();
+                }
+            }
+        });
+
+        Ok(Self {
+            port,
+            stop,
+            epoch,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Close the current connections, as a network drop would. The server
+    /// keeps accepting new ones.
+    pub fn drop_connections(&self) {
+        self.epoch.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Point the client `settings` to the server, with TLS security only.
+    pub fn configure_client(&self, settings: &mut Settings) -> Result<()> {
+        settings.set_server_hostname(Some("127.0.0.1"))?;
+        settings.set_server_port(self.port.into());
+        settings.set::<Bool>(Key::RdpSecurity, false)?;
+        settings.set::<Bool>(Key::NlaSecurity, false)?;
+        settings.set::<Bool>(Key::TlsSecurity, true)?;
+        settings.set::<Bool>(Key::IgnoreCertificate, true)?;
+        Ok(())
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        self.drop_connections();
+        // wake the listener up
+        let _ = TcpStream::connect(("127.0.0.1", self.port));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve<H: PeerHandler>(peer: &mut Peer<H>, epoch: Arc<AtomicUsize>) -> Result<()> {
+    let started = epoch.load(Ordering::SeqCst);
+
+    peer.initialize()?;
+    while epoch.load(Ordering::SeqCst) == started {
+        let handles = peer.event_handles()?;
+        let handles: Vec<_> = handles.iter().collect();
+        wait_for_multiple_objects(&handles, false, Some(&POLL_INTERVAL))?;
+
+        if !peer.check_event_handles() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn configure_server(settings: &mut Settings) -> Result<()> {
+    settings.set::<Str>(Key::CertificateContent, Some(CERTIFICATE.into()))?;
+    settings.set::<Str>(Key::PrivateKeyContent, Some(PRIVATE_KEY.into()))?;
+    settings.set::<Bool>(Key::RdpSecurity, false)?;
+    settings.set::<Bool>(Key::NlaSecurity, false)?;
+    settings.set::<Bool>(Key::TlsSecurity, true)?;
+    Ok(())
+}
+
+/// Once activated, fills the desktop with `color` in a single frame of
+/// uncompressed surface bits, and shows the default pointer.
+#[derive(Debug, Clone)]
+pub struct TestPeer {
+    /// BGRA
+    pub color: [u8; 4],
+}
+
+impl Default for TestPeer {
+    fn default() -> Self {
+        Self {
+            color: [0x80, 0x40, 0x20, 0xff],
+        }
+    }
+}
+
+impl PeerHandler for TestPeer {
+    fn activate(&mut self, peer: &mut Peer<Self>) -> Result<()> {
+        let width = u16::try_from(peer.settings.get::<UInt32>(Key::DesktopWidth)?)?;
+        let height = u16::try_from(peer.settings.get::<UInt32>(Key::DesktopHeight)?)?;
+        let tile = self.color.repeat((TILE_SIZE * TILE_SIZE).into());
+
+        peer.send_frame_marker(&FrameMarker {
+            action: FrameAction::Begin,
+            frame_id: 1,
+        })?;
+        for top in (0..height).step_by(TILE_SIZE.into()) {
+            for left in (0..width).step_by(TILE_SIZE.into()) {
+                let w = TILE_SIZE.min(width - left);
+                let h = TILE_SIZE.min(height - top);
+                peer.send_surface_bits(&SurfaceBits {
+                    cmd_type: CMDTYPE_STREAM_SURFACE_BITS,
+                    dest: Rect::new(left, top, w, h),
+                    bpp: 32,
+                    flags: 0,
+                    codec: Ok(CodecId::None),
+                    width: w,
+                    height: h,
+                    data: &tile[..usize::from(w) * usize::from(h) * 4],
+                })?;
+            }
+        }
+        peer.send_frame_marker(&FrameMarker {
+            action: FrameAction::End,
+            frame_id: 1,
+        })?;
+        peer.send_pointer_system(SystemPointer::Default)
+    }
+}
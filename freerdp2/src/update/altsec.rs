@@ -0,0 +1,497 @@
+use std::{fmt::Debug, marker::PhantomData, ptr};
+
+use crate::{
+    client::{Context, Handler, RdpContext},
+    sys,
+    update::FrameAction,
+    Result,
+};
+
+use super::data;
+
+// The previous callbacks, see the chaining section of the module docs.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct AltSecChain {
+    create_offscreen_bitmap: sys::pCreateOffscreenBitmap,
+    switch_surface: sys::pSwitchSurface,
+    create_nine_grid_bitmap: sys::pCreateNineGridBitmap,
+    frame_marker: sys::pFrameMarker,
+    stream_bitmap_first: sys::pStreamBitmapFirst,
+    stream_bitmap_next: sys::pStreamBitmapNext,
+    draw_gdi_plus_first: sys::pDrawGdiPlusFirst,
+    draw_gdi_plus_next: sys::pDrawGdiPlusNext,
+    draw_gdi_plus_end: sys::pDrawGdiPlusEnd,
+    draw_gdi_plus_cache_first: sys::pDrawGdiPlusCacheFirst,
+    draw_gdi_plus_cache_next: sys::pDrawGdiPlusCacheNext,
+    draw_gdi_plus_cache_end: sys::pDrawGdiPlusCacheEnd,
+}
+
+pub struct AltSecUpdate<'a> {
+    inner: ptr::NonNull<sys::rdpAltSecUpdate>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> Debug for AltSecUpdate<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AltSecUpdate").finish()
+    }
+}
+
+impl<'a> AltSecUpdate<'a> {
+    pub(crate) fn new(altsec: *mut sys::rdpAltSecUpdate) -> Self {
+        Self {
+            inner: ptr::NonNull::new(altsec).unwrap(),
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// Observe the alternate secondary orders, chained to the previous
+    /// callbacks, see [chaining](super#chaining).
+    pub fn register<H: AltSecUpdateHandler>(&mut self) {
+        let inner = unsafe { self.inner.as_mut() };
+
+        let chain = &mut RdpContext::<H::ContextHandler>::from_ptr(inner.context).altsec_chain;
+        chain.create_offscreen_bitmap = inner.CreateOffscreenBitmap;
+        chain.switch_surface = inner.SwitchSurface;
+        chain.create_nine_grid_bitmap = inner.CreateNineGridBitmap;
+        chain.frame_marker = inner.FrameMarker;
+        chain.stream_bitmap_first = inner.StreamBitmapFirst;
+        chain.stream_bitmap_next = inner.StreamBitmapNext;
+        chain.draw_gdi_plus_first = inner.DrawGdiPlusFirst;
+        chain.draw_gdi_plus_next = inner.DrawGdiPlusNext;
+        chain.draw_gdi_plus_end = inner.DrawGdiPlusEnd;
+        chain.draw_gdi_plus_cache_first = inner.DrawGdiPlusCacheFirst;
+        chain.draw_gdi_plus_cache_next = inner.DrawGdiPlusCacheNext;
+        chain.draw_gdi_plus_cache_end = inner.DrawGdiPlusCacheEnd;
+
+        inner.CreateOffscreenBitmap = Some(rdp_altsec_create_offscreen_bitmap::<H>);
+        inner.SwitchSurface = Some(rdp_altsec_switch_surface::<H>);
+        inner.CreateNineGridBitmap = Some(rdp_altsec_create_nine_grid_bitmap::<H>);
+        inner.FrameMarker = Some(rdp_altsec_frame_marker::<H>);
+        inner.StreamBitmapFirst = Some(rdp_altsec_stream_bitmap_first::<H>);
+        inner.StreamBitmapNext = Some(rdp_altsec_stream_bitmap_next::<H>);
+        inner.DrawGdiPlusFirst = Some(rdp_altsec_draw_gdi_plus_first::<H>);
+        inner.DrawGdiPlusNext = Some(rdp_altsec_draw_gdi_plus_next::<H>);
+        inner.DrawGdiPlusEnd = Some(rdp_altsec_draw_gdi_plus_end::<H>);
+        inner.DrawGdiPlusCacheFirst = Some(rdp_altsec_draw_gdi_plus_cache_first::<H>);
+        inner.DrawGdiPlusCacheNext = Some(rdp_altsec_draw_gdi_plus_cache_next::<H>);
+        inner.DrawGdiPlusCacheEnd = Some(rdp_altsec_draw_gdi_plus_cache_end::<H>);
+    }
+}
+
+pub trait AltSecUpdateHandler {
+    type ContextHandler: Handler;
+
+    fn create_offscreen_bitmap(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CreateOffscreenBitmap,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn switch_surface(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &SwitchSurface,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn create_nine_grid_bitmap(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CreateNineGridBitmap,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn frame_marker(
+        _context: &mut Context<Self::ContextHandler>,
+        _action: FrameAction,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn stream_bitmap_first(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &StreamBitmapFirst,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn stream_bitmap_next(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &StreamBitmapNext,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw_gdi_plus_first(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &DrawGdiPlusFirst,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw_gdi_plus_next(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &DrawGdiPlusNext,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw_gdi_plus_end(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &DrawGdiPlusEnd,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw_gdi_plus_cache_first(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &DrawGdiPlusCache,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw_gdi_plus_cache_next(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &DrawGdiPlusCache,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn draw_gdi_plus_cache_end(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &DrawGdiPlusCache,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+macro_rules! callback {
+    ($name:ident, $method:ident, $sys:ty, $order:ty) => {
+        extern "C" fn $name<H: AltSecUpdateHandler>(
+            context: *mut sys::rdpContext,
+            order: $sys,
+        ) -> sys::BOOL {
+            let ctxt = Context::from_ptr(context);
+            let o = <$order>::from(unsafe { order.as_ref() }.unwrap());
+
+            if H::$method(ctxt, &o).is_err() {
+                return 0;
+            }
+            match RdpContext::<H::ContextHandler>::from_ptr(context)
+                .altsec_chain
+                .$method
+            {
+                Some(f) => unsafe { f(context, order) },
+                None => 1,
+            }
+        }
+    };
+}
+
+callback!(
+    rdp_altsec_create_offscreen_bitmap,
+    create_offscreen_bitmap,
+    *const sys::CREATE_OFFSCREEN_BITMAP_ORDER,
+    CreateOffscreenBitmap
+);
+callback!(
+    rdp_altsec_switch_surface,
+    switch_surface,
+    *const sys::SWITCH_SURFACE_ORDER,
+    SwitchSurface
+);
+callback!(
+    rdp_altsec_create_nine_grid_bitmap,
+    create_nine_grid_bitmap,
+    *const sys::CREATE_NINE_GRID_BITMAP_ORDER,
+    CreateNineGridBitmap
+);
+callback!(
+    rdp_altsec_stream_bitmap_first,
+    stream_bitmap_first,
+    *const sys::STREAM_BITMAP_FIRST_ORDER,
+    StreamBitmapFirst
+);
+callback!(
+    rdp_altsec_stream_bitmap_next,
+    stream_bitmap_next,
+    *const sys::STREAM_BITMAP_NEXT_ORDER,
+    StreamBitmapNext
+);
+callback!(
+    rdp_altsec_draw_gdi_plus_first,
+    draw_gdi_plus_first,
+    *const sys::DRAW_GDIPLUS_FIRST_ORDER,
+    DrawGdiPlusFirst
+);
+callback!(
+    rdp_altsec_draw_gdi_plus_next,
+    draw_gdi_plus_next,
+    *const sys::DRAW_GDIPLUS_NEXT_ORDER,
+    DrawGdiPlusNext
+);
+callback!(
+    rdp_altsec_draw_gdi_plus_end,
+    draw_gdi_plus_end,
+    *const sys::DRAW_GDIPLUS_END_ORDER,
+    DrawGdiPlusEnd
+);
+callback!(
+    rdp_altsec_draw_gdi_plus_cache_first,
+    draw_gdi_plus_cache_first,
+    *const sys::DRAW_GDIPLUS_CACHE_FIRST_ORDER,
+    DrawGdiPlusCache
+);
+callback!(
+    rdp_altsec_draw_gdi_plus_cache_next,
+    draw_gdi_plus_cache_next,
+    *const sys::DRAW_GDIPLUS_CACHE_NEXT_ORDER,
+    DrawGdiPlusCache
+);
+callback!(
+    rdp_altsec_draw_gdi_plus_cache_end,
+    draw_gdi_plus_cache_end,
+    *const sys::DRAW_GDIPLUS_CACHE_END_ORDER,
+    DrawGdiPlusCache
+);
+
+extern "C" fn rdp_altsec_frame_marker<H: AltSecUpdateHandler>(
+    context: *mut sys::rdpContext,
+    order: *const sys::FRAME_MARKER_ORDER,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let action = match unsafe { order.as_ref() }.unwrap().action.try_into() {
+        Ok(action) => action,
+        Err(_) => return 0,
+    };
+
+    if H::frame_marker(ctxt, action).is_err() {
+        return 0;
+    }
+    match RdpContext::<H::ContextHandler>::from_ptr(context)
+        .altsec_chain
+        .frame_marker
+    {
+        Some(f) => unsafe { f(context, order) },
+        None => 1,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreateOffscreenBitmap {
+    pub id: u32,
+    pub cx: u32,
+    pub cy: u32,
+    /// The offscreen bitmaps to delete first.
+    pub delete_list: Vec<u16>,
+}
+
+impl From<&sys::CREATE_OFFSCREEN_BITMAP_ORDER> for CreateOffscreenBitmap {
+    fn from(o: &sys::CREATE_OFFSCREEN_BITMAP_ORDER) -> Self {
+        let list = &o.deleteList;
+        let delete_list = if list.indices.is_null() {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(list.indices, list.cIndices as _) }.to_vec()
+        };
+
+        Self {
+            id: o.id,
+            cx: o.cx,
+            cy: o.cy,
+            delete_list,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SwitchSurface {
+    /// The offscreen bitmap to draw to, or 0xFFFF for the screen.
+    pub bitmap_id: u32,
+}
+
+impl From<&sys::SWITCH_SURFACE_ORDER> for SwitchSurface {
+    fn from(o: &sys::SWITCH_SURFACE_ORDER) -> Self {
+        Self {
+            bitmap_id: o.bitmapId,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NineGridInfo {
+    pub flags: u32,
+    pub left_width: u32,
+    pub right_width: u32,
+    pub top_height: u32,
+    pub bottom_height: u32,
+    pub transparent: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CreateNineGridBitmap {
+    pub bpp: u32,
+    pub bitmap_id: u32,
+    pub cx: u32,
+    pub cy: u32,
+    pub info: NineGridInfo,
+}
+
+impl From<&sys::CREATE_NINE_GRID_BITMAP_ORDER> for CreateNineGridBitmap {
+    fn from(o: &sys::CREATE_NINE_GRID_BITMAP_ORDER) -> Self {
+        let i = &o.nineGridInfo;
+
+        Self {
+            bpp: o.bitmapBpp,
+            bitmap_id: o.bitmapId,
+            cx: o.cx,
+            cy: o.cy,
+            info: NineGridInfo {
+                flags: i.flFlags,
+                left_width: i.ulLeftWidth,
+                right_width: i.ulRightWidth,
+                top_height: i.ulTopHeight,
+                bottom_height: i.ulBottomHeight,
+                transparent: i.crTransparent,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamBitmapFirst<'a> {
+    pub flags: u32,
+    pub bpp: u32,
+    pub type_: u32,
+    pub width: u32,
+    pub height: u32,
+    /// The total size of the bitmap, over all the blocks.
+    pub size: u32,
+    pub block: &'a [u8],
+}
+
+impl<'a> From<&'a sys::STREAM_BITMAP_FIRST_ORDER> for StreamBitmapFirst<'a> {
+    fn from(o: &'a sys::STREAM_BITMAP_FIRST_ORDER) -> Self {
+        Self {
+            flags: o.bitmapFlags,
+            bpp: o.bitmapBpp,
+            type_: o.bitmapType,
+            width: o.bitmapWidth,
+            height: o.bitmapHeight,
+            size: o.bitmapSize,
+            block: data(o.bitmapBlock, o.bitmapBlockSize),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamBitmapNext<'a> {
+    pub flags: u32,
+    pub type_: u32,
+    pub block: &'a [u8],
+}
+
+impl<'a> From<&'a sys::STREAM_BITMAP_NEXT_ORDER> for StreamBitmapNext<'a> {
+    fn from(o: &'a sys::STREAM_BITMAP_NEXT_ORDER) -> Self {
+        Self {
+            flags: o.bitmapFlags,
+            type_: o.bitmapType,
+            block: data(o.bitmapBlock, o.bitmapBlockSize),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DrawGdiPlusFirst<'a> {
+    pub total_size: u32,
+    pub total_emf_size: u32,
+    pub emf_records: &'a [u8],
+}
+
+impl<'a> From<&'a sys::DRAW_GDIPLUS_FIRST_ORDER> for DrawGdiPlusFirst<'a> {
+    fn from(o: &'a sys::DRAW_GDIPLUS_FIRST_ORDER) -> Self {
+        Self {
+            total_size: o.cbTotalSize,
+            total_emf_size: o.cbTotalEmfSize,
+            emf_records: data(o.emfRecords, o.cbSize),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DrawGdiPlusNext<'a> {
+    pub emf_records: &'a [u8],
+}
+
+impl<'a> From<&'a sys::DRAW_GDIPLUS_NEXT_ORDER> for DrawGdiPlusNext<'a> {
+    fn from(o: &'a sys::DRAW_GDIPLUS_NEXT_ORDER) -> Self {
+        Self {
+            emf_records: data(o.emfRecords, o.cbSize),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DrawGdiPlusEnd<'a> {
+    pub total_size: u32,
+    pub total_emf_size: u32,
+    pub emf_records: &'a [u8],
+}
+
+impl<'a> From<&'a sys::DRAW_GDIPLUS_END_ORDER> for DrawGdiPlusEnd<'a> {
+    fn from(o: &'a sys::DRAW_GDIPLUS_END_ORDER) -> Self {
+        Self {
+            total_size: o.cbTotalSize,
+            total_emf_size: o.cbTotalEmfSize,
+            emf_records: data(o.emfRecords, o.cbSize),
+        }
+    }
+}
+
+/// A cached GDI+ order. `total_size` isn't sent with the next orders.
+#[derive(Debug, Clone)]
+pub struct DrawGdiPlusCache<'a> {
+    pub flags: u32,
+    pub cache_type: u32,
+    pub cache_index: u32,
+    pub total_size: Option<u32>,
+    pub emf_records: &'a [u8],
+}
+
+impl<'a> From<&'a sys::DRAW_GDIPLUS_CACHE_FIRST_ORDER> for DrawGdiPlusCache<'a> {
+    fn from(o: &'a sys::DRAW_GDIPLUS_CACHE_FIRST_ORDER) -> Self {
+        Self {
+            flags: o.flags,
+            cache_type: o.cacheType,
+            cache_index: o.cacheIndex,
+            total_size: Some(o.cbTotalSize),
+            emf_records: data(o.emfRecords, o.cbSize),
+        }
+    }
+}
+
+impl<'a> From<&'a sys::DRAW_GDIPLUS_CACHE_NEXT_ORDER> for DrawGdiPlusCache<'a> {
+    fn from(o: &'a sys::DRAW_GDIPLUS_CACHE_NEXT_ORDER) -> Self {
+        Self {
+            flags: o.flags,
+            cache_type: o.cacheType,
+            cache_index: o.cacheIndex,
+            total_size: None,
+            emf_records: data(o.emfRecords, o.cbSize),
+        }
+    }
+}
+
+impl<'a> From<&'a sys::DRAW_GDIPLUS_CACHE_END_ORDER> for DrawGdiPlusCache<'a> {
+    fn from(o: &'a sys::DRAW_GDIPLUS_CACHE_END_ORDER) -> Self {
+        Self {
+            flags: o.flags,
+            cache_type: o.cacheType,
+            cache_index: o.cacheIndex,
+            total_size: Some(o.cbTotalSize),
+            emf_records: data(o.emfRecords, o.cbSize),
+        }
+    }
+}
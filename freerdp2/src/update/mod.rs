@@ -0,0 +1,696 @@
+//! The updates sent by the server, observed with the `register()` methods.
+//!
+//! # Chaining
+//!
+//! `register()` replaces the FreeRDP callbacks, which may already be set by
+//! `gdi_init()` to render the updates, or by the pointer cache. Those
+//! previous callbacks are kept, and called once the handler returns
+//! successfully, so that observing an update doesn't break rendering. An
+//! error from the handler rejects the update, without calling them.
+//!
+//! The begin/end paint, bounds, synchronize and desktop resize callbacks of
+//! [`UpdateHandler`] are not chained, they replace the previous ones.
+
+use std::{ffi::CStr, fmt::Debug, marker::PhantomData, ptr, slice, time::Instant};
+
+use bitflags::bitflags;
+
+use crate::{
+    client::{Context, Handler, RdpContext},
+    primary::PrimaryUpdate,
+    sys,
+    window::WindowUpdate,
+    RdpError, Rect, Result,
+};
+
+mod altsec;
+pub use altsec::*;
+mod pointer;
+pub use pointer::*;
+mod secondary;
+pub use secondary::*;
+
+// The previous callbacks, see the chaining section of the module docs.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct Chain {
+    bitmap_update: sys::pBitmapUpdate,
+    palette: sys::pPalette,
+    play_sound: sys::pPlaySound,
+    set_keyboard_indicators: sys::pSetKeyboardIndicators,
+    set_keyboard_ime_status: sys::pSetKeyboardImeStatus,
+    surface_bits: sys::pSurfaceBits,
+    surface_frame_marker: sys::pSurfaceFrameMarker,
+    save_session_info: sys::pSaveSessionInfo,
+    server_status_info: sys::pServerStatusInfo,
+}
+
+fn chain<H: Handler>(context: *mut sys::rdpContext) -> Chain {
+    RdpContext::<H>::from_ptr(context).update_chain
+}
+
+pub struct Update<'a> {
+    inner: ptr::NonNull<sys::rdpUpdate>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> Debug for Update<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Update")
+            // .field("y", &self.y())
+            .finish()
+    }
+}
+
+impl<'a> Update<'a> {
+    pub(crate) fn new(update: *mut sys::rdpUpdate) -> Self {
+        Self {
+            inner: ptr::NonNull::new(update).unwrap(),
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// Observe the updates, chained to the previous callbacks, see
+    /// [chaining](self#chaining).
+    pub fn register<H: UpdateHandler>(&mut self) {
+        let inner = unsafe { self.inner.as_mut() };
+
+        inner.BeginPaint = Some(rdp_update_begin_paint::<H>);
+        inner.EndPaint = Some(rdp_update_end_paint::<H>);
+        inner.SetBounds = Some(rdp_update_set_bounds::<H>);
+        inner.Synchronize = Some(rdp_update_synchronize::<H>);
+        inner.DesktopResize = Some(rdp_update_desktop_resize::<H>);
+
+        let chain = &mut RdpContext::<H::ContextHandler>::from_ptr(inner.context).update_chain;
+        chain.bitmap_update = inner.BitmapUpdate;
+        chain.palette = inner.Palette;
+        chain.play_sound = inner.PlaySound;
+        chain.set_keyboard_indicators = inner.SetKeyboardIndicators;
+        chain.set_keyboard_ime_status = inner.SetKeyboardImeStatus;
+        chain.surface_bits = inner.SurfaceBits;
+        chain.surface_frame_marker = inner.SurfaceFrameMarker;
+        chain.save_session_info = inner.SaveSessionInfo;
+        chain.server_status_info = inner.ServerStatusInfo;
+        inner.BitmapUpdate = Some(rdp_update_bitmap_update::<H>);
+        inner.Palette = Some(rdp_update_palette::<H>);
+        inner.PlaySound = Some(rdp_update_play_sound::<H>);
+        inner.SetKeyboardIndicators = Some(rdp_update_set_keyboard_indicators::<H>);
+        inner.SetKeyboardImeStatus = Some(rdp_update_set_keyboard_ime_status::<H>);
+        inner.SurfaceBits = Some(rdp_update_surface_bits::<H>);
+        inner.SurfaceFrameMarker = Some(rdp_update_surface_frame_marker::<H>);
+        inner.SaveSessionInfo = Some(rdp_update_save_session_info::<H>);
+        inner.ServerStatusInfo = Some(rdp_update_server_status_info::<H>);
+    }
+
+    /// The primary drawing orders.
+    pub fn primary(&self) -> Option<PrimaryUpdate<'a>> {
+        let primary = unsafe { self.inner.as_ref() }.primary;
+        if primary.is_null() {
+            None
+        } else {
+            Some(PrimaryUpdate::new(primary))
+        }
+    }
+
+    /// The pointer position and shape updates.
+    pub fn pointer(&self) -> Option<PointerUpdate<'a>> {
+        let pointer = unsafe { self.inner.as_ref() }.pointer;
+        if pointer.is_null() {
+            None
+        } else {
+            Some(PointerUpdate::new(pointer))
+        }
+    }
+
+    /// The secondary orders, filling the bitmap, glyph and brush caches.
+    pub fn secondary(&self) -> Option<SecondaryUpdate<'a>> {
+        let secondary = unsafe { self.inner.as_ref() }.secondary;
+        if secondary.is_null() {
+            None
+        } else {
+            Some(SecondaryUpdate::new(secondary))
+        }
+    }
+
+    /// The alternate secondary orders: offscreen surfaces, frame markers
+    /// and GDI+.
+    pub fn altsec(&self) -> Option<AltSecUpdate<'a>> {
+        let altsec = unsafe { self.inner.as_ref() }.altsec;
+        if altsec.is_null() {
+            None
+        } else {
+            Some(AltSecUpdate::new(altsec))
+        }
+    }
+
+    /// The alternate secondary window orders, used by RemoteApp.
+    pub fn window(&self) -> Option<WindowUpdate<'a>> {
+        let window = unsafe { self.inner.as_ref() }.window;
+        if window.is_null() {
+            None
+        } else {
+            Some(WindowUpdate::new(window))
+        }
+    }
+}
+
+pub trait UpdateHandler {
+    type ContextHandler: Handler;
+
+    fn begin_paint(_context: &mut Context<Self::ContextHandler>) -> Result<()> {
+        Ok(())
+    }
+
+    fn end_paint(_context: &mut Context<Self::ContextHandler>) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_bounds(_context: &mut Context<Self::ContextHandler>, _bounds: &Bounds) -> Result<()> {
+        Ok(())
+    }
+
+    fn synchronize(_context: &mut Context<Self::ContextHandler>) -> Result<()> {
+        Ok(())
+    }
+
+    fn desktop_resize(_context: &mut Context<Self::ContextHandler>) -> Result<()> {
+        Ok(())
+    }
+
+    fn bitmap_update(
+        _context: &mut Context<Self::ContextHandler>,
+        _bitmaps: &[BitmapData],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn palette(
+        _context: &mut Context<Self::ContextHandler>,
+        _palette: &[PaletteEntry],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn play_sound(_context: &mut Context<Self::ContextHandler>, _sound: &PlaySound) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_keyboard_indicators(
+        _context: &mut Context<Self::ContextHandler>,
+        _indicators: KeyboardIndicators,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_keyboard_ime_status(
+        _context: &mut Context<Self::ContextHandler>,
+        _ime_id: u16,
+        _ime_state: u32,
+        _ime_conv_mode: u32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn surface_bits(
+        _context: &mut Context<Self::ContextHandler>,
+        _bits: &SurfaceBits,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn surface_frame_marker(
+        _context: &mut Context<Self::ContextHandler>,
+        _marker: &FrameMarker,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn save_session_info(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &SessionInfo,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn server_status_info(
+        _context: &mut Context<Self::ContextHandler>,
+        _status: u32,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+extern "C" fn rdp_update_begin_paint<H: UpdateHandler>(context: *mut sys::rdpContext) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+
+    H::begin_paint(context).is_ok() as _
+}
+
+extern "C" fn rdp_update_end_paint<H: UpdateHandler>(context: *mut sys::rdpContext) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+
+    H::end_paint(context).is_ok() as _
+}
+
+extern "C" fn rdp_update_set_bounds<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    bounds: *const sys::rdpBounds,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let bounds = unsafe { bounds.as_ref() }.unwrap();
+
+    H::set_bounds(context, &bounds.into()).is_ok() as _
+}
+
+extern "C" fn rdp_update_synchronize<H: UpdateHandler>(context: *mut sys::rdpContext) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+
+    H::synchronize(context).is_ok() as _
+}
+
+extern "C" fn rdp_update_desktop_resize<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+
+    H::desktop_resize(context).is_ok() as _
+}
+
+extern "C" fn rdp_update_bitmap_update<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    bitmap: *const sys::BITMAP_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let update = unsafe { bitmap.as_ref() }.unwrap();
+    let rects = if update.rectangles.is_null() {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(update.rectangles, update.number as _) }
+    };
+    let bitmaps: Vec<_> = rects.iter().map(BitmapData::from).collect();
+
+    if H::bitmap_update(ctxt, &bitmaps).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).bitmap_update {
+        Some(f) => unsafe { f(context, bitmap) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_update_palette<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    palette: *const sys::PALETTE_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let update = unsafe { palette.as_ref() }.unwrap();
+    let len = update.entries.len().min(update.number as _);
+    let entries: Vec<_> = update.entries[..len]
+        .iter()
+        .map(|e| PaletteEntry {
+            red: e.red,
+            green: e.green,
+            blue: e.blue,
+        })
+        .collect();
+
+    if H::palette(ctxt, &entries).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).palette {
+        Some(f) => unsafe { f(context, palette) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_update_play_sound<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    play_sound: *const sys::PLAY_SOUND_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let sound = unsafe { play_sound.as_ref() }.unwrap();
+    let sound = PlaySound {
+        duration: sound.duration,
+        frequency: sound.frequency,
+    };
+
+    if H::play_sound(ctxt, &sound).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).play_sound {
+        Some(f) => unsafe { f(context, play_sound) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_update_set_keyboard_indicators<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    led_flags: sys::UINT16,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let indicators = KeyboardIndicators::from_bits_retain(led_flags);
+
+    if H::set_keyboard_indicators(ctxt, indicators).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).set_keyboard_indicators {
+        Some(f) => unsafe { f(context, led_flags) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_update_set_keyboard_ime_status<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    ime_id: sys::UINT16,
+    ime_state: sys::UINT32,
+    ime_conv_mode: sys::UINT32,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+
+    if H::set_keyboard_ime_status(ctxt, ime_id, ime_state, ime_conv_mode).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).set_keyboard_ime_status {
+        Some(f) => unsafe { f(context, ime_id, ime_state, ime_conv_mode) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_update_surface_bits<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    cmd: *const sys::SURFACE_BITS_COMMAND,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let bits = SurfaceBits::from(unsafe { cmd.as_ref() }.unwrap());
+
+    if H::surface_bits(ctxt, &bits).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).surface_bits {
+        Some(f) => unsafe { f(context, cmd) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_update_surface_frame_marker<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    marker: *const sys::SURFACE_FRAME_MARKER,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let m = unsafe { marker.as_ref() }.unwrap();
+    let action = match m.frameAction.try_into() {
+        Ok(action) => action,
+        Err(_) => return 0,
+    };
+    let frame_marker = FrameMarker {
+        action,
+        frame_id: m.frameId,
+    };
+
+    if H::surface_frame_marker(ctxt, &frame_marker).is_err() {
+        return 0;
+    }
+    if frame_marker.action == FrameAction::End {
+        if let Some(throttle) = ctxt.frame_throttle.as_mut() {
+            throttle.end_frame(Instant::now());
+        }
+    }
+    match chain::<H::ContextHandler>(context).surface_frame_marker {
+        Some(f) => unsafe { f(context, marker) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_update_save_session_info<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    type_: sys::UINT32,
+    data: *mut ::std::os::raw::c_void,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let info = unsafe { SessionInfo::new(type_, data) };
+
+    if H::save_session_info(ctxt, &info).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).save_session_info {
+        Some(f) => unsafe { f(context, type_, data) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_update_server_status_info<H: UpdateHandler>(
+    context: *mut sys::rdpContext,
+    status: sys::UINT32,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+
+    if H::server_status_info(ctxt, status).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).server_status_info {
+        Some(f) => unsafe { f(context, status) },
+        None => 1,
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Bounds {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl From<&sys::rdpBounds> for Bounds {
+    fn from(bounds: &sys::rdpBounds) -> Self {
+        Self {
+            left: bounds.left,
+            top: bounds.top,
+            right: bounds.right,
+            bottom: bounds.bottom,
+        }
+    }
+}
+
+/// A rectangle of a bitmap update, `data` is compressed with interleaved
+/// RLE or planar depending on `bpp`, if `compressed` is set.
+#[derive(Debug, Clone)]
+pub struct BitmapData<'a> {
+    pub dest: Rect,
+    pub width: u32,
+    pub height: u32,
+    pub bpp: u32,
+    pub flags: u32,
+    pub compressed: bool,
+    pub data: &'a [u8],
+}
+
+fn dest_rect(left: u32, top: u32, right: u32, bottom: u32) -> Rect {
+    let clamp = |v: u32| v.min(u16::MAX.into()) as u16;
+
+    Rect {
+        left: clamp(left),
+        top: clamp(top),
+        right: clamp(right),
+        bottom: clamp(bottom),
+    }
+}
+
+fn data<'a>(data: *const u8, len: u32) -> &'a [u8] {
+    if data.is_null() {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(data, len as _) }
+    }
+}
+
+impl<'a> From<&'a sys::BITMAP_DATA> for BitmapData<'a> {
+    fn from(b: &'a sys::BITMAP_DATA) -> Self {
+        Self {
+            // destRight/destBottom are inclusive
+            dest: dest_rect(
+                b.destLeft,
+                b.destTop,
+                b.destRight.saturating_add(1),
+                b.destBottom.saturating_add(1),
+            ),
+            width: b.width,
+            height: b.height,
+            bpp: b.bitsPerPixel,
+            flags: b.flags,
+            compressed: b.compressed != 0,
+            data: data(b.bitmapDataStream, b.bitmapLength),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PaletteEntry {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PlaySound {
+    pub duration: u32,
+    pub frequency: u32,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct KeyboardIndicators: u16 {
+        const SCROLL_LOCK = 0x0001;
+        const NUM_LOCK = 0x0002;
+        const CAPS_LOCK = 0x0004;
+        const KANA_LOCK = 0x0008;
+    }
+}
+
+/// The codec IDs FreeRDP uses in surface bits, negotiated with the bitmap
+/// codecs capability.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CodecId {
+    None = 0x00,
+    NsCodec = 0x01,
+    Jpeg = 0x02,
+    RemoteFx = 0x03,
+    ImageRemoteFx = 0x04,
+}
+
+impl TryFrom<u16> for CodecId {
+    type Error = RdpError;
+
+    fn try_from(value: u16) -> Result<Self> {
+        match value {
+            v if v == CodecId::None as u16 => Ok(CodecId::None),
+            v if v == CodecId::NsCodec as u16 => Ok(CodecId::NsCodec),
+            v if v == CodecId::Jpeg as u16 => Ok(CodecId::Jpeg),
+            v if v == CodecId::RemoteFx as u16 => Ok(CodecId::RemoteFx),
+            v if v == CodecId::ImageRemoteFx as u16 => Ok(CodecId::ImageRemoteFx),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SurfaceBits<'a> {
+    pub cmd_type: u32,
+    pub dest: Rect,
+    pub bpp: u8,
+    pub flags: u8,
+    /// The codec, or its raw ID if unknown.
+    pub codec: std::result::Result<CodecId, u16>,
+    pub width: u16,
+    pub height: u16,
+    pub data: &'a [u8],
+}
+
+impl<'a> From<&'a sys::SURFACE_BITS_COMMAND> for SurfaceBits<'a> {
+    fn from(cmd: &'a sys::SURFACE_BITS_COMMAND) -> Self {
+        Self {
+            cmd_type: cmd.cmdType,
+            dest: dest_rect(cmd.destLeft, cmd.destTop, cmd.destRight, cmd.destBottom),
+            bpp: cmd.bmp.bpp,
+            flags: cmd.bmp.flags,
+            codec: cmd.bmp.codecID.try_into().map_err(|_| cmd.bmp.codecID),
+            width: cmd.bmp.width,
+            height: cmd.bmp.height,
+            data: data(cmd.bmp.bitmapData, cmd.bmp.bitmapDataLength),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum FrameAction {
+    Begin = 0x0000,
+    End = 0x0001,
+}
+
+impl TryFrom<u32> for FrameAction {
+    type Error = RdpError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            v if v == FrameAction::Begin as u32 => Ok(FrameAction::Begin),
+            v if v == FrameAction::End as u32 => Ok(FrameAction::End),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FrameMarker {
+    pub action: FrameAction,
+    pub frame_id: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionInfo {
+    Logon {
+        session_id: u32,
+        username: Option<String>,
+        domain: Option<String>,
+    },
+    PlainNotify,
+    Extended {
+        logon_id: Option<u32>,
+        /// The error notification type and data.
+        error: Option<(u32, u32)>,
+    },
+    Unknown(u32),
+}
+
+fn opt_string(s: *const std::os::raw::c_char) -> Option<String> {
+    if s.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(s) }.to_string_lossy().into())
+    }
+}
+
+impl SessionInfo {
+    unsafe fn new(type_: u32, data: *const std::os::raw::c_void) -> Self {
+        match type_ {
+            sys::INFO_TYPE_LOGON | sys::INFO_TYPE_LOGON_LONG => {
+                match (data as *const sys::logon_info).as_ref() {
+                    Some(info) => Self::Logon {
+                        session_id: info.sessionId,
+                        username: opt_string(info.username),
+                        domain: opt_string(info.domain),
+                    },
+                    None => Self::Unknown(type_),
+                }
+            }
+            sys::INFO_TYPE_LOGON_PLAIN_NOTIFY => Self::PlainNotify,
+            sys::INFO_TYPE_LOGON_EXTENDED_INF => {
+                match (data as *const sys::logon_info_ex).as_ref() {
+                    Some(info) => Self::Extended {
+                        logon_id: (info.haveCookie != 0).then_some(info.LogonId),
+                        error: (info.haveErrors != 0)
+                            .then_some((info.ErrorNotificationType, info.ErrorNotificationData)),
+                    },
+                    None => Self::Unknown(type_),
+                }
+            }
+            _ => Self::Unknown(type_),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitmap_data_dest() {
+        let mut b: sys::BITMAP_DATA = unsafe { std::mem::zeroed() };
+        b.destLeft = 10;
+        b.destTop = 20;
+        b.destRight = 19;
+        b.destBottom = u32::MAX;
+
+        let data = BitmapData::from(&b);
+        assert_eq!(data.dest, Rect::new(10, 20, 10, u16::MAX - 20));
+    }
+}
@@ -0,0 +1,307 @@
+use std::{fmt::Debug, marker::PhantomData, ptr, sync::Arc};
+
+use crate::{
+    client::{Context, Handler, RdpContext},
+    sys, Cursor, RdpError, Result,
+};
+
+// The previous callbacks, see the chaining section of the module docs.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct PointerChain {
+    pointer_position: sys::pPointerPosition,
+    pointer_system: sys::pPointerSystem,
+    pointer_color: sys::pPointerColor,
+    pointer_new: sys::pPointerNew,
+    pointer_cached: sys::pPointerCached,
+    pointer_large: sys::pPointerLarge,
+}
+
+fn chain<H: Handler>(context: *mut sys::rdpContext) -> PointerChain {
+    RdpContext::<H>::from_ptr(context).pointer_chain
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SystemPointer {
+    /// The pointer is hidden.
+    Null = 0x0000_0000,
+    /// The default operating system pointer.
+    Default = 0x0000_7F00,
+}
+
+impl TryFrom<u32> for SystemPointer {
+    type Error = RdpError;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            v if v == SystemPointer::Null as u32 => Ok(SystemPointer::Null),
+            v if v == SystemPointer::Default as u32 => Ok(SystemPointer::Default),
+            _ => Err(RdpError::Unsupported),
+        }
+    }
+}
+
+pub struct PointerUpdate<'a> {
+    inner: ptr::NonNull<sys::rdpPointerUpdate>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> Debug for PointerUpdate<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PointerUpdate").finish()
+    }
+}
+
+impl<'a> PointerUpdate<'a> {
+    pub(crate) fn new(pointer: *mut sys::rdpPointerUpdate) -> Self {
+        Self {
+            inner: ptr::NonNull::new(pointer).unwrap(),
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// Observe the pointer updates, chained to the previous callbacks, see
+    /// [chaining](super#chaining). New pointer shapes are decoded to RGBA and
+    /// kept in `Context::cursors`.
+    pub fn register<H: PointerUpdateHandler>(&mut self) {
+        let inner = unsafe { self.inner.as_mut() };
+
+        let chain = &mut RdpContext::<H::ContextHandler>::from_ptr(inner.context).pointer_chain;
+        chain.pointer_position = inner.PointerPosition;
+        chain.pointer_system = inner.PointerSystem;
+        chain.pointer_color = inner.PointerColor;
+        chain.pointer_new = inner.PointerNew;
+        chain.pointer_cached = inner.PointerCached;
+        chain.pointer_large = inner.PointerLarge;
+
+        inner.PointerPosition = Some(rdp_pointer_position::<H>);
+        inner.PointerSystem = Some(rdp_pointer_system::<H>);
+        inner.PointerColor = Some(rdp_pointer_color::<H>);
+        inner.PointerNew = Some(rdp_pointer_new::<H>);
+        inner.PointerCached = Some(rdp_pointer_cached::<H>);
+        inner.PointerLarge = Some(rdp_pointer_large::<H>);
+    }
+}
+
+pub trait PointerUpdateHandler {
+    type ContextHandler: Handler;
+
+    fn pointer_position(
+        _context: &mut Context<Self::ContextHandler>,
+        _x: u32,
+        _y: u32,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn pointer_system(
+        _context: &mut Context<Self::ContextHandler>,
+        _pointer: SystemPointer,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// The pointer shape changed, to a new or a cached cursor.
+    fn pointer_set(
+        _context: &mut Context<Self::ContextHandler>,
+        _cursor: Arc<Cursor>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// A pointer failed to decode, or isn't in the cache. The update is still
+    /// chained, unless an error is returned.
+    fn pointer_error(_context: &mut Context<Self::ContextHandler>, _error: RdpError) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn cache_cursor<H: Handler>(
+    context: &mut Context<H>,
+    index: u32,
+    cursor: Result<Cursor>,
+) -> Result<Arc<Cursor>> {
+    match cursor {
+        Ok(cursor) => Ok(context.cursors.insert(index, cursor)),
+        Err(e) => {
+            // don't show the previous cursor at this index later
+            context.cursors.remove(index);
+            Err(e)
+        }
+    }
+}
+
+// A pointer that fails to decode, or a missing cache entry, is given to
+// pointer_error() instead of pointer_set(). Returns whether the cursor was
+// set, or None if the handler failed.
+fn set_cursor<H: PointerUpdateHandler>(
+    context: &mut Context<H::ContextHandler>,
+    cursor: Result<Arc<Cursor>>,
+) -> Option<bool> {
+    match cursor {
+        Ok(cursor) => H::pointer_set(context, cursor).ok().map(|_| true),
+        Err(e) => H::pointer_error(context, e).ok().map(|_| false),
+    }
+}
+
+fn decode_color<H: Handler>(
+    context: &Context<H>,
+    xor_bpp: u32,
+    color: &sys::POINTER_COLOR_UPDATE,
+) -> Result<Cursor> {
+    let gdi = context.gdi();
+    Cursor::decode(
+        color.width,
+        color.height,
+        (color.xPos, color.yPos),
+        xor_bpp,
+        color.xorMaskData,
+        color.lengthXorMask,
+        color.andMaskData,
+        color.lengthAndMask,
+        gdi.as_ref().map(|gdi| gdi.palette()),
+    )
+}
+
+extern "C" fn rdp_pointer_position<H: PointerUpdateHandler>(
+    context: *mut sys::rdpContext,
+    position: *const sys::POINTER_POSITION_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let p = unsafe { position.as_ref() }.unwrap();
+
+    if H::pointer_position(ctxt, p.xPos, p.yPos).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).pointer_position {
+        Some(f) => unsafe { f(context, position) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_pointer_system<H: PointerUpdateHandler>(
+    context: *mut sys::rdpContext,
+    system: *const sys::POINTER_SYSTEM_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let pointer = match unsafe { system.as_ref() }.unwrap().type_.try_into() {
+        Ok(pointer) => pointer,
+        Err(_) => return 0,
+    };
+
+    if H::pointer_system(ctxt, pointer).is_err() {
+        return 0;
+    }
+    match chain::<H::ContextHandler>(context).pointer_system {
+        Some(f) => unsafe { f(context, system) },
+        None => 1,
+    }
+}
+
+extern "C" fn rdp_pointer_color<H: PointerUpdateHandler>(
+    context: *mut sys::rdpContext,
+    color: *const sys::POINTER_COLOR_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let c = unsafe { color.as_ref() }.unwrap();
+    let cursor = decode_color(ctxt, 24, c);
+
+    let cursor = cache_cursor(ctxt, c.cacheIndex, cursor);
+    let set = match set_cursor::<H>(ctxt, cursor) {
+        Some(set) => set,
+        None => return 0,
+    };
+    let res = match chain::<H::ContextHandler>(context).pointer_color {
+        Some(f) => unsafe { f(context, color) },
+        None => 1,
+    };
+    if set {
+        res
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_pointer_new<H: PointerUpdateHandler>(
+    context: *mut sys::rdpContext,
+    new: *const sys::POINTER_NEW_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let n = unsafe { new.as_ref() }.unwrap();
+    let cursor = decode_color(ctxt, n.xorBpp, &n.colorPtrAttr);
+
+    let cursor = cache_cursor(ctxt, n.colorPtrAttr.cacheIndex, cursor);
+    let set = match set_cursor::<H>(ctxt, cursor) {
+        Some(set) => set,
+        None => return 0,
+    };
+    let res = match chain::<H::ContextHandler>(context).pointer_new {
+        Some(f) => unsafe { f(context, new) },
+        None => 1,
+    };
+    if set {
+        res
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_pointer_large<H: PointerUpdateHandler>(
+    context: *mut sys::rdpContext,
+    large: *const sys::POINTER_LARGE_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let l = unsafe { large.as_ref() }.unwrap();
+    let gdi = ctxt.gdi();
+    let cursor = Cursor::decode(
+        l.width.into(),
+        l.height.into(),
+        (l.hotSpotX.into(), l.hotSpotY.into()),
+        l.xorBpp.into(),
+        l.xorMaskData,
+        l.lengthXorMask,
+        l.andMaskData,
+        l.lengthAndMask,
+        gdi.as_ref().map(|gdi| gdi.palette()),
+    );
+
+    let cursor = cache_cursor(ctxt, l.cacheIndex.into(), cursor);
+    let set = match set_cursor::<H>(ctxt, cursor) {
+        Some(set) => set,
+        None => return 0,
+    };
+    let res = match chain::<H::ContextHandler>(context).pointer_large {
+        Some(f) => unsafe { f(context, large) },
+        None => 1,
+    };
+    if set {
+        res
+    } else {
+        1
+    }
+}
+
+extern "C" fn rdp_pointer_cached<H: PointerUpdateHandler>(
+    context: *mut sys::rdpContext,
+    cached: *const sys::POINTER_CACHED_UPDATE,
+) -> sys::BOOL {
+    let ctxt = Context::from_ptr(context);
+    let index = unsafe { cached.as_ref() }.unwrap().cacheIndex;
+
+    let cursor = ctxt
+        .cursors
+        .get(index)
+        .ok_or_else(|| RdpError::Failed(format!("No cached pointer at index {}", index)));
+    let set = match set_cursor::<H>(ctxt, cursor) {
+        Some(set) => set,
+        None => return 0,
+    };
+    let res = match chain::<H::ContextHandler>(context).pointer_cached {
+        Some(f) => unsafe { f(context, cached) },
+        None => 1,
+    };
+    if set {
+        res
+    } else {
+        1
+    }
+}
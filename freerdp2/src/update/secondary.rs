@@ -0,0 +1,378 @@
+use std::{fmt::Debug, marker::PhantomData, ptr};
+
+use crate::{
+    client::{Context, Handler, RdpContext},
+    primary::GlyphData,
+    sys,
+    update::CodecId,
+    Result,
+};
+
+use super::data;
+
+// The previous callbacks, see the chaining section of the module docs.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct SecondaryChain {
+    cache_bitmap: sys::pCacheBitmap,
+    cache_bitmap_v2: sys::pCacheBitmapV2,
+    cache_bitmap_v3: sys::pCacheBitmapV3,
+    cache_color_table: sys::pCacheColorTable,
+    cache_glyph: sys::pCacheGlyph,
+    cache_glyph_v2: sys::pCacheGlyphV2,
+    cache_brush: sys::pCacheBrush,
+}
+
+pub struct SecondaryUpdate<'a> {
+    inner: ptr::NonNull<sys::rdpSecondaryUpdate>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> Debug for SecondaryUpdate<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecondaryUpdate").finish()
+    }
+}
+
+impl<'a> SecondaryUpdate<'a> {
+    pub(crate) fn new(secondary: *mut sys::rdpSecondaryUpdate) -> Self {
+        Self {
+            inner: ptr::NonNull::new(secondary).unwrap(),
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// Observe the cache orders, chained to the previous callbacks, see
+    /// [chaining](super#chaining).
+    pub fn register<H: SecondaryUpdateHandler>(&mut self) {
+        let inner = unsafe { self.inner.as_mut() };
+
+        let chain = &mut RdpContext::<H::ContextHandler>::from_ptr(inner.context).secondary_chain;
+        chain.cache_bitmap = inner.CacheBitmap;
+        chain.cache_bitmap_v2 = inner.CacheBitmapV2;
+        chain.cache_bitmap_v3 = inner.CacheBitmapV3;
+        chain.cache_color_table = inner.CacheColorTable;
+        chain.cache_glyph = inner.CacheGlyph;
+        chain.cache_glyph_v2 = inner.CacheGlyphV2;
+        chain.cache_brush = inner.CacheBrush;
+
+        inner.CacheBitmap = Some(rdp_secondary_cache_bitmap::<H>);
+        inner.CacheBitmapV2 = Some(rdp_secondary_cache_bitmap_v2::<H>);
+        inner.CacheBitmapV3 = Some(rdp_secondary_cache_bitmap_v3::<H>);
+        inner.CacheColorTable = Some(rdp_secondary_cache_color_table::<H>);
+        inner.CacheGlyph = Some(rdp_secondary_cache_glyph::<H>);
+        inner.CacheGlyphV2 = Some(rdp_secondary_cache_glyph_v2::<H>);
+        inner.CacheBrush = Some(rdp_secondary_cache_brush::<H>);
+    }
+}
+
+pub trait SecondaryUpdateHandler {
+    type ContextHandler: Handler;
+
+    fn cache_bitmap(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CacheBitmap,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn cache_bitmap_v2(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CacheBitmapV2,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn cache_bitmap_v3(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CacheBitmapV3,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn cache_color_table(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CacheColorTable,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn cache_glyph(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CacheGlyph,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn cache_glyph_v2(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CacheGlyphV2,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn cache_brush(
+        _context: &mut Context<Self::ContextHandler>,
+        _order: &CacheBrush,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+macro_rules! callback {
+    ($name:ident, $method:ident, $sys:ty, $order:ty) => {
+        extern "C" fn $name<H: SecondaryUpdateHandler>(
+            context: *mut sys::rdpContext,
+            order: $sys,
+        ) -> sys::BOOL {
+            let ctxt = Context::from_ptr(context);
+            let o = <$order>::from(unsafe { order.as_ref() }.unwrap());
+
+            if H::$method(ctxt, &o).is_err() {
+                return 0;
+            }
+            match RdpContext::<H::ContextHandler>::from_ptr(context)
+                .secondary_chain
+                .$method
+            {
+                Some(f) => unsafe { f(context, order) },
+                None => 1,
+            }
+        }
+    };
+}
+
+callback!(
+    rdp_secondary_cache_bitmap,
+    cache_bitmap,
+    *const sys::CACHE_BITMAP_ORDER,
+    CacheBitmap
+);
+callback!(
+    rdp_secondary_cache_bitmap_v2,
+    cache_bitmap_v2,
+    *mut sys::CACHE_BITMAP_V2_ORDER,
+    CacheBitmapV2
+);
+callback!(
+    rdp_secondary_cache_bitmap_v3,
+    cache_bitmap_v3,
+    *mut sys::CACHE_BITMAP_V3_ORDER,
+    CacheBitmapV3
+);
+callback!(
+    rdp_secondary_cache_color_table,
+    cache_color_table,
+    *const sys::CACHE_COLOR_TABLE_ORDER,
+    CacheColorTable
+);
+callback!(
+    rdp_secondary_cache_glyph,
+    cache_glyph,
+    *const sys::CACHE_GLYPH_ORDER,
+    CacheGlyph
+);
+callback!(
+    rdp_secondary_cache_glyph_v2,
+    cache_glyph_v2,
+    *const sys::CACHE_GLYPH_V2_ORDER,
+    CacheGlyphV2
+);
+callback!(
+    rdp_secondary_cache_brush,
+    cache_brush,
+    *const sys::CACHE_BRUSH_ORDER,
+    CacheBrush
+);
+
+/// A bitmap to store in the bitmap cache, `data` is interleaved RLE
+/// compressed if `compressed` is set.
+#[derive(Debug, Clone)]
+pub struct CacheBitmap<'a> {
+    pub cache_id: u32,
+    pub cache_index: u32,
+    pub bpp: u32,
+    pub width: u32,
+    pub height: u32,
+    pub compressed: bool,
+    pub data: &'a [u8],
+}
+
+impl<'a> From<&'a sys::CACHE_BITMAP_ORDER> for CacheBitmap<'a> {
+    fn from(o: &'a sys::CACHE_BITMAP_ORDER) -> Self {
+        Self {
+            cache_id: o.cacheId,
+            cache_index: o.cacheIndex,
+            bpp: o.bitmapBpp,
+            width: o.bitmapWidth,
+            height: o.bitmapHeight,
+            compressed: o.compressed != 0,
+            data: data(o.bitmapDataStream, o.bitmapLength),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheBitmapV2<'a> {
+    pub cache_id: u32,
+    pub cache_index: u32,
+    pub flags: u32,
+    /// The persistent key, if the bitmap is to be kept across sessions.
+    pub key1: u32,
+    pub key2: u32,
+    pub bpp: u32,
+    pub width: u32,
+    pub height: u32,
+    pub compressed: bool,
+    pub data: &'a [u8],
+}
+
+impl<'a> From<&'a sys::CACHE_BITMAP_V2_ORDER> for CacheBitmapV2<'a> {
+    fn from(o: &'a sys::CACHE_BITMAP_V2_ORDER) -> Self {
+        Self {
+            cache_id: o.cacheId,
+            cache_index: o.cacheIndex,
+            flags: o.flags,
+            key1: o.key1,
+            key2: o.key2,
+            bpp: o.bitmapBpp,
+            width: o.bitmapWidth,
+            height: o.bitmapHeight,
+            compressed: o.compressed != 0,
+            data: data(o.bitmapDataStream, o.bitmapLength),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheBitmapV3<'a> {
+    pub cache_id: u32,
+    pub cache_index: u32,
+    pub flags: u32,
+    pub key1: u32,
+    pub key2: u32,
+    pub bpp: u32,
+    /// The codec, or its raw ID if unknown.
+    pub codec: std::result::Result<CodecId, u32>,
+    pub width: u32,
+    pub height: u32,
+    pub data: &'a [u8],
+}
+
+impl<'a> From<&'a sys::CACHE_BITMAP_V3_ORDER> for CacheBitmapV3<'a> {
+    fn from(o: &'a sys::CACHE_BITMAP_V3_ORDER) -> Self {
+        let b = &o.bitmapData;
+        let codec = u16::try_from(b.codecID)
+            .ok()
+            .and_then(|id| CodecId::try_from(id).ok())
+            .ok_or(b.codecID);
+
+        Self {
+            cache_id: o.cacheId,
+            cache_index: o.cacheIndex,
+            flags: o.flags,
+            key1: o.key1,
+            key2: o.key2,
+            bpp: b.bpp,
+            codec,
+            width: b.width,
+            height: b.height,
+            data: data(b.data, b.length),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheColorTable {
+    pub cache_index: u32,
+    pub colors: Vec<u32>,
+}
+
+impl From<&sys::CACHE_COLOR_TABLE_ORDER> for CacheColorTable {
+    fn from(o: &sys::CACHE_COLOR_TABLE_ORDER) -> Self {
+        let len = o.numberColors.min(o.colorTable.len() as _) as usize;
+
+        Self {
+            cache_index: o.cacheIndex,
+            colors: o.colorTable[..len].to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheGlyph {
+    pub cache_id: u32,
+    pub glyphs: Vec<GlyphData>,
+}
+
+impl From<&sys::CACHE_GLYPH_ORDER> for CacheGlyph {
+    fn from(o: &sys::CACHE_GLYPH_ORDER) -> Self {
+        let len = o.cGlyphs.min(o.glyphData.len() as _) as usize;
+
+        Self {
+            cache_id: o.cacheId,
+            glyphs: o.glyphData[..len].iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheGlyphV2 {
+    pub cache_id: u32,
+    pub flags: u32,
+    pub glyphs: Vec<GlyphData>,
+}
+
+impl From<&sys::CACHE_GLYPH_V2_ORDER> for CacheGlyphV2 {
+    fn from(o: &sys::CACHE_GLYPH_V2_ORDER) -> Self {
+        let len = o.cGlyphs.min(o.glyphData.len() as _) as usize;
+
+        Self {
+            cache_id: o.cacheId,
+            flags: o.flags,
+            glyphs: o.glyphData[..len].iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheBrush {
+    pub index: u32,
+    pub bpp: u32,
+    pub cx: u32,
+    pub cy: u32,
+    pub style: u32,
+    pub data: Vec<u8>,
+}
+
+impl From<&sys::CACHE_BRUSH_ORDER> for CacheBrush {
+    fn from(o: &sys::CACHE_BRUSH_ORDER) -> Self {
+        let len = o.length.min(o.data.len() as _) as usize;
+
+        Self {
+            index: o.index,
+            bpp: o.bpp,
+            cx: o.cx,
+            cy: o.cy,
+            style: o.style,
+            data: o.data[..len].to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_color_table() {
+        let mut order: sys::CACHE_COLOR_TABLE_ORDER = unsafe { std::mem::zeroed() };
+        order.cacheIndex = 2;
+        order.numberColors = 1000;
+        order.colorTable[255] = 0xff00ff;
+
+        let table = CacheColorTable::from(&order);
+        assert_eq!(table.cache_index, 2);
+        assert_eq!(table.colors.len(), 256);
+        assert_eq!(table.colors[255], 0xff00ff);
+    }
+}
@@ -0,0 +1,623 @@
+use std::{fmt::Debug, marker::PhantomData, ptr, slice};
+
+use bitflags::{bitflags, Flags};
+
+use crate::{
+    channels::rail::unicode_string,
+    client::{Context, Handler},
+    sys, RdpError, Rect, Result, PIXEL_FORMAT_RGBA32,
+};
+
+// The bits common to all the alternate secondary window orders, the field
+// bits depend on the order.
+macro_rules! order_flags {
+    ($(#[$attr:meta])* $name:ident { $($field:ident = $value:expr;)* }) => {
+        bitflags! {
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name: u32 {
+                const TYPE_WINDOW = 0x01000000;
+                const TYPE_NOTIFY = 0x02000000;
+                const TYPE_DESKTOP = 0x04000000;
+                const STATE_NEW = 0x10000000;
+                const STATE_DELETED = 0x20000000;
+                const ICON = 0x40000000;
+                const CACHED_ICON = 0x80000000;
+
+                $(const $field = $value;)*
+            }
+        }
+    };
+}
+
+order_flags! {
+    /// The `fieldFlags` of a window order.
+    WindowOrderFlags {
+        FIELD_APPBAR_EDGE = 0x00000001;
+        FIELD_OWNER = 0x00000002;
+        FIELD_TITLE = 0x00000004;
+        FIELD_STYLE = 0x00000008;
+        FIELD_SHOW = 0x00000010;
+        FIELD_APPBAR_STATE = 0x00000040;
+        FIELD_RESIZE_MARGIN_X = 0x00000080;
+        FIELD_WND_RECTS = 0x00000100;
+        FIELD_VISIBILITY = 0x00000200;
+        FIELD_WND_SIZE = 0x00000400;
+        FIELD_WND_OFFSET = 0x00000800;
+        FIELD_VIS_OFFSET = 0x00001000;
+        FIELD_ICON_BIG = 0x00002000;
+        FIELD_CLIENT_AREA_OFFSET = 0x00004000;
+        FIELD_WND_CLIENT_DELTA = 0x00008000;
+        FIELD_CLIENT_AREA_SIZE = 0x00010000;
+        FIELD_RP_CONTENT = 0x00020000;
+        FIELD_ROOT_PARENT = 0x00040000;
+        FIELD_ENFORCE_SERVER_ZORDER = 0x00080000;
+        FIELD_ICON_OVERLAY = 0x00100000;
+        FIELD_ICON_OVERLAY_NULL = 0x00200000;
+        FIELD_OVERLAY_DESCRIPTION = 0x00400000;
+        FIELD_TASKBAR_BUTTON = 0x00800000;
+        FIELD_RESIZE_MARGIN_Y = 0x08000000;
+    }
+}
+
+order_flags! {
+    /// The `fieldFlags` of a notification icon order.
+    NotifyIconOrderFlags {
+        FIELD_TIP = 0x00000001;
+        FIELD_INFO_TIP = 0x00000002;
+        FIELD_STATE = 0x00000004;
+        FIELD_VERSION = 0x00000008;
+    }
+}
+
+order_flags! {
+    /// The `fieldFlags` of a desktop order.
+    DesktopOrderFlags {
+        FIELD_NONE = 0x00000001;
+        FIELD_HOOKED = 0x00000002;
+        FIELD_ARC_COMPLETED = 0x00000004;
+        FIELD_ARC_BEGAN = 0x00000008;
+        FIELD_ZORDER = 0x00000010;
+        FIELD_ACTIVE_WND = 0x00000020;
+    }
+}
+
+/// The header of a window order, with the flags of its kind:
+/// [`WindowOrderFlags`], [`NotifyIconOrderFlags`] or [`DesktopOrderFlags`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OrderInfo<F> {
+    pub window_id: u32,
+    pub notify_icon_id: u32,
+    pub flags: F,
+}
+
+impl<F: Flags<Bits = u32>> From<&sys::WINDOW_ORDER_INFO> for OrderInfo<F> {
+    fn from(info: &sys::WINDOW_ORDER_INFO) -> Self {
+        Self {
+            window_id: info.windowId,
+            notify_icon_id: info.notifyIconId,
+            flags: F::from_bits_retain(info.fieldFlags),
+        }
+    }
+}
+
+/// The window state, only the fields present in the order are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WindowState {
+    pub owner_window_id: Option<u32>,
+    /// The window style and extended style.
+    pub style: Option<(u32, u32)>,
+    pub show_state: Option<u32>,
+    pub title: Option<String>,
+    pub client_offset: Option<(i32, i32)>,
+    pub client_area_size: Option<(u32, u32)>,
+    pub rp_content: Option<u32>,
+    pub root_parent: Option<u32>,
+    pub window_offset: Option<(i32, i32)>,
+    pub window_client_delta: Option<(i32, i32)>,
+    pub window_size: Option<(u32, u32)>,
+    pub window_rects: Option<Vec<Rect>>,
+    pub visible_offset: Option<(i32, i32)>,
+    pub visibility_rects: Option<Vec<Rect>>,
+    /// The left and right resize margins.
+    pub resize_margin_x: Option<(u32, u32)>,
+    /// The top and bottom resize margins.
+    pub resize_margin_y: Option<(u32, u32)>,
+    pub overlay_description: Option<String>,
+    pub taskbar_button: Option<u8>,
+    pub enforce_server_zorder: Option<bool>,
+    pub appbar_state: Option<u8>,
+    pub appbar_edge: Option<u8>,
+}
+
+fn rects(rects: *const sys::RECTANGLE_16, len: u32) -> Vec<Rect> {
+    if rects.is_null() {
+        return Vec::new();
+    }
+    unsafe { slice::from_raw_parts(rects, len as _) }
+        .iter()
+        .map(|r| Rect::from(*r))
+        .collect()
+}
+
+impl WindowState {
+    fn new(flags: WindowOrderFlags, s: &sys::WINDOW_STATE_ORDER) -> Self {
+        let has = |f| flags.contains(f);
+
+        Self {
+            owner_window_id: has(WindowOrderFlags::FIELD_OWNER).then_some(s.ownerWindowId),
+            style: has(WindowOrderFlags::FIELD_STYLE).then_some((s.style, s.extendedStyle)),
+            show_state: has(WindowOrderFlags::FIELD_SHOW).then_some(s.showState),
+            title: has(WindowOrderFlags::FIELD_TITLE).then(|| unicode_string(&s.titleInfo)),
+            client_offset: has(WindowOrderFlags::FIELD_CLIENT_AREA_OFFSET)
+                .then_some((s.clientOffsetX, s.clientOffsetY)),
+            client_area_size: has(WindowOrderFlags::FIELD_CLIENT_AREA_SIZE)
+                .then_some((s.clientAreaWidth, s.clientAreaHeight)),
+            rp_content: has(WindowOrderFlags::FIELD_RP_CONTENT).then_some(s.RPContent),
+            root_parent: has(WindowOrderFlags::FIELD_ROOT_PARENT).then_some(s.rootParentHandle),
+            window_offset: has(WindowOrderFlags::FIELD_WND_OFFSET)
+                .then_some((s.windowOffsetX, s.windowOffsetY)),
+            window_client_delta: has(WindowOrderFlags::FIELD_WND_CLIENT_DELTA)
+                .then_some((s.windowClientDeltaX, s.windowClientDeltaY)),
+            window_size: has(WindowOrderFlags::FIELD_WND_SIZE)
+                .then_some((s.windowWidth, s.windowHeight)),
+            window_rects: has(WindowOrderFlags::FIELD_WND_RECTS)
+                .then(|| rects(s.windowRects, s.numWindowRects)),
+            visible_offset: has(WindowOrderFlags::FIELD_VIS_OFFSET)
+                .then_some((s.visibleOffsetX, s.visibleOffsetY)),
+            visibility_rects: has(WindowOrderFlags::FIELD_VISIBILITY)
+                .then(|| rects(s.visibilityRects, s.numVisibilityRects)),
+            resize_margin_x: has(WindowOrderFlags::FIELD_RESIZE_MARGIN_X)
+                .then_some((s.resizeMarginLeft, s.resizeMarginRight)),
+            resize_margin_y: has(WindowOrderFlags::FIELD_RESIZE_MARGIN_Y)
+                .then_some((s.resizeMarginTop, s.resizeMarginBottom)),
+            overlay_description: has(WindowOrderFlags::FIELD_OVERLAY_DESCRIPTION)
+                .then(|| unicode_string(&s.OverlayDescription)),
+            taskbar_button: has(WindowOrderFlags::FIELD_TASKBAR_BUTTON).then_some(s.TaskbarButton),
+            enforce_server_zorder: has(WindowOrderFlags::FIELD_ENFORCE_SERVER_ZORDER)
+                .then_some(s.EnforceServerZOrder != 0),
+            appbar_state: has(WindowOrderFlags::FIELD_APPBAR_STATE).then_some(s.AppBarState),
+            appbar_edge: has(WindowOrderFlags::FIELD_APPBAR_EDGE).then_some(s.AppBarEdge),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CachedIcon {
+    pub cache_entry: u32,
+    pub cache_id: u32,
+}
+
+impl From<&sys::CACHED_ICON_INFO> for CachedIcon {
+    fn from(info: &sys::CACHED_ICON_INFO) -> Self {
+        Self {
+            cache_entry: info.cacheEntry,
+            cache_id: info.cacheId,
+        }
+    }
+}
+
+/// An icon, decoded to RGBA with the top row first.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Icon {
+    pub cache: CachedIcon,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl Debug for Icon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Icon")
+            .field("cache", &self.cache)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish()
+    }
+}
+
+impl TryFrom<&sys::ICON_INFO> for Icon {
+    type Error = RdpError;
+
+    fn try_from(info: &sys::ICON_INFO) -> Result<Self> {
+        let width = u16::try_from(info.width)?;
+        let height = u16::try_from(info.height)?;
+        let mut rgba = vec![0; width as usize * height as usize * 4];
+
+        let res = unsafe {
+            sys::freerdp_image_copy_from_icon_data(
+                rgba.as_mut_ptr(),
+                PIXEL_FORMAT_RGBA32.into(),
+                width as u32 * 4,
+                0,
+                0,
+                width,
+                height,
+                info.bitsColor,
+                info.cbBitsColor.try_into()?,
+                info.bitsMask,
+                info.cbBitsMask.try_into()?,
+                info.colorTable,
+                info.cbColorTable.try_into()?,
+                info.bpp,
+            )
+        };
+        if res == 0 {
+            return Err(RdpError::Failed("Failed to decode icon".into()));
+        }
+
+        Ok(Self {
+            cache: CachedIcon {
+                cache_entry: info.cacheEntry,
+                cache_id: info.cacheId,
+            },
+            width: width.into(),
+            height: height.into(),
+            rgba,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfoTip {
+    pub timeout: u32,
+    pub flags: u32,
+    pub text: String,
+    pub title: String,
+}
+
+impl From<&sys::NOTIFY_ICON_INFOTIP> for InfoTip {
+    fn from(tip: &sys::NOTIFY_ICON_INFOTIP) -> Self {
+        Self {
+            timeout: tip.timeout,
+            flags: tip.flags,
+            text: unicode_string(&tip.text),
+            title: unicode_string(&tip.title),
+        }
+    }
+}
+
+/// A notification area icon, only the fields present in the order are set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotifyIconState {
+    pub version: Option<u32>,
+    pub tool_tip: Option<String>,
+    pub info_tip: Option<InfoTip>,
+    pub state: Option<u32>,
+    /// Also `None` when the icon fails to decode.
+    pub icon: Option<Icon>,
+    pub cached_icon: Option<CachedIcon>,
+}
+
+impl NotifyIconState {
+    fn new(flags: NotifyIconOrderFlags, s: &sys::NOTIFY_ICON_STATE_ORDER) -> Self {
+        let has = |f| flags.contains(f);
+
+        Self {
+            version: has(NotifyIconOrderFlags::FIELD_VERSION).then_some(s.version),
+            tool_tip: has(NotifyIconOrderFlags::FIELD_TIP).then(|| unicode_string(&s.toolTip)),
+            info_tip: has(NotifyIconOrderFlags::FIELD_INFO_TIP).then(|| (&s.infoTip).into()),
+            state: has(NotifyIconOrderFlags::FIELD_STATE).then_some(s.state),
+            icon: has(NotifyIconOrderFlags::ICON)
+                .then(|| Icon::try_from(&s.icon).ok())
+                .flatten(),
+            cached_icon: has(NotifyIconOrderFlags::CACHED_ICON).then(|| (&s.cachedIcon).into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MonitoredDesktop {
+    pub active_window_id: Option<u32>,
+    /// The windows in z-order, the topmost first.
+    pub window_ids: Option<Vec<u32>>,
+}
+
+impl MonitoredDesktop {
+    fn new(flags: DesktopOrderFlags, d: &sys::MONITORED_DESKTOP_ORDER) -> Self {
+        let window_ids = if d.windowIds.is_null() {
+            Vec::new()
+        } else {
+            unsafe { slice::from_raw_parts(d.windowIds, d.numWindowIds as _) }.to_vec()
+        };
+
+        Self {
+            active_window_id: flags
+                .contains(DesktopOrderFlags::FIELD_ACTIVE_WND)
+                .then_some(d.activeWindowId),
+            window_ids: flags
+                .contains(DesktopOrderFlags::FIELD_ZORDER)
+                .then_some(window_ids),
+        }
+    }
+}
+
+pub struct WindowUpdate<'a> {
+    inner: ptr::NonNull<sys::rdpWindowUpdate>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> Debug for WindowUpdate<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowUpdate").finish()
+    }
+}
+
+impl<'a> WindowUpdate<'a> {
+    pub(crate) fn new(window: *mut sys::rdpWindowUpdate) -> Self {
+        Self {
+            inner: ptr::NonNull::new(window).unwrap(),
+            _lifetime: PhantomData,
+        }
+    }
+
+    pub fn register<H: WindowUpdateHandler>(&mut self) {
+        let inner = unsafe { self.inner.as_mut() };
+
+        inner.WindowCreate = Some(rdp_window_create::<H>);
+        inner.WindowUpdate = Some(rdp_window_update::<H>);
+        inner.WindowIcon = Some(rdp_window_icon::<H>);
+        inner.WindowCachedIcon = Some(rdp_window_cached_icon::<H>);
+        inner.WindowDelete = Some(rdp_window_delete::<H>);
+        inner.NotifyIconCreate = Some(rdp_notify_icon_create::<H>);
+        inner.NotifyIconUpdate = Some(rdp_notify_icon_update::<H>);
+        inner.NotifyIconDelete = Some(rdp_notify_icon_delete::<H>);
+        inner.MonitoredDesktop = Some(rdp_monitored_desktop::<H>);
+        inner.NonMonitoredDesktop = Some(rdp_non_monitored_desktop::<H>);
+    }
+}
+
+pub trait WindowUpdateHandler {
+    type ContextHandler: Handler;
+
+    fn window_create(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<WindowOrderFlags>,
+        _state: &WindowState,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn window_update(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<WindowOrderFlags>,
+        _state: &WindowState,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// A window icon, `info.flags` has `WindowOrderFlags::FIELD_ICON_BIG` for
+    /// the large icon. `icon` is `None` when it fails to decode.
+    fn window_icon(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<WindowOrderFlags>,
+        _icon: Option<&Icon>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn window_cached_icon(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<WindowOrderFlags>,
+        _icon: &CachedIcon,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn window_delete(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<WindowOrderFlags>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn notify_icon_create(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<NotifyIconOrderFlags>,
+        _state: &NotifyIconState,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn notify_icon_update(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<NotifyIconOrderFlags>,
+        _state: &NotifyIconState,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn notify_icon_delete(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<NotifyIconOrderFlags>,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn monitored_desktop(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<DesktopOrderFlags>,
+        _desktop: &MonitoredDesktop,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn non_monitored_desktop(
+        _context: &mut Context<Self::ContextHandler>,
+        _info: &OrderInfo<DesktopOrderFlags>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+extern "C" fn rdp_window_create<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+    state: *const sys::WINDOW_STATE_ORDER,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<WindowOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+    let state = WindowState::new(info.flags, unsafe { state.as_ref() }.unwrap());
+
+    H::window_create(context, &info, &state).is_ok() as _
+}
+
+extern "C" fn rdp_window_update<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+    state: *const sys::WINDOW_STATE_ORDER,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<WindowOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+    let state = WindowState::new(info.flags, unsafe { state.as_ref() }.unwrap());
+
+    H::window_update(context, &info, &state).is_ok() as _
+}
+
+extern "C" fn rdp_window_icon<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+    icon: *const sys::WINDOW_ICON_ORDER,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<WindowOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+    let icon = match unsafe { icon.as_ref().and_then(|i| i.iconInfo.as_ref()) } {
+        Some(icon) => icon,
+        None => return 0,
+    };
+    let icon = Icon::try_from(icon).ok();
+
+    H::window_icon(context, &info, icon.as_ref()).is_ok() as _
+}
+
+extern "C" fn rdp_window_cached_icon<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+    icon: *const sys::WINDOW_CACHED_ICON_ORDER,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<WindowOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+    let icon = CachedIcon::from(&unsafe { icon.as_ref() }.unwrap().cachedIcon);
+
+    H::window_cached_icon(context, &info, &icon).is_ok() as _
+}
+
+extern "C" fn rdp_window_delete<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<WindowOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+
+    H::window_delete(context, &info).is_ok() as _
+}
+
+extern "C" fn rdp_notify_icon_create<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+    state: *const sys::NOTIFY_ICON_STATE_ORDER,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<NotifyIconOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+    let state = NotifyIconState::new(info.flags, unsafe { state.as_ref() }.unwrap());
+
+    H::notify_icon_create(context, &info, &state).is_ok() as _
+}
+
+extern "C" fn rdp_notify_icon_update<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+    state: *const sys::NOTIFY_ICON_STATE_ORDER,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<NotifyIconOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+    let state = NotifyIconState::new(info.flags, unsafe { state.as_ref() }.unwrap());
+
+    H::notify_icon_update(context, &info, &state).is_ok() as _
+}
+
+extern "C" fn rdp_notify_icon_delete<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<NotifyIconOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+
+    H::notify_icon_delete(context, &info).is_ok() as _
+}
+
+extern "C" fn rdp_monitored_desktop<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+    desktop: *const sys::MONITORED_DESKTOP_ORDER,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<DesktopOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+    let desktop = MonitoredDesktop::new(info.flags, unsafe { desktop.as_ref() }.unwrap());
+
+    H::monitored_desktop(context, &info, &desktop).is_ok() as _
+}
+
+extern "C" fn rdp_non_monitored_desktop<H: WindowUpdateHandler>(
+    context: *mut sys::rdpContext,
+    info: *const sys::WINDOW_ORDER_INFO,
+) -> sys::BOOL {
+    let context = Context::from_ptr(context);
+    let info = OrderInfo::<DesktopOrderFlags>::from(unsafe { info.as_ref() }.unwrap());
+
+    H::non_monitored_desktop(context, &info).is_ok() as _
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_state_fields() {
+        let mut title: Vec<u8> = "Notepad"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let mut rect = sys::RECTANGLE_16 {
+            left: 0,
+            top: 0,
+            right: 640,
+            bottom: 480,
+        };
+        let mut order: sys::WINDOW_STATE_ORDER = unsafe { std::mem::zeroed() };
+        order.titleInfo.length = title.len() as _;
+        order.titleInfo.string = title.as_mut_ptr();
+        order.numVisibilityRects = 1;
+        order.visibilityRects = &mut rect;
+        order.windowWidth = 640;
+
+        let flags = WindowOrderFlags::TYPE_WINDOW
+            | WindowOrderFlags::FIELD_TITLE
+            | WindowOrderFlags::FIELD_VISIBILITY;
+        let state = WindowState::new(flags, &order);
+        assert_eq!(state.title.as_deref(), Some("Notepad"));
+        assert_eq!(
+            state.visibility_rects,
+            Some(vec![Rect::new(0, 0, 640, 480)])
+        );
+        assert_eq!(state.window_size, None);
+    }
+
+    #[test]
+    fn order_flags() {
+        let info = sys::WINDOW_ORDER_INFO {
+            windowId: 1,
+            notifyIconId: 2,
+            fieldFlags: 0x02000001,
+        };
+        let info = OrderInfo::<NotifyIconOrderFlags>::from(&info);
+        assert_eq!(
+            info.flags,
+            NotifyIconOrderFlags::TYPE_NOTIFY | NotifyIconOrderFlags::FIELD_TIP
+        );
+        assert_eq!(
+            format!("{:?}", info.flags),
+            "NotifyIconOrderFlags(TYPE_NOTIFY | FIELD_TIP)"
+        );
+    }
+}
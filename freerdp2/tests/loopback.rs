@@ -0,0 +1,135 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use freerdp2::{
+    client::*,
+    settings::typed::{Bool, Key, UInt32},
+    testing::{TestPeer, TestServer},
+    update::*,
+    winpr::wait_for_multiple_objects,
+    RdpError, Result, PIXEL_FORMAT_BGRA32,
+};
+
+#[derive(Debug, Default)]
+struct TestHandler {
+    connected: bool,
+    disconnected: bool,
+    surface_bits: usize,
+    frames: usize,
+    reconnected: usize,
+    // dropped after the first frame
+    server: Option<Arc<TestServer>>,
+}
+
+impl Handler for TestHandler {
+    fn post_connect(&mut self, context: &mut Context<Self>) -> Result<()> {
+        context.instance.gdi_init(PIXEL_FORMAT_BGRA32)?;
+        context
+            .update()
+            .ok_or(RdpError::Unsupported)?
+            .register::<TestUpdateHandler>();
+        self.connected = true;
+        Ok(())
+    }
+
+    fn post_disconnect(&mut self, context: &mut Context<Self>) {
+        context.instance.gdi_uninit();
+        self.disconnected = true;
+    }
+
+    fn reconnected(&mut self) {
+        self.reconnected += 1;
+    }
+}
+
+struct TestUpdateHandler;
+
+impl UpdateHandler for TestUpdateHandler {
+    type ContextHandler = TestHandler;
+
+    fn surface_bits(context: &mut Context<TestHandler>, _bits: &SurfaceBits) -> Result<()> {
+        context.handler.surface_bits += 1;
+        Ok(())
+    }
+
+    fn surface_frame_marker(
+        context: &mut Context<TestHandler>,
+        marker: &FrameMarker,
+    ) -> Result<()> {
+        if marker.action != FrameAction::End {
+            return Ok(());
+        }
+        context.handler.frames += 1;
+        if let Some(server) = context.handler.server.take() {
+            server.drop_connections();
+        } else if context.handler.reconnected > 0 {
+            context.instance.abort_connect()?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn connect_update_disconnect() {
+    let server = TestServer::start().unwrap();
+    let mut ctxt = Context::new(TestHandler::default());
+
+    ctxt.client_start().unwrap();
+    server.configure_client(&mut ctxt.settings).unwrap();
+    ctxt.settings.set::<UInt32>(Key::DesktopWidth, 128).unwrap();
+    ctxt.settings.set::<UInt32>(Key::DesktopHeight, 96).unwrap();
+
+    ctxt.instance.connect().unwrap();
+    assert!(ctxt.handler.connected);
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while ctxt.handler.frames == 0 {
+        assert!(Instant::now() < deadline, "no frame received");
+        let handles = ctxt.event_handles().unwrap();
+        let handles: Vec<_> = handles.iter().collect();
+        wait_for_multiple_objects(&handles, false, Some(&Duration::from_millis(100))).unwrap();
+        assert!(ctxt.check_event_handles());
+    }
+    // 2x2 tiles
+    assert_eq!(ctxt.handler.surface_bits, 4);
+
+    let image = ctxt.gdi().unwrap().snapshot().unwrap();
+    let color = TestPeer::default().color;
+    assert_eq!((image.width, image.height), (128, 96));
+    assert_eq!(&image.data[..3], &color[..3]);
+
+    ctxt.instance.disconnect().unwrap();
+    assert!(ctxt.handler.disconnected);
+    ctxt.client_stop().unwrap();
+}
+
+#[test]
+fn reconnect_after_drop() {
+    let server = Arc::new(TestServer::start().unwrap());
+    let mut ctxt = Context::new(TestHandler {
+        server: Some(server.clone()),
+        ..Default::default()
+    });
+
+    ctxt.client_start().unwrap();
+    server.configure_client(&mut ctxt.settings).unwrap();
+    ctxt.settings
+        .set::<Bool>(Key::AutoReconnectionEnabled, true)
+        .unwrap();
+
+    ctxt.instance.connect().unwrap();
+    let policy = ReconnectPolicy {
+        max_retries: Some(3),
+        initial_delay: Duration::from_millis(10),
+        ..Default::default()
+    };
+    ctxt.run(&policy).unwrap();
+
+    assert_eq!(ctxt.handler.reconnected, 1);
+    assert_eq!(ctxt.handler.frames, 2);
+
+    ctxt.instance.disconnect().unwrap();
+    ctxt.client_stop().unwrap();
+}